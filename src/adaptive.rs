@@ -0,0 +1,105 @@
+//! Adaptive difficulty: how well the last few rounds went decides whether
+//! the next one is a level up or down the progression, which brings in or
+//! takes away attributes, stricter rules and guesses. It aims to keep the
+//! player succeeding most of the time, but not all of it.
+
+use crate::model::{Mode, VennModel, LEVEL_COUNT};
+use serde::{Deserialize, Serialize};
+
+// How many of the latest rounds are gone by.
+const WINDOW: usize = 5;
+
+// How many rounds have to be played at a level before it moves on.
+const SETTLE: usize = 3;
+
+// The success to keep the player within, from 0 for a lost round to 1 for
+// a quick one without a red guess.
+const LOW: f32 = 0.5;
+const HIGH: f32 = 0.8;
+
+// Seconds per guess a round can take before it counts as slow.
+const PACE: f32 = 8.0;
+
+/// Where the adaptation stands.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Trend {
+    /// Too few rounds at the level to go by yet.
+    Settling,
+    Easier,
+    Steady,
+    Harder,
+}
+
+/// The success of the latest rounds at the level they were played at.
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Adaptive {
+    recent: Vec<f32>,
+    level: usize,
+}
+
+impl Adaptive {
+    /// Adds a round that just ended. Lost rounds count as no success at
+    /// all, won ones by their share of green guesses, less when they took
+    /// longer than the pace. Rounds at another level no longer count.
+    pub fn record(&mut self, model: &VennModel) {
+        if model.level() != self.level {
+            self.recent.clear();
+            self.level = model.level();
+        }
+        let success = if model.is_won() {
+            let accuracy = model.accuracy().map_or(1.0, |a| a as f32 / 100.0);
+            let expected = PACE * model.guesses().len() as f32;
+            accuracy * (expected / model.elapsed().max(1.0)).min(1.0)
+        } else {
+            0.0
+        };
+        self.recent.push(success);
+        if self.recent.len() > WINDOW {
+            self.recent.remove(0);
+        }
+    }
+
+    /// The average success of the latest rounds, or None before any.
+    pub fn success(&self) -> Option<f32> {
+        if self.recent.is_empty() {
+            None
+        } else {
+            Some(self.recent.iter().sum::<f32>() / self.recent.len() as f32)
+        }
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.recent.len()
+    }
+
+    /// The level the latest round was played at.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Which way the next level goes.
+    pub fn trend(&self) -> Trend {
+        match self.success() {
+            _ if self.recent.len() < SETTLE => Trend::Settling,
+            Some(success) if success < LOW => Trend::Easier,
+            Some(success) if success > HIGH => Trend::Harder,
+            _ => Trend::Steady,
+        }
+    }
+
+    /// The level to play after the round. Presets and Set mode stay where
+    /// they are, like without adapting.
+    pub fn next_level(&self, model: &VennModel) -> usize {
+        let options = model.options();
+        if options.difficulty.is_some() || options.mode == Mode::Set {
+            return model.next_level();
+        }
+        let level = model.level();
+        match self.trend() {
+            Trend::Settling | Trend::Steady => level,
+            Trend::Easier => level.saturating_sub(1),
+            Trend::Harder => (level + 1).min(LEVEL_COUNT - 1),
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! What the game loads before its first frame: the pictures of the first
+//! puzzle's own cards and the sound effects, each a stage of the task the
+//! loading screen shows the progress of, and the font, which the loading
+//! screen loads to write them and hands over once they're done. The rest
+//! of the game asks for them by handle. The pictures of puzzles started
+//! later are loaded the first time they are drawn.
+
+use crate::{locale::text, sound::Effect, theme::Theme, tokens::Tokens};
+use coffee::{
+    graphics::{
+        Font, Frame, Gpu, HorizontalAlignment, Image, Mesh, Point, Quad, Rectangle, Shape, Target,
+        Text,
+    },
+    load::{loading_screen::LoadingScreen, Join, Progress, Task},
+};
+use log::warn;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    f32::consts::PI,
+    fmt,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+const FONT: &[u8] = include_bytes!("../resources/font/Inconsolata-Regular.ttf");
+
+thread_local! {
+    // The font the loading screen loaded, until the game takes it over.
+    static FONT_LOADED: RefCell<Option<Font>> = const { RefCell::new(None) };
+}
+
+// The samples a second of the sound effects.
+const RATE: u32 = 44_100;
+
+/// One of the assets of the type, as loaded.
+pub struct Handle<T> {
+    index: usize,
+    asset: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    fn new(index: usize) -> Handle<T> {
+        Handle {
+            index,
+            asset: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Handle<T> {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Handle<T>) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({})", self.index)
+    }
+}
+
+/// A sound effect, as samples from -1 to 1.
+#[cfg_attr(not(feature = "sound"), allow(dead_code))]
+pub struct Clip {
+    pub rate: u32,
+    pub samples: Vec<f32>,
+}
+
+/// The pictures and sounds of the game.
+pub struct Assets {
+    images: Vec<Image>,
+    // The pictures of cards by file, None for one that didn't load, which
+    // isn't tried again.
+    pictures: HashMap<PathBuf, Option<Handle<Image>>>,
+    clips: Vec<Clip>,
+    // The clip of each effect, in the order of Effect::ALL.
+    effects: Vec<Handle<Clip>>,
+}
+
+/// Loads the assets, with the pictures in the files, and takes the font
+/// over from the loading screen for the ui.
+pub fn load(pictures: Vec<PathBuf>) -> Task<(Font, Assets)> {
+    let pictures = pictures
+        .into_iter()
+        .fold(Task::new(Vec::new), |loaded, path| {
+            let picture = Task::using_gpu(move |gpu| Ok((path.clone(), open(gpu, &path))));
+            (loaded, picture).join().map(|(mut loaded, picture)| {
+                loaded.push(picture);
+                loaded
+            })
+        });
+    let pictures = Task::stage(text("loading.pictures"), pictures);
+    let clips = Task::new(|| {
+        Effect::ALL
+            .iter()
+            .map(|&effect| synthesize(effect))
+            .collect()
+    });
+    let clips = Task::stage(text("loading.sounds"), clips);
+    (pictures, clips)
+        .join()
+        .map(|(pictures, clips): (Vec<_>, Vec<Clip>)| {
+            // Nothing is drawn on the loading screen after the last stage.
+            let font = FONT_LOADED.with(|font| font.borrow_mut().take());
+            let font = font.expect("the loading screen loads the font");
+            let mut assets = Assets {
+                images: Vec::new(),
+                pictures: HashMap::new(),
+                effects: (0..clips.len()).map(Handle::new).collect(),
+                clips,
+            };
+            for (path, image) in pictures {
+                assets.add(path, image);
+            }
+            (font, assets)
+        })
+}
+
+impl Assets {
+    /// Loads the pictures of the cards that aren't loaded yet.
+    pub fn load_pictures(&mut self, gpu: &mut Gpu, tokens: Option<&Tokens>) {
+        let cards = tokens.iter().flat_map(|tokens| &tokens.tokens);
+        for path in cards.filter_map(|token| token.image.as_ref()) {
+            if !self.pictures.contains_key(path) {
+                let image = open(gpu, path);
+                self.add(path.clone(), image);
+            }
+        }
+    }
+
+    fn add(&mut self, path: PathBuf, image: Option<Image>) {
+        let handle = image.map(|image| {
+            self.images.push(image);
+            Handle::new(self.images.len() - 1)
+        });
+        self.pictures.insert(path, handle);
+    }
+
+    /// The picture from the file, if it loaded.
+    pub fn picture(&self, path: &Path) -> Option<Handle<Image>> {
+        self.pictures.get(path).copied().flatten()
+    }
+
+    pub fn image(&self, handle: Handle<Image>) -> &Image {
+        &self.images[handle.index]
+    }
+
+    /// Draws the image centered on the point, as big as fits in a square of
+    /// the size without stretching it.
+    pub fn draw(&self, handle: Handle<Image>, center: Point, size: f32, target: &mut Target<'_>) {
+        let image = self.image(handle);
+        let (width, height) = (f32::from(image.width()), f32::from(image.height()));
+        let scale = size / width.max(height);
+        let (width, height) = (width * scale, height * scale);
+        image.draw(
+            Quad {
+                position: Point::new(center.x - width / 2.0, center.y - height / 2.0),
+                size: (width, height),
+                ..Quad::default()
+            },
+            target,
+        );
+    }
+
+    /// The sound of the effect.
+    pub fn effect(&self, effect: Effect) -> Handle<Clip> {
+        let index = Effect::ALL.iter().position(|&e| e == effect).unwrap();
+        self.effects[index]
+    }
+
+    pub fn clip(&self, handle: Handle<Clip>) -> &Clip {
+        &self.clips[handle.index]
+    }
+}
+
+// The picture in the file, or None if it doesn't load, which is reported
+// and leaves the card's name to be written instead.
+fn open(gpu: &mut Gpu, path: &Path) -> Option<Image> {
+    Image::new(gpu, path)
+        .map_err(|error| warn!("could not load {}: {}", path.display(), error))
+        .ok()
+}
+
+// A few short notes for each effect: rising for a right guess, a low one
+// for a wrong one and a little tune once the round is over.
+fn synthesize(effect: Effect) -> Clip {
+    let notes: &[(f32, f32)] = match effect {
+        Effect::PickUp => &[(660.0, 0.05)],
+        Effect::Drop => &[(440.0, 0.06)],
+        Effect::Right => &[(660.0, 0.08), (880.0, 0.12)],
+        Effect::Wrong => &[(220.0, 0.2)],
+        Effect::RoundOver => &[(523.0, 0.12), (659.0, 0.12), (784.0, 0.25)],
+    };
+    let mut samples = Vec::new();
+    for &(frequency, seconds) in notes {
+        let count = (seconds * RATE as f32) as usize;
+        samples.extend((0..count).map(|i| {
+            let t = i as f32 / RATE as f32;
+            // Fades out over the note, so it doesn't click at the end.
+            let fade = 1.0 - i as f32 / count as f32;
+            (2.0 * PI * frequency * t).sin() * fade * 0.5
+        }));
+    }
+    Clip {
+        rate: RATE,
+        samples,
+    }
+}
+
+/// A bar filling up in the colors of the theme, with what is being loaded
+/// over it.
+pub struct Loading {
+    theme: Theme,
+}
+
+impl LoadingScreen for Loading {
+    fn new(gpu: &mut Gpu) -> coffee::Result<Loading> {
+        let font = Font::from_bytes(gpu, FONT)?;
+        FONT_LOADED.with(|loaded| *loaded.borrow_mut() = Some(font));
+        Ok(Loading {
+            theme: crate::game::theme(),
+        })
+    }
+
+    fn draw(&mut self, progress: &Progress, frame: &mut Frame<'_>) {
+        frame.clear(self.theme.background);
+        let (width, height) = (frame.width(), frame.height());
+        let bar = Rectangle {
+            x: 50.0,
+            y: height / 2.0 - 15.0,
+            width: width - 100.0,
+            height: 30.0,
+        };
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            Shape::Rectangle(Rectangle {
+                width: bar.width * progress.percentage() / 100.0,
+                ..bar
+            }),
+            self.theme.hint,
+        );
+        mesh.stroke(Shape::Rectangle(bar), self.theme.stroke, 2);
+        mesh.draw(&mut frame.as_target());
+        FONT_LOADED.with(|font| {
+            if let Some(font) = font.borrow_mut().as_mut() {
+                self.write(font, bar, progress, frame);
+            }
+        });
+    }
+}
+
+impl Loading {
+    // What is being loaded over the bar and how much of it is under it.
+    fn write(
+        &self,
+        font: &mut Font,
+        bar: Rectangle<f32>,
+        progress: &Progress,
+        frame: &mut Frame<'_>,
+    ) {
+        let width = frame.width();
+        if let Some(stage) = progress.stage() {
+            font.add(Text {
+                content: stage,
+                position: Point::new(width / 2.0, bar.y - 50.0),
+                size: 30.0,
+                color: self.theme.text,
+                horizontal_alignment: HorizontalAlignment::Center,
+                ..Text::default()
+            });
+        }
+        font.add(Text {
+            content: &format!("{:.0}%", progress.percentage()),
+            position: Point::new(width / 2.0, bar.y + bar.height + 20.0),
+            size: 24.0,
+            color: self.theme.faint,
+            horizontal_alignment: HorizontalAlignment::Center,
+            ..Text::default()
+        });
+        font.draw(&mut frame.as_target());
+    }
+}
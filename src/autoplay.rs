@@ -0,0 +1,57 @@
+//! Playing a round the way the solver would, to work out its par and to
+//! see how hard the levels are.
+
+use crate::model::{Placement, VennModel};
+
+// A puzzle still unsolved after this many placements never will be.
+const MAX_PLACEMENTS: u32 = 1000;
+
+/// How one round went.
+pub struct Playthrough {
+    pub solved: bool,
+    pub placements: u32,
+    pub wrong: u32,
+    /// The hints ran out, or stopped moving anything, before the round was
+    /// solved, so finishing it needs the hidden cards.
+    pub stuck: bool,
+    /// Parts of the diagram no guess belongs in.
+    pub empty_regions: bool,
+    /// Circles whose rule and card the guesses don't settle.
+    pub ambiguous: bool,
+}
+
+/// Places guesses where the solver suggests until the puzzle is solved, or
+/// the solver is stuck, with no hint or one that moves nothing. It only
+/// goes by what the placements showed, never by the hidden cards.
+pub fn play(model: &mut VennModel) -> Playthrough {
+    let (mut placements, mut wrong, mut stuck) = (0, 0, false);
+    while !model.is_solved() && placements < MAX_PLACEMENTS {
+        let placement = model.hint().and_then(|hint| {
+            let point = model.region_center(hint.region)?;
+            model.place(hint.guess, &point)
+        });
+        match placement {
+            Some(Placement::Region { matches, .. }) => {
+                placements += 1;
+                if !matches {
+                    wrong += 1;
+                }
+            }
+            Some(_) => placements += 1,
+            None => {
+                stuck = true;
+                break;
+            }
+        }
+    }
+    let solver = model.solver();
+    let circles = if model.options().three { 3 } else { 2 };
+    Playthrough {
+        solved: model.is_solved(),
+        placements,
+        wrong,
+        stuck,
+        empty_regions: model.region_counts().iter().any(|&(_, count)| count == 0),
+        ambiguous: (0..circles).any(|c| solver.candidates(c).len() > 1),
+    }
+}
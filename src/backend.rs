@@ -0,0 +1,265 @@
+//! What another engine has to give the game to play rounds on it: a
+//! [`Renderer`] to draw the board on and an [`InputSource`] to move the
+//! guesses with. The coffee window only draws through [`Canvas`] and reads
+//! its own input, as it does far more than play rounds; the macroquad
+//! frontend in `src/bin/venn-web.rs` plays with [`draw`] and [`steer`].
+//!
+//! [`Canvas`]: ../trait.Canvas.html
+//! [`Renderer`]: trait.Renderer.html
+//! [`InputSource`]: trait.InputSource.html
+//! [`draw`]: fn.draw.html
+//! [`steer`]: fn.steer.html
+
+use crate::{
+    graphics::{Color, Point, Rectangle, Shape},
+    model::{Placement, VennModel, HEIGHT, WIDTH},
+    palette::Style,
+    render::Canvas,
+    words::{caption, label_at, wrap},
+};
+
+// How big the captions of the circles are written.
+const CAPTION_SIZE: f32 = 18.0;
+
+/// Somewhere the board and its text can be drawn, in board coordinates.
+pub trait Renderer: Canvas {
+    /// Fills a circle.
+    fn circle(&mut self, center: Point, radius: f32, color: Color) {
+        self.fill(Shape::Circle { center, radius }, color);
+    }
+
+    /// Fills the polygon through the points.
+    fn polygon(&mut self, points: &[Point], color: Color) {
+        self.fill(
+            Shape::Polyline {
+                points: points.to_vec(),
+            },
+            color,
+        );
+    }
+
+    /// Writes a line of text centered on the point, with its top there.
+    fn text(&mut self, text: &str, position: Point, size: f32, color: Color);
+}
+
+/// A pointer to play with: a mouse and its button, or a finger on a screen.
+pub trait InputSource {
+    /// Where the pointer is, in board coordinates.
+    fn pointer(&self) -> Point;
+
+    /// Whether the button is down, or the finger on the screen.
+    fn is_pressed(&self) -> bool;
+}
+
+/// Moves the guesses with the pointer: pressing on one picks it up, it
+/// follows the pointer while held and is dropped where it is let go of.
+/// Returns where a guess was dropped, if one was.
+pub fn steer(model: &mut VennModel, input: &impl InputSource) -> Option<Placement> {
+    let point = input.pointer();
+    match model.dragging() {
+        None if input.is_pressed() => {
+            model.pick(&point);
+            None
+        }
+        None => None,
+        Some(_) if input.is_pressed() => {
+            model.drag(&point);
+            None
+        }
+        Some(_) => model.drop(&point),
+    }
+}
+
+/// Draws the board: the circles with the region under the pointer
+/// highlighted, their captions and the guesses, the one being dragged on
+/// top.
+pub fn draw(model: &VennModel, renderer: &mut impl Renderer, style: &Style, pointer: Point) {
+    renderer.fill(
+        Shape::Rectangle(Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: WIDTH,
+            height: HEIGHT,
+        }),
+        style.theme.background,
+    );
+    let dragging = model.dragging();
+    let pointer = dragging.map_or(pointer, |i| model.guesses()[i].center);
+    let revealed = model.is_forfeited() || model.shows_rules();
+    model.draw_diagram(renderer, style, pointer, revealed);
+    // The labels give the rules away, so they only show once the round is
+    // over or when the cards are shown anyway.
+    let finished = model.is_finished() || model.shows_rules();
+    for circle in model.circles() {
+        let (position, above) = label_at(circle);
+        let content = caption(circle, model.current_level(), finished, above);
+        // Inconsolata is half as wide as it is high, and the captions are
+        // no wider than the circle's radius.
+        let lines = wrap(&content, (circle.radius * 2.0 / CAPTION_SIZE) as usize);
+        let top = if above {
+            position.y
+        } else {
+            position.y - lines.len() as f32 * CAPTION_SIZE
+        };
+        for (i, line) in lines.iter().enumerate() {
+            let at = Point::new(position.x, top + i as f32 * CAPTION_SIZE);
+            renderer.text(line, at, CAPTION_SIZE, style.theme.text);
+        }
+    }
+    for i in model.stacking() {
+        if dragging != Some(i) {
+            model.guesses()[i].draw(renderer, style);
+        }
+    }
+    if let Some(i) = dragging {
+        model.guesses()[i].draw(renderer, style);
+    }
+}
+
+/// Cuts the polygon through the points into triangles, for engines that
+/// only fill triangles. The polygon may be concave, but not cross itself,
+/// and may end on the point it starts with.
+pub fn triangulate(points: &[Point]) -> Vec<[Point; 3]> {
+    let mut left = points.to_vec();
+    if left.len() > 1 && left.first() == left.last() {
+        left.pop();
+    }
+    // Which way the polygon goes round, so an ear turns the same way.
+    let area: f32 = (0..left.len())
+        .map(|i| {
+            let (a, b) = (left[i], left[(i + 1) % left.len()]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum();
+    let turn = area.signum();
+    let mut triangles = Vec::new();
+    let (mut i, mut tried) = (0, 0);
+    while left.len() > 3 {
+        let n = left.len();
+        i %= n;
+        let (a, b, c) = (left[(i + n - 1) % n], left[i], left[(i + 1) % n]);
+        let convex = cross(a, b, c) * turn >= 0.0;
+        let empty = || {
+            left.iter()
+                .all(|&p| p == a || p == b || p == c || !inside(p, a, b, c))
+        };
+        // A polygon that crosses itself or folds back has no ear left to
+        // cut, so one is cut anyway rather than dropping the rest.
+        if (convex && empty()) || tried > n {
+            triangles.push([a, b, c]);
+            left.remove(i);
+            tried = 0;
+        } else {
+            i += 1;
+            tried += 1;
+        }
+    }
+    if let [a, b, c] = left[..] {
+        triangles.push([a, b, c]);
+    }
+    triangles
+}
+
+// Which way the path from a through b to c turns, and how sharply.
+fn cross(a: Point, b: Point, c: Point) -> f32 {
+    (b.x - a.x) * (c.y - b.y) - (b.y - a.y) * (c.x - b.x)
+}
+
+// Whether the point is inside the triangle or on its edge.
+fn inside(p: Point, a: Point, b: Point, c: Point) -> bool {
+    let (ab, bc, ca) = (cross(a, b, p), cross(b, c, p), cross(c, a, p));
+    (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Options, Place};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Plays back a pointer, one position and button at a time.
+    struct Script(Point, bool);
+
+    impl InputSource for Script {
+        fn pointer(&self) -> Point {
+            self.0
+        }
+
+        fn is_pressed(&self) -> bool {
+            self.1
+        }
+    }
+
+    // Counts what is drawn and keeps what is written.
+    #[derive(Default)]
+    struct Record {
+        shapes: usize,
+        lines: Vec<String>,
+    }
+
+    impl Canvas for Record {
+        fn fill(&mut self, _: Shape, _: Color) {
+            self.shapes += 1;
+        }
+
+        fn stroke(&mut self, _: Shape, _: Color, _: u16) {
+            self.shapes += 1;
+        }
+    }
+
+    impl Renderer for Record {
+        fn text(&mut self, text: &str, _: Point, _: f32, _: Color) {
+            self.lines.push(text.to_string());
+        }
+    }
+
+    fn model() -> VennModel {
+        let options = Options {
+            seed: 5,
+            ..Options::default()
+        };
+        VennModel::new(0, &options, &mut StdRng::seed_from_u64(5))
+    }
+
+    #[test]
+    fn a_guess_is_dragged_where_the_pointer_lets_go() {
+        let mut model = model();
+        let guess = model.guesses()[0].clone();
+        let to = model.circles().next().unwrap().center;
+        assert_eq!(steer(&mut model, &Script(guess.center, true)), None);
+        assert_eq!(model.dragging(), Some(0));
+        steer(&mut model, &Script(to, true));
+        let placement = steer(&mut model, &Script(to, false));
+        assert!(placement.is_some());
+        assert_eq!(model.dragging(), None);
+        assert_ne!(model.results()[0].place, Place::Rail);
+    }
+
+    #[test]
+    fn the_board_is_drawn_with_a_question_mark_per_circle() {
+        let model = model();
+        let mut record = Record::default();
+        draw(&model, &mut record, &Style::default(), Point::new(0.0, 0.0));
+        assert!(record.shapes > model.guesses().len());
+        assert_eq!(record.lines, ["?", "?"]);
+    }
+
+    #[test]
+    fn a_concave_polygon_is_cut_into_triangles_of_its_area() {
+        // An arrow head, with a notch at the back.
+        let points = [
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 2.0),
+            Point::new(0.0, 4.0),
+            Point::new(1.0, 2.0),
+            Point::new(0.0, 0.0),
+        ];
+        let triangles = triangulate(&points);
+        assert_eq!(triangles.len(), 2);
+        let area: f32 = triangles
+            .iter()
+            .map(|&[a, b, c]| cross(a, b, c).abs() / 2.0)
+            .sum();
+        assert!((area - 6.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,133 @@
+//! Plays generated puzzles without a window, following the solver's hints
+//! and nothing else, to see how hard each level is and to catch puzzles the
+//! hints can't solve.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::process;
+use venn::{autoplay, Options, Preset, VennModel, LEVEL_COUNT};
+
+struct Settings {
+    options: Options,
+    puzzles: u64,
+}
+
+fn parse_args() -> Settings {
+    let mut settings = Settings {
+        options: Options {
+            seed: rand::thread_rng().gen(),
+            ..Options::default()
+        },
+        puzzles: 100,
+    };
+    let options = &mut settings.options;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--three" => options.three = true,
+            "--outside" => options.outside = true,
+            "--seed" => match args.next().map(|seed| seed.parse()) {
+                Some(Ok(seed)) => options.seed = seed,
+                _ => usage("--seed expects an unsigned integer"),
+            },
+            "--difficulty" => match args.next().as_deref() {
+                Some("easy") => options.difficulty = Some(Preset::Easy),
+                Some("medium") => options.difficulty = Some(Preset::Medium),
+                Some("hard") => options.difficulty = Some(Preset::Hard),
+                _ => usage("--difficulty expects easy, medium or hard"),
+            },
+            "--puzzles" => match args.next().map(|puzzles| puzzles.parse()) {
+                Some(Ok(puzzles)) if puzzles > 0 => settings.puzzles = puzzles,
+                _ => usage("--puzzles expects a number of puzzles"),
+            },
+            // main reads it first, to log from the start.
+            "--verbose" => {}
+            _ => usage(&format!("unknown argument {}", arg)),
+        }
+    }
+    settings
+}
+
+fn usage(error: &str) -> ! {
+    eprintln!("venn-sim: {}", error);
+    eprintln!(concat!(
+        "usage: venn-sim [--puzzles <n>] [--three] [--outside] [--seed <u64>]\n",
+        "                [--difficulty <easy|medium|hard>] [--verbose]"
+    ));
+    process::exit(2);
+}
+
+// Plays the puzzles of one level, or of the preset, and prints how they
+// went. Returns how many the hints couldn't solve, stuck or not.
+fn simulate(name: &str, level: usize, settings: &Settings) -> u64 {
+    let mut runs = Vec::new();
+    let mut unsolved = 0;
+    for i in 0..settings.puzzles {
+        let options = Options {
+            seed: settings.options.seed.wrapping_add(i),
+            ..settings.options
+        };
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let run = autoplay::play(&mut VennModel::new(level, &options, &mut rng));
+        if !run.solved {
+            let why = if run.stuck { "stuck" } else { "unsolved" };
+            eprintln!(
+                "venn-sim: {} seed {}: {} after {} placements",
+                name, options.seed, why, run.placements
+            );
+            unsolved += 1;
+        }
+        runs.push(run);
+    }
+    let mut placements: Vec<u32> = runs.iter().map(|run| run.placements).collect();
+    placements.sort_unstable();
+    let total: u32 = placements.iter().sum();
+    let wrong: u32 = runs.iter().map(|run| run.wrong).sum();
+    println!(
+        "{:<8} {:>6} {:>6} {:>5} {:>4} {:>6} {:>6.1} {:>4} {:>6.0}% {:>6} {:>9}",
+        name,
+        runs.len(),
+        runs.len() as u64 - unsolved,
+        runs.iter().filter(|run| run.stuck).count(),
+        placements[0],
+        placements[placements.len() / 2],
+        total as f32 / runs.len() as f32,
+        placements[placements.len() - 1],
+        wrong as f32 / total.max(1) as f32 * 100.0,
+        runs.iter().filter(|run| run.empty_regions).count(),
+        runs.iter().filter(|run| run.ambiguous).count(),
+    );
+    unsolved
+}
+
+fn main() {
+    venn::logging::init(std::env::args().any(|arg| arg == "--verbose"));
+    let settings = parse_args();
+    println!("seed {}", settings.options.seed);
+    println!(
+        "{:<8} {:>6} {:>6} {:>5} {:>4} {:>6} {:>6} {:>4} {:>7} {:>6} {:>9}",
+        "",
+        "played",
+        "solved",
+        "stuck",
+        "min",
+        "median",
+        "mean",
+        "max",
+        "wrong",
+        "empty",
+        "ambiguous"
+    );
+    let unsolved = match settings.options.difficulty {
+        Some(preset) => {
+            let name = format!("{:?}", preset).to_lowercase();
+            simulate(&name, 0, &settings)
+        }
+        None => (0..LEVEL_COUNT)
+            .map(|level| simulate(&format!("level {}", level + 1), level, &settings))
+            .sum(),
+    };
+    if unsolved > 0 {
+        eprintln!("venn-sim: the hints could not solve {} puzzles", unsolved);
+        process::exit(1);
+    }
+}
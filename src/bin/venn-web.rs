@@ -0,0 +1,257 @@
+//! Plays rounds with macroquad, in a window or in the browser, one after the
+//! other, with the mouse or a finger. The menus, settings and everything
+//! else are only in the coffee window.
+
+use macroquad::prelude::{
+    clear_background, draw_circle, draw_circle_lines, draw_line, draw_rectangle,
+    draw_rectangle_lines, draw_text_ex, draw_triangle, get_frame_time, is_mouse_button_down,
+    load_ttf_font_from_bytes, measure_text, mouse_position, next_frame, screen_height,
+    screen_width, simulate_mouse_with_touch, touches, vec2, Conf, Font, MouseButton, TextParams,
+    TouchPhase, Vec2,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::f32::consts::PI;
+use venn::{
+    backend::{self, InputSource, Renderer},
+    graphics::{Color, Point, Rectangle, Shape},
+    locale::{fill, text},
+    Canvas, Options, Style, VennModel,
+};
+
+const FONT: &[u8] = include_bytes!("../../resources/font/Inconsolata-Regular.ttf");
+
+// The board, as big as fits on the screen and in the middle of it.
+struct Screen {
+    scale: f32,
+    origin: Vec2,
+    font: Font,
+}
+
+impl Screen {
+    fn fit(font: &Font) -> Screen {
+        let scale = (screen_width() / venn::WIDTH).min(screen_height() / venn::HEIGHT);
+        let origin = vec2(
+            (screen_width() - venn::WIDTH * scale) / 2.0,
+            (screen_height() - venn::HEIGHT * scale) / 2.0,
+        );
+        Screen {
+            scale,
+            origin,
+            font: font.clone(),
+        }
+    }
+
+    // Where a point of the board is on the screen.
+    fn at(&self, point: Point) -> Vec2 {
+        self.origin + vec2(point.x, point.y) * self.scale
+    }
+
+    // Where a point of the screen is on the board.
+    fn board(&self, (x, y): (f32, f32)) -> Point {
+        let point = (vec2(x, y) - self.origin) / self.scale;
+        Point::new(point.x, point.y)
+    }
+
+    // The pointer: the first finger on the screen, or the mouse if there is
+    // none.
+    fn pointer(&self) -> Pointer {
+        match touches().first() {
+            Some(touch) => Pointer {
+                at: self.board((touch.position.x, touch.position.y)),
+                is_pressed: !matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled),
+            },
+            None => Pointer {
+                at: self.board(mouse_position()),
+                is_pressed: is_mouse_button_down(MouseButton::Left),
+            },
+        }
+    }
+
+    fn outline(&self, points: &[Point], color: Color, width: u16) {
+        let width = width as f32 * self.scale;
+        for pair in points.windows(2) {
+            let (a, b) = (self.at(pair[0]), self.at(pair[1]));
+            draw_line(a.x, a.y, b.x, b.y, width, tint(color));
+        }
+    }
+}
+
+impl Canvas for Screen {
+    fn fill(&mut self, shape: Shape, color: Color) {
+        match shape {
+            Shape::Rectangle(Rectangle {
+                x,
+                y,
+                width,
+                height,
+            }) => {
+                let corner = self.at(Point::new(x, y));
+                let (width, height) = (width * self.scale, height * self.scale);
+                draw_rectangle(corner.x, corner.y, width, height, tint(color));
+            }
+            Shape::Circle { center, radius } => {
+                let center = self.at(center);
+                draw_circle(center.x, center.y, radius * self.scale, tint(color));
+            }
+            Shape::Ellipse {
+                center,
+                horizontal_radius,
+                vertical_radius,
+                rotation,
+            } => {
+                let points = ellipse(center, horizontal_radius, vertical_radius, rotation);
+                self.polygon(&points, color);
+            }
+            Shape::Polyline { points } => self.polygon(&points, color),
+        }
+    }
+
+    fn stroke(&mut self, shape: Shape, color: Color, width: u16) {
+        match shape {
+            Shape::Rectangle(Rectangle {
+                x,
+                y,
+                width: w,
+                height: h,
+            }) => {
+                let corner = self.at(Point::new(x, y));
+                let (w, h) = (w * self.scale, h * self.scale);
+                let width = width as f32 * self.scale;
+                draw_rectangle_lines(corner.x, corner.y, w, h, width, tint(color));
+            }
+            Shape::Circle { center, radius } => {
+                let center = self.at(center);
+                let (radius, width) = (radius * self.scale, width as f32 * self.scale);
+                draw_circle_lines(center.x, center.y, radius, width, tint(color));
+            }
+            Shape::Ellipse {
+                center,
+                horizontal_radius,
+                vertical_radius,
+                rotation,
+            } => {
+                let mut points = ellipse(center, horizontal_radius, vertical_radius, rotation);
+                points.push(points[0]);
+                self.outline(&points, color, width);
+            }
+            Shape::Polyline { points } => self.outline(&points, color, width),
+        }
+    }
+}
+
+impl Renderer for Screen {
+    // macroquad only fills triangles.
+    fn polygon(&mut self, points: &[Point], color: Color) {
+        for [a, b, c] in backend::triangulate(points) {
+            let (a, b, c) = (self.at(a), self.at(b), self.at(c));
+            draw_triangle(a, b, c, tint(color));
+        }
+    }
+
+    fn text(&mut self, text: &str, position: Point, size: f32, color: Color) {
+        let size = (size * self.scale).round() as u16;
+        let measured = measure_text(text, Some(&self.font), size, 1.0);
+        let at = self.at(position);
+        let params = TextParams {
+            font: Some(&self.font),
+            font_size: size,
+            color: tint(color),
+            ..TextParams::default()
+        };
+        draw_text_ex(
+            text,
+            at.x - measured.width / 2.0,
+            at.y + measured.offset_y,
+            params,
+        );
+    }
+}
+
+// Where the pointer is and whether it is down.
+struct Pointer {
+    at: Point,
+    is_pressed: bool,
+}
+
+impl InputSource for Pointer {
+    fn pointer(&self) -> Point {
+        self.at
+    }
+
+    fn is_pressed(&self) -> bool {
+        self.is_pressed
+    }
+}
+
+// Points around the edge of an ellipse, turned by the rotation.
+fn ellipse(center: Point, horizontal: f32, vertical: f32, rotation: f32) -> Vec<Point> {
+    const STEPS: usize = 48;
+    let (sin, cos) = rotation.sin_cos();
+    (0..STEPS)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f32 / STEPS as f32;
+            let (x, y) = (horizontal * angle.cos(), vertical * angle.sin());
+            Point::new(center.x + x * cos - y * sin, center.y + x * sin + y * cos)
+        })
+        .collect()
+}
+
+fn tint(color: Color) -> macroquad::prelude::Color {
+    macroquad::prelude::Color::new(color.r, color.g, color.b, color.a)
+}
+
+fn window() -> Conf {
+    Conf {
+        window_title: "Venn".to_string(),
+        window_width: venn::WIDTH as i32,
+        window_height: venn::HEIGHT as i32,
+        high_dpi: true,
+        ..Conf::default()
+    }
+}
+
+#[macroquad::main(window)]
+async fn main() {
+    // Fingers are read as they are, not as a mouse.
+    simulate_mouse_with_touch(false);
+    let font = load_ttf_font_from_bytes(FONT).expect("the font is built in");
+    let seed = (macroquad::miniquad::date::now() * 1000.0) as u64;
+    let options = Options {
+        seed,
+        ..Options::default()
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut model = VennModel::new(0, &options, &mut rng);
+    let style = Style::default();
+    let mut was_pressed = false;
+    loop {
+        let mut screen = Screen::fit(&font);
+        let pointer = screen.pointer();
+        model.advance(get_frame_time());
+        if !model.is_finished() {
+            backend::steer(&mut model, &pointer);
+        } else if pointer.is_pressed && !was_pressed {
+            let score = model.score();
+            model = VennModel::new(model.next_level(), &options, &mut rng).with_score(score);
+        }
+        was_pressed = pointer.is_pressed;
+        clear_background(tint(style.theme.background));
+        backend::draw(&model, &mut screen, &style, pointer.at);
+        let status = format!(
+            "{}    {}",
+            fill("hud.level", &[&(model.level() + 1)]),
+            fill("hud.score", &[&model.score().points]),
+        );
+        screen.text(
+            &status,
+            Point::new(venn::WIDTH / 2.0, 8.0),
+            18.0,
+            style.theme.text,
+        );
+        if model.is_finished() {
+            let at = Point::new(venn::WIDTH / 2.0, 30.0);
+            screen.text(text("web.next"), at, 18.0, style.theme.text);
+        }
+        next_frame().await;
+    }
+}
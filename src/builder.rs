@@ -0,0 +1,191 @@
+//! Building an answer in the box above a circle. There is a row of chips for
+//! each attribute the level cares about. For a circle with a hidden card,
+//! picking a chip sets that attribute of the card. For a circle with a
+//! compound rule, the chips picked are the values of the rule, each row can
+//! be left out, and a last row joins them with and or with or, or on levels
+//! with negated rules makes the rule everything except them. In Set mode
+//! there is a single row to name the attribute the circles sort by.
+
+use crate::{
+    model::{Mode, VennModel},
+    rule::RuleExpr,
+    shapes::{Kind, Value, VennTarget},
+};
+use coffee::input::keyboard::KeyCode;
+
+/// One chip in a row of the builder.
+#[derive(PartialEq, Copy, Clone)]
+pub enum Chip {
+    Value(Value),
+    /// Names the attribute the circles sort by in Set mode.
+    Kind(Kind),
+    /// Leaves the attribute of the row out of a compound rule.
+    Skip,
+    And,
+    Or,
+    /// Makes a compound rule everything except the values picked.
+    Except,
+}
+
+/// The chips to build an answer for the circle from, a row for each
+/// attribute the level cares about and in the values its cards come in.
+pub fn rows(model: &VennModel, circle: usize) -> Vec<Vec<Chip>> {
+    let values = model.current_level().values(model.in_play());
+    if model.options().mode == Mode::Set {
+        return vec![values.iter().map(|row| Chip::Kind(row[0].kind())).collect()];
+    }
+    let values = values
+        .into_iter()
+        .map(|row| row.into_iter().map(Chip::Value));
+    if !is_compound(model, circle) {
+        return values.map(Iterator::collect).collect();
+    }
+    let mut rows: Vec<Vec<Chip>> = values
+        .map(|row| std::iter::once(Chip::Skip).chain(row).collect())
+        .collect();
+    let mut join = vec![Chip::And, Chip::Or];
+    if model.current_level().negated {
+        join.push(Chip::Except);
+    }
+    rows.push(join);
+    rows
+}
+
+fn is_compound(model: &VennModel, circle: usize) -> bool {
+    model
+        .circles()
+        .nth(circle)
+        .is_some_and(|c| c.compound.is_some())
+}
+
+// The most attributes a level can care about.
+const ATTRIBUTES: usize = 7;
+
+/// An answer being built for the answer box of a circle.
+#[derive(PartialEq, Copy, Clone)]
+pub struct Builder {
+    pub circle: usize,
+    pub card: VennTarget,
+    pub kind: Kind,
+    // Whether the answer names an attribute, in Set mode.
+    naming: bool,
+    // Whether the answer is a compound rule rather than a card.
+    compound: bool,
+    // For a compound rule, which attribute rows are part of it.
+    used: [bool; ATTRIBUTES],
+    // For a compound rule, whether its values are joined by or.
+    or: bool,
+    // For a compound rule, whether it is everything except its values.
+    except: bool,
+    // The row the arrow keys change.
+    row: usize,
+}
+
+impl Builder {
+    /// Starts from the card built in the box before, or from the first chip
+    /// of every row. A compound rule starts out empty.
+    pub fn new(circle: usize, model: &VennModel) -> Builder {
+        // The guesses have the values of the attributes the level ignores.
+        let mut card = model.guesses()[0].target;
+        for row in model.current_level().values(model.in_play()) {
+            row[0].set(&mut card);
+        }
+        let answer = &model.circles().nth(circle).unwrap().answer;
+        let naming = model.options().mode == Mode::Set;
+        Builder {
+            circle,
+            card: answer.built.unwrap_or(card),
+            kind: answer.built_kind.unwrap_or(Kind::Shape),
+            naming,
+            compound: !naming && is_compound(model, circle),
+            used: [false; ATTRIBUTES],
+            or: false,
+            except: false,
+            row: 0,
+        }
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Whether the answer names an attribute, in Set mode.
+    pub fn is_naming(&self) -> bool {
+        self.naming
+    }
+
+    /// Whether the answer is a compound rule rather than a card.
+    pub fn is_compound(&self) -> bool {
+        self.compound
+    }
+
+    /// Whether the chip in the row is picked.
+    pub fn is_on(&self, row: usize, chip: Chip) -> bool {
+        match chip {
+            Chip::Value(value) => (!self.compound || self.used[row]) && value.holds(&self.card),
+            Chip::Kind(kind) => self.kind == kind,
+            Chip::Skip => !self.used[row],
+            Chip::And => !self.or && !self.except,
+            Chip::Or => self.or && !self.except,
+            Chip::Except => self.except,
+        }
+    }
+
+    /// Picks the chip in the row.
+    pub fn choose(&mut self, row: usize, chip: Chip) {
+        self.row = row;
+        match chip {
+            Chip::Value(value) => {
+                value.set(&mut self.card);
+                self.used[row] = true;
+            }
+            Chip::Kind(kind) => self.kind = kind,
+            Chip::Skip => self.used[row] = false,
+            Chip::And => (self.or, self.except) = (false, false),
+            Chip::Or => (self.or, self.except) = (true, false),
+            Chip::Except => self.except = true,
+        }
+    }
+
+    /// Moves between the rows with the up and down keys, and between the
+    /// chips of a row with left and right.
+    pub fn press(&mut self, key: KeyCode, rows: &[Vec<Chip>]) {
+        let count = rows.len();
+        match key {
+            KeyCode::Up => self.row = (self.row + count - 1) % count,
+            KeyCode::Down | KeyCode::Tab => self.row = (self.row + 1) % count,
+            KeyCode::Left | KeyCode::Right => {
+                let row = &rows[self.row];
+                let step = if key == KeyCode::Right {
+                    1
+                } else {
+                    row.len() - 1
+                };
+                let on = row
+                    .iter()
+                    .position(|&c| self.is_on(self.row, c))
+                    .unwrap_or(0);
+                self.choose(self.row, row[(on + step) % row.len()]);
+            }
+            _ => {}
+        }
+    }
+
+    /// The compound rule built, or None while no value is picked.
+    pub fn rule(&self, rows: &[Vec<Chip>]) -> Option<RuleExpr> {
+        let values: Vec<Value> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| row.iter().filter(move |&&c| self.is_on(r, c)))
+            .filter_map(|&chip| match chip {
+                Chip::Value(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+        if self.except {
+            // Neither of the values, so a single one is everything except it.
+            return RuleExpr::join(&values, true).map(|rule| RuleExpr::Not(Box::new(rule)));
+        }
+        RuleExpr::join(&values, self.or)
+    }
+}
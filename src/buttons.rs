@@ -0,0 +1,52 @@
+//! Which mouse button does which job, kept in the settings.
+
+use serde::{Deserialize, Serialize};
+
+/// A mouse button that can be given a job.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Button {
+    Left,
+    Right,
+    Middle,
+}
+
+impl Button {
+    pub const ALL: [Button; 3] = [Button::Left, Button::Right, Button::Middle];
+}
+
+/// Which button does what. Every job has a button of its own.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Buttons {
+    /// Picks up, drags and drops guesses and clicks menus.
+    pub drag: Button,
+    /// Sends the guess under the pointer back to the rail.
+    pub home: Button,
+    /// Asks for a hint.
+    pub hint: Button,
+}
+
+impl Default for Buttons {
+    fn default() -> Buttons {
+        Buttons::USUAL
+    }
+}
+
+impl Buttons {
+    pub(crate) const USUAL: Buttons = Buttons {
+        drag: Button::Left,
+        home: Button::Right,
+        hint: Button::Middle,
+    };
+
+    /// Gives the job at the index, in the order the fields are in, the
+    /// button. The job that had that button takes the one given up.
+    pub fn assign(&mut self, job: usize, button: Button) {
+        let mut jobs = [self.drag, self.home, self.hint];
+        if let Some(other) = jobs.iter().position(|&b| b == button) {
+            jobs[other] = jobs[job];
+        }
+        jobs[job] = button;
+        [self.drag, self.home, self.hint] = jobs;
+    }
+}
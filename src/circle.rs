@@ -0,0 +1,174 @@
+use crate::{
+    graphics::Point,
+    model::Level,
+    region::{self, Boundary, Outline},
+    rule::{Rule, RuleExpr},
+    shapes::{Kind, VennColor, VennTarget},
+};
+use serde::{Deserialize, Serialize};
+
+/// The box above a circle that holds its hidden card.
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct VennAnswer {
+    pub width: f32,
+    pub height: f32,
+    #[serde(with = "crate::save::point")]
+    pub center: Point,
+    pub target: VennTarget,
+    // The card the player built as their answer, if any.
+    #[serde(default)]
+    pub built: Option<VennTarget>,
+    // The rule built instead, for a circle with a compound rule.
+    #[serde(default)]
+    pub built_rule: Option<RuleExpr>,
+    // The attribute named in Set mode as the one the circles sort by.
+    #[serde(default)]
+    pub built_kind: Option<Kind>,
+    // Whether what was built is right. Deductions are only checked once
+    // every circle has one.
+    #[serde(default)]
+    pub matches: Option<bool>,
+    // Whether the cards in the box are written out by name rather than
+    // drawn, for a puzzle with its own cards.
+    #[serde(default)]
+    pub written: bool,
+}
+
+impl VennAnswer {
+    pub fn contains(&self, point: &Point) -> bool {
+        region::in_box(point, &self.center, self.width, self.height)
+    }
+
+    // Only the exact hidden card is right, as far as the level can tell.
+    pub fn is_card(&self, target: &VennTarget, level: &Level) -> bool {
+        Rule::All.evaluate(&compare(&self.target, target, level))
+    }
+}
+
+/// One set of the diagram. Guesses inside it have to fit its rule.
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct VennSet {
+    #[serde(with = "crate::save::point")]
+    pub center: Point,
+    /// Half the width of the set, which is a circle unless the outline
+    /// says otherwise.
+    pub radius: f32,
+    #[serde(default)]
+    pub outline: Outline,
+    pub color: VennColor,
+    pub rule: Rule,
+    pub answer: VennAnswer,
+    // A compound rule decides which guesses fit on its own, and the rule
+    // and hidden card go unused.
+    #[serde(default)]
+    pub compound: Option<RuleExpr>,
+    // What the puzzle calls the circle, which shows from the start.
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl VennSet {
+    pub fn boundary(&self) -> Boundary {
+        Boundary {
+            center: self.center,
+            radius: self.radius,
+            outline: self.outline,
+        }
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.boundary().contains(point)
+    }
+
+    pub fn matches(&self, target: &VennTarget, level: &Level) -> bool {
+        match &self.compound {
+            Some(expr) => expr.evaluate(target),
+            None => self
+                .rule
+                .evaluate(&compare(&self.answer.target, target, level)),
+        }
+    }
+
+    /// Whether what was built in the answer box is right: the hidden card,
+    /// a rule that picks out the same guesses as the compound one, or in Set
+    /// mode the attribute it sorts by. None until something is built.
+    pub fn answer_matches(&self, level: &Level, guesses: &[VennTarget]) -> Option<bool> {
+        // A named attribute is right if one of its values picks out the
+        // same guesses as the circle.
+        if let Some(kind) = self.answer.built_kind {
+            let expr = self.compound.as_ref()?;
+            return Some(
+                guesses
+                    .iter()
+                    .any(|guess| RuleExpr::Is(kind.of(guess)).same_as(expr, guesses)),
+            );
+        }
+        match &self.compound {
+            Some(expr) => {
+                let built = self.answer.built_rule.as_ref()?;
+                Some(built.same_as(expr, guesses))
+            }
+            None => {
+                let built = self.answer.built?;
+                Some(self.answer.is_card(&built, level))
+            }
+        }
+    }
+
+    /// Whether something was built in the answer box.
+    pub fn is_answered(&self) -> bool {
+        self.answer.built.is_some()
+            || self.answer.built_rule.is_some()
+            || self.answer.built_kind.is_some()
+    }
+
+    /// The two points where the edges of the circles cross, or None if they
+    /// don't overlap, one is inside the other or either isn't a circle.
+    /// Looking from this circle towards the other, the first is on the left.
+    pub fn crossings(&self, other: &VennSet) -> Option<(Point, Point)> {
+        if self.outline != Outline::Circle || other.outline != Outline::Circle {
+            return None;
+        }
+        let d = nalgebra::distance(&self.center, &other.center);
+        if d >= self.radius + other.radius || d <= (self.radius - other.radius).abs() {
+            return None;
+        }
+        // How far along the line between the centers the chord through the
+        // crossings is, and how far the crossings are either side of it.
+        let along = (self.radius.powi(2) - other.radius.powi(2) + d * d) / (2.0 * d);
+        let half = (self.radius.powi(2) - along * along).max(0.0).sqrt();
+        let (dx, dy) = (
+            (other.center.x - self.center.x) / d,
+            (other.center.y - self.center.y) / d,
+        );
+        let (x, y) = (self.center.x + dx * along, self.center.y + dy * along);
+        Some((
+            Point::new(x + dy * half, y - dx * half),
+            Point::new(x - dy * half, y + dx * half),
+        ))
+    }
+}
+
+// Whether each attribute the level cares about is the same on both.
+pub(crate) fn compare(hidden: &VennTarget, target: &VennTarget, level: &Level) -> Vec<bool> {
+    let mut attributes = vec![hidden.shape == target.shape];
+    if level.color {
+        attributes.push(hidden.color == target.color);
+    }
+    if level.size {
+        attributes.push(hidden.size == target.size);
+    }
+    if level.pattern {
+        attributes.push(hidden.pattern == target.pattern);
+    }
+    if level.count {
+        attributes.push(hidden.count == target.count);
+    }
+    if level.orientation {
+        attributes.push(hidden.orientation == target.orientation);
+    }
+    if level.fill {
+        attributes.push(hidden.fill == target.fill);
+    }
+    attributes
+}
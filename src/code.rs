@@ -0,0 +1,118 @@
+//! Short codes that name a puzzle by its seed and difficulty, so friends
+//! can race the same puzzle without a server, and the daily puzzle
+//! everyone gets on the same date.
+
+use crate::model::Preset;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// RFC 4648 base32, which doesn't mix up 0 and O or 1 and I.
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The seed of the puzzle of the day, the same everywhere on a UTC date.
+pub fn daily_seed() -> u64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    seconds / (24 * 60 * 60)
+}
+
+/// The code for a puzzle. Small seeds, like the daily ones, make short
+/// codes.
+pub fn encode(seed: u64, difficulty: Option<Preset>) -> String {
+    let difficulty = match difficulty {
+        None => 0,
+        Some(Preset::Easy) => 1,
+        Some(Preset::Medium) => 2,
+        Some(Preset::Hard) => 3,
+    };
+    let seed = seed.to_be_bytes();
+    let start = seed.iter().position(|&byte| byte != 0).unwrap_or(8);
+    let mut bytes = vec![difficulty];
+    bytes.extend(&seed[start..]);
+    let mut code = String::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            code.push(ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        code.push(ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    code
+}
+
+/// The seed and difficulty in a code made by [`encode`], or None if it
+/// isn't one. Letters may be in either case.
+///
+/// [`encode`]: fn.encode.html
+pub fn decode(code: &str) -> Option<(u64, Option<Preset>)> {
+    let mut bytes = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in code.trim().bytes() {
+        let value = ALPHABET
+            .iter()
+            .position(|&letter| letter == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    let (&difficulty, seed) = bytes.split_first()?;
+    if seed.len() > 8 {
+        return None;
+    }
+    let difficulty = match difficulty {
+        0 => None,
+        1 => Some(Preset::Easy),
+        2 => Some(Preset::Medium),
+        3 => Some(Preset::Hard),
+        _ => return None,
+    };
+    let seed = seed
+        .iter()
+        .fold(0u64, |seed, &byte| (seed << 8) | byte as u64);
+    Some((seed, difficulty))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFFICULTIES: [Option<Preset>; 4] = [
+        None,
+        Some(Preset::Easy),
+        Some(Preset::Medium),
+        Some(Preset::Hard),
+    ];
+
+    #[test]
+    fn codes_round_trip() {
+        for &seed in &[0, 1, 255, 256, 20_000, 0xdead_beef, u64::MAX] {
+            for &difficulty in &DIFFICULTIES {
+                let code = encode(seed, difficulty);
+                assert_eq!(decode(&code), Some((seed, difficulty)), "{}", code);
+                assert_eq!(decode(&code.to_lowercase()), Some((seed, difficulty)));
+            }
+        }
+    }
+
+    #[test]
+    fn small_seeds_make_short_codes() {
+        assert!(encode(20_000, None).len() < encode(u64::MAX, None).len());
+        assert_eq!(encode(0, None).len(), 2);
+    }
+
+    #[test]
+    fn other_text_is_no_code() {
+        for code in &["", "A", "A1", "AB-C", "H7777777777777777"] {
+            assert_eq!(decode(code), None, "{}", code);
+        }
+    }
+}
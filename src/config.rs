@@ -0,0 +1,266 @@
+//! Preferences kept between sessions, which the settings screen changes.
+
+use crate::{
+    buttons::{Button, Buttons},
+    locale::Language,
+    model::Preset,
+    palette::{Palette, Style},
+    profile::{self, Profile},
+    theme::ThemeName,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The most ticks a second the game loop runs at.
+pub const MAX_TICK_RATE: u16 = 60;
+
+// The biggest UI scale, past which the diagram would have to shrink too
+// far for the guesses.
+const MAX_UI_SCALE: f32 = 2.0;
+
+// The tick rates and frame caps the settings screen steps through, which
+// divide the game loop's rate evenly.
+const TICK_RATES: [u16; 4] = [60, 30, 20, 15];
+const FRAME_CAPS: [Option<u16>; 3] = [None, Some(60), Some(30)];
+
+/// Everything the settings screen can change. Missing entries take their
+/// default, so older files keep working.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// How big the window is, as a multiple of 800 by 600.
+    pub scale: f32,
+    pub palette: Palette,
+    /// Whether shapes carry a pattern that tells their color.
+    pub patterns: bool,
+    /// How loud sound effects are, from 0 to 100.
+    pub volume: u8,
+    /// The preset new games are played at, or None to work through the
+    /// levels.
+    pub difficulty: Option<Preset>,
+    /// Whether the home button sends the guess under the pointer back to
+    /// the rail.
+    pub right_click_home: bool,
+    /// Whether a guess is picked up with one click and dropped with the
+    /// next, instead of being held with the button down.
+    pub click_to_carry: bool,
+    /// How many times a second the game moves on, up to 60. Fewer go
+    /// easier on slow machines without slowing the game down.
+    pub tick_rate: u16,
+    /// How many frames a second are drawn at most, or None for as many as
+    /// the screen shows.
+    pub frame_cap: Option<u16>,
+    /// The language the game shows its text in.
+    pub language: Language,
+    /// Whether the guess under the pointer and how a drop went are read
+    /// out loud.
+    pub speech: bool,
+    /// The colors of everything but the cards.
+    pub theme: ThemeName,
+    /// How big guesses, answer boxes, lines and the text over the board
+    /// are, as a multiple of their usual size.
+    pub ui_scale: f32,
+    /// Which mouse button drags, sends home and asks for a hint.
+    pub buttons: Buttons,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            scale: 1.0,
+            palette: Palette::Standard,
+            patterns: false,
+            volume: 80,
+            difficulty: None,
+            right_click_home: true,
+            click_to_carry: false,
+            tick_rate: MAX_TICK_RATE,
+            frame_cap: None,
+            language: Language::English,
+            speech: false,
+            theme: ThemeName::Light,
+            ui_scale: 1.0,
+            buttons: Buttons::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn style(&self) -> Style {
+        Style {
+            palette: self.palette,
+            patterns: self.patterns,
+            theme: self.theme.theme(),
+            scale: self.ui_scale(),
+        }
+    }
+
+    /// The UI scale kept to sizes the board can make room for.
+    pub fn ui_scale(&self) -> f32 {
+        if self.ui_scale.is_finite() {
+            self.ui_scale.clamp(1.0, MAX_UI_SCALE)
+        } else {
+            1.0
+        }
+    }
+
+    /// Makes everything on the board a step bigger, or smaller.
+    pub fn zoom(&mut self, bigger: bool) {
+        let step = if bigger { 0.25 } else { -0.25 };
+        self.ui_scale = (self.ui_scale() + step).clamp(1.0, MAX_UI_SCALE);
+    }
+
+    /// The scale kept to sizes a screen can show.
+    pub fn window_scale(&self) -> f32 {
+        if self.scale.is_finite() {
+            self.scale.clamp(0.5, 3.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// The tick rate kept to what the game loop can do.
+    pub fn tick_rate(&self) -> u16 {
+        self.tick_rate.clamp(1, MAX_TICK_RATE)
+    }
+
+    // Steps the setting at the index of the settings screen to its next
+    // value, or its previous one.
+    pub(crate) fn adjust(&mut self, entry: usize, forward: bool) {
+        match entry {
+            0 => {
+                self.palette = match self.palette {
+                    Palette::Standard => Palette::Colorblind,
+                    Palette::Colorblind => Palette::Standard,
+                }
+            }
+            1 => self.patterns = !self.patterns,
+            2 => {
+                self.volume = if forward {
+                    self.volume.saturating_add(10).min(100)
+                } else {
+                    self.volume.saturating_sub(10)
+                }
+            }
+            3 => {
+                let presets = [
+                    None,
+                    Some(Preset::Easy),
+                    Some(Preset::Medium),
+                    Some(Preset::Hard),
+                ];
+                let i = presets.iter().position(|&p| p == self.difficulty).unwrap();
+                let step = if forward { 1 } else { presets.len() - 1 };
+                self.difficulty = presets[(i + step) % presets.len()];
+            }
+            4 => self.right_click_home = !self.right_click_home,
+            5 => self.click_to_carry = !self.click_to_carry,
+            6 => {
+                let step = if forward { 0.25 } else { -0.25 };
+                self.scale = (self.window_scale() + step).clamp(0.5, 3.0);
+            }
+            7 => self.tick_rate = step(&TICK_RATES, self.tick_rate(), forward),
+            8 => self.frame_cap = step(&FRAME_CAPS, self.frame_cap, forward),
+            9 => self.language = step(&Language::ALL, self.language, forward),
+            10 => self.speech = !self.speech,
+            11 => self.theme = step(&ThemeName::ALL, self.theme, forward),
+            12 => self.zoom(forward),
+            13..=15 => {
+                let job = entry - 13;
+                let current = [self.buttons.drag, self.buttons.home, self.buttons.hint][job];
+                self.buttons
+                    .assign(job, step(&Button::ALL, current, forward));
+            }
+            _ => {}
+        }
+    }
+}
+
+// The value after the current one, or before it, going round. A value
+// that isn't one of them starts over from the first.
+fn step<T: PartialEq + Copy>(values: &[T], current: T, forward: bool) -> T {
+    let i = match values.iter().position(|&v| v == current) {
+        Some(i) if forward => i + 1,
+        Some(i) => i + values.len() - 1,
+        None => 0,
+    };
+    values[i % values.len()]
+}
+
+/// Where the preferences of the profile, or of everyone without one, are
+/// kept.
+pub fn path(profile: Option<&Profile>) -> Option<PathBuf> {
+    match profile {
+        Some(profile) => Some(profile.config_path()),
+        None => profile::root().map(|root| root.join("config.toml")),
+    }
+}
+
+/// Reads the preferences, writing the defaults first if there are none yet.
+/// Preferences still in the JSON file of earlier versions next to it are
+/// carried over.
+pub fn read(path: &Path) -> io::Result<Config> {
+    let mut config = match fs::read_to_string(path) {
+        Ok(contents) => toml::from_str(&contents).map_err(invalid)?,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            let config = match fs::read_to_string(path.with_extension("json")) {
+                Ok(contents) => serde_json::from_str(&contents)?,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Config::default(),
+                Err(error) => return Err(error),
+            };
+            write(path, &config)?;
+            config
+        }
+        Err(error) => return Err(error),
+    };
+    config.volume = config.volume.min(100);
+    Ok(config)
+}
+
+/// Writes the preferences, making the directory for them if needed.
+pub fn write(path: &Path, config: &Config) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, toml::to_string(config).map_err(invalid)?)
+}
+
+fn invalid(error: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_carry_over_and_stay_in_range() {
+        let dir = std::env::temp_dir().join(format!("venn-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(
+            dir.join("config.json"),
+            r#"{ "volume": 250, "tick_rate": 30 }"#,
+        )
+        .unwrap();
+        let config = read(&path).unwrap();
+        assert_eq!((config.volume, config.tick_rate), (100, 30));
+        // The carried over settings were written as TOML.
+        let mut changed = read(&path).unwrap();
+        assert_eq!(changed, config);
+        changed.difficulty = Some(Preset::Hard);
+        changed.frame_cap = Some(30);
+        write(&path, &changed).unwrap();
+        assert_eq!(read(&path).unwrap(), changed);
+        let mut loud = Config {
+            volume: 250,
+            ..Config::default()
+        };
+        loud.adjust(2, true);
+        assert_eq!(loud.volume, 100);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,31 @@
+//! A hand drawn next to the pointer that shows what pressing the button
+//! would do: pick up the guess under it, or drop the one held.
+
+use crate::{graphics::Point, model::VennModel, region::Region};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum Cursor {
+    /// Over a guess that can be picked up.
+    Open,
+    /// Holding a guess somewhere it can't be dropped in the diagram.
+    Closed,
+    /// Holding a guess over a part of the diagram it can be dropped in.
+    Dropping,
+}
+
+impl Cursor {
+    /// What the pointer shows at the point, if anything. While a guess is
+    /// dragged the point is where the guess is.
+    pub fn at(model: &VennModel, point: &Point) -> Option<Cursor> {
+        if model.is_finished() {
+            return None;
+        }
+        match model.dragging() {
+            Some(_) if model.region(point) != Region::Outside || model.options().outside => {
+                Some(Cursor::Dropping)
+            }
+            Some(_) => Some(Cursor::Closed),
+            None => model.guess_at(point).map(|_| Cursor::Open),
+        }
+    }
+}
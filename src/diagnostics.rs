@@ -0,0 +1,48 @@
+//! What the debug overlay shows besides the board: how fast the game ticks
+//! and how long a frame takes to build its meshes.
+
+use std::time::{Duration, Instant};
+
+/// Measurements for the debug overlay, kept while it is shown.
+pub struct Diagnostics {
+    // Ticks since the start of the current second.
+    ticks: u32,
+    second: Instant,
+    tick_rate: u32,
+    build: Duration,
+}
+
+impl Diagnostics {
+    pub fn new() -> Diagnostics {
+        Diagnostics {
+            ticks: 0,
+            second: Instant::now(),
+            tick_rate: 0,
+            build: Duration::ZERO,
+        }
+    }
+
+    /// Counts a tick of the game loop.
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+        if self.second.elapsed() >= Duration::from_secs(1) {
+            self.tick_rate = self.ticks;
+            self.ticks = 0;
+            self.second = Instant::now();
+        }
+    }
+
+    /// How many ticks there were in the last whole second.
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    /// Notes how long the latest frame took to build its meshes.
+    pub fn built(&mut self, took: Duration) {
+        self.build = took;
+    }
+
+    pub fn build_time(&self) -> Duration {
+        self.build
+    }
+}
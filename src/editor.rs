@@ -0,0 +1,350 @@
+//! Writing puzzle files in the game. The puzzle being edited is shown as
+//! the board it makes, laid out and drawn like any other, with every guess
+//! of its level so the ones the puzzle leaves out can be picked too.
+
+use crate::{
+    graphics::{Point, Vector},
+    model::{Options, VennModel, LEVEL_COUNT},
+    puzzle::{self, Puzzle, PuzzleCircle},
+    region::Outline,
+    rule::{Rule, RuleExpr},
+    shapes::{Value, VennTarget},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+// How much a circle grows or shrinks at a time, and how far.
+const RESIZE: f32 = 10.0;
+const SMALLEST: f32 = 60.0;
+const LARGEST: f32 = 260.0;
+
+// How far an ellipse turns at a time, in degrees.
+const TURN: f32 = 15.0;
+
+// The outlines a set steps through.
+const OUTLINES: [Outline; 3] = [
+    Outline::Circle,
+    Outline::Ellipse {
+        ratio: 0.6,
+        angle: 0.0,
+    },
+    Outline::Rounded {
+        ratio: 0.6,
+        corner: 0.4,
+    },
+];
+
+// The rules a circle steps through.
+const RULES: [Rule; 5] = [
+    Rule::Any,
+    Rule::All,
+    Rule::Not,
+    Rule::Exactly(1),
+    Rule::Exactly(2),
+];
+
+/// A puzzle being edited and the file it is saved to.
+pub struct Editor {
+    pub puzzle: Puzzle,
+    path: PathBuf,
+    /// The circle the keys change.
+    pub selected: usize,
+    // Guesses taken off the board, which stay out of the puzzle.
+    removed: Vec<VennTarget>,
+    // The offset of the circle being dragged from the pointer.
+    grab: Option<Vector>,
+}
+
+impl Editor {
+    /// Edits the puzzle file, or a new puzzle like the first generated one
+    /// if there is no file yet.
+    pub fn open(path: &Path, options: &Options) -> io::Result<Editor> {
+        let puzzle = if path.exists() {
+            puzzle::read(path)?
+        } else {
+            let model = VennModel::new(1, options, &mut StdRng::seed_from_u64(options.seed));
+            Puzzle {
+                level: model.level(),
+                circles: model
+                    .circles()
+                    .map(|circle| PuzzleCircle {
+                        rule: circle.rule,
+                        card: circle.answer.target,
+                        token: None,
+                        has: None,
+                        compound: circle.compound.clone(),
+                        center: None,
+                        radius: None,
+                        outline: None,
+                        label: None,
+                    })
+                    .collect(),
+                shapes: None,
+                guesses: None,
+                tokens: None,
+                pictures: None,
+                table: None,
+            }
+        };
+        Ok(Editor {
+            puzzle,
+            path: path.to_path_buf(),
+            selected: 0,
+            removed: Vec::new(),
+            grab: None,
+        })
+    }
+
+    /// The board the puzzle makes with every guess of its level that
+    /// wasn't taken off, to edit.
+    pub fn model(&self, options: &Options) -> VennModel {
+        let mut puzzle = Puzzle {
+            guesses: None,
+            ..self.puzzle.clone()
+        };
+        if !self.removed.is_empty() {
+            let all = VennModel::from_puzzle(&puzzle, options).palette();
+            let kept = all.into_iter().filter(|g| !self.removed.contains(g));
+            puzzle.guesses = Some(kept.collect());
+        }
+        VennModel::from_puzzle(&puzzle, options)
+    }
+
+    /// The board the puzzle makes, to play.
+    pub fn play(&self, options: &Options) -> VennModel {
+        VennModel::from_puzzle(&self.puzzle, options)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        puzzle::write(&self.path, &self.puzzle)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the puzzle plays with the guess.
+    pub fn is_chosen(&self, target: &VennTarget) -> bool {
+        match &self.puzzle.guesses {
+            Some(guesses) => guesses.contains(target),
+            None => true,
+        }
+    }
+
+    /// Takes the guess out of the puzzle or puts it back, out of all those
+    /// of the level. The last one stays.
+    pub fn toggle(&mut self, target: VennTarget, all: &[VennTarget]) {
+        let mut guesses = self.puzzle.guesses.take().unwrap_or_else(|| all.to_vec());
+        match guesses.iter().position(|g| *g == target) {
+            Some(index) if guesses.len() > 1 => {
+                guesses.remove(index);
+            }
+            Some(_) => {}
+            None => guesses.push(target),
+        }
+        // Every guess of the level is the same as not listing them.
+        if all.iter().all(|g| guesses.contains(g)) {
+            return;
+        }
+        self.puzzle.guesses = Some(guesses);
+    }
+
+    /// Leaves the guess out of the puzzle and takes it off the board, so the
+    /// rest move up the rail. The last one stays. It comes back, crossed
+    /// out, with another level or when the file is opened again.
+    pub fn remove(&mut self, target: VennTarget, all: &[VennTarget]) {
+        if self.is_chosen(&target) {
+            self.toggle(target, all);
+        }
+        if !self.is_chosen(&target) && !self.removed.contains(&target) {
+            self.removed.push(target);
+        }
+    }
+
+    /// Selects the next circle.
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.puzzle.circles.len();
+    }
+
+    /// Steps the level the puzzle is played by, which decides the
+    /// attributes that count and the guesses there are.
+    pub fn step_level(&mut self, up: bool) {
+        let level = &mut self.puzzle.level;
+        *level = if up {
+            (*level + 1) % LEVEL_COUNT
+        } else {
+            (*level + LEVEL_COUNT - 1) % LEVEL_COUNT
+        };
+        // The guesses of one level mostly aren't those of another.
+        self.puzzle.guesses = None;
+        self.removed.clear();
+    }
+
+    /// Steps the selected circle to the next rule, or from a compound rule
+    /// back to a hidden card.
+    pub fn step_rule(&mut self) {
+        let circle = &mut self.puzzle.circles[self.selected];
+        if circle.compound.take().is_some() {
+            circle.has = None;
+            return;
+        }
+        let next = RULES
+            .iter()
+            .position(|&r| r == circle.rule)
+            .map_or(0, |i| i + 1);
+        circle.rule = RULES[next % RULES.len()];
+    }
+
+    /// Gives the selected circle a compound rule of the value instead of
+    /// its rule and hidden card, to build on.
+    pub fn make_compound(&mut self, value: Value) {
+        let circle = &mut self.puzzle.circles[self.selected];
+        if circle.compound.is_none() {
+            circle.compound = Some(RuleExpr::Is(value));
+        }
+    }
+
+    /// Gives the selected circle the card, or the compound rule if it has
+    /// one.
+    pub fn set_card(&mut self, card: VennTarget, rule: Option<RuleExpr>) {
+        let circle = &mut self.puzzle.circles[self.selected];
+        match rule {
+            Some(rule) if circle.compound.is_some() => {
+                circle.compound = Some(rule);
+                // Saved as built, rather than in the tokens' words.
+                circle.has = None;
+            }
+            _ => {
+                circle.card = card;
+                // The card of a puzzle with its own cards goes by its name.
+                let table = self.puzzle.table.as_ref();
+                circle.token = table.and_then(|t| t.named(&card)).map(str::to_string);
+            }
+        }
+    }
+
+    /// Names the selected circle, or takes its label away if there is
+    /// nothing to it.
+    pub fn set_label(&mut self, label: &str) {
+        let label = label.trim();
+        self.puzzle.circles[self.selected].label = (!label.is_empty()).then(|| label.to_string());
+    }
+
+    /// Adds a bottom circle, like the one before it, or takes it away.
+    pub fn toggle_third(&mut self) {
+        let circles = &mut self.puzzle.circles;
+        if circles.len() == 3 {
+            circles.pop();
+            self.selected = self.selected.min(1);
+        } else {
+            let mut bottom = circles[1].clone();
+            bottom.center = None;
+            bottom.radius = None;
+            bottom.label = None;
+            circles.push(bottom);
+        }
+    }
+
+    /// Grows or shrinks the selected circle.
+    pub fn resize(&mut self, model: &VennModel, grow: bool) {
+        let radius = match model.circles().nth(self.selected) {
+            Some(circle) => circle.radius,
+            None => return,
+        };
+        let step = if grow { RESIZE } else { -RESIZE };
+        self.puzzle.circles[self.selected].radius = Some((radius + step).clamp(SMALLEST, LARGEST));
+    }
+
+    /// Steps the selected set to the next outline, from a circle to an
+    /// ellipse to a rounded rectangle and back.
+    pub fn step_outline(&mut self) {
+        let circle = &mut self.puzzle.circles[self.selected];
+        let outline = circle.outline.unwrap_or_default();
+        let next = OUTLINES
+            .iter()
+            .position(|o| std::mem::discriminant(o) == std::mem::discriminant(&outline))
+            .map_or(0, |i| i + 1);
+        circle.outline = match OUTLINES[next % OUTLINES.len()] {
+            Outline::Circle => None,
+            outline => Some(outline),
+        };
+    }
+
+    /// Turns the selected set, if it is an ellipse, clockwise or back.
+    pub fn turn(&mut self, clockwise: bool) {
+        let circle = &mut self.puzzle.circles[self.selected];
+        if let Some(Outline::Ellipse { angle, .. }) = &mut circle.outline {
+            let step = if clockwise { TURN } else { -TURN };
+            *angle = (*angle + step) % 180.0;
+        }
+    }
+
+    /// Picks up the circle under the point, the selected one first.
+    pub fn grab(&mut self, model: &VennModel, point: &Point) {
+        let circles: Vec<_> = model.circles().collect();
+        let under = std::iter::once(self.selected)
+            .chain(0..circles.len())
+            .find(|&i| circles.get(i).is_some_and(|c| c.contains(point)));
+        if let Some(index) = under {
+            self.selected = index;
+            self.grab = Some(circles[index].center - point);
+        }
+    }
+
+    /// Where the circle being dragged is, with the pointer at the point.
+    pub fn dragged(&self, point: &Point) -> Option<Point> {
+        self.grab.map(|offset| point + offset)
+    }
+
+    /// Puts the circle being dragged down at the point.
+    pub fn drop(&mut self, point: &Point) {
+        if let Some(center) = self.dragged(point) {
+            self.puzzle.circles[self.selected].center = Some((center.x, center.y));
+        }
+        self.grab = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // What the editor saves, the game can load.
+    #[test]
+    fn saved_puzzles_load() {
+        let options = Options {
+            seed: 7,
+            ..Options::default()
+        };
+        let path = std::env::temp_dir().join(format!("venn-editor-{}.json", std::process::id()));
+        let mut editor = Editor::open(&path, &options).unwrap();
+        editor.toggle_third();
+        editor.step_rule();
+        editor.step_outline();
+        editor.turn(true);
+        let model = editor.model(&options);
+        let all: Vec<VennTarget> = model.guesses().iter().map(|g| g.target).collect();
+        editor.toggle(all[0], &all);
+        editor.remove(all[1], &all);
+        assert_eq!(editor.model(&options).guesses().len(), all.len() - 1);
+        editor.grab(&model, &model.circles().next().unwrap().center);
+        editor.drop(&Point::new(250.0, 280.0));
+        editor.save().unwrap();
+        let loaded = puzzle::read(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.circles.len(), 3);
+        assert_eq!(loaded.circles[0].center, Some((250.0, 280.0)));
+        assert_eq!(
+            loaded.circles[0].outline,
+            Some(Outline::Ellipse {
+                ratio: 0.6,
+                angle: TURN
+            })
+        );
+        assert_eq!(loaded.guesses.map(|g| g.len()), Some(all.len() - 2));
+    }
+}
@@ -0,0 +1,2154 @@
+use crate::{
+    assets::{self, Assets, Loading},
+    builder::{self, Builder},
+    circle::VennSet,
+    code,
+    config::{self, Config},
+    cursor::Cursor,
+    diagnostics::Diagnostics,
+    editor::Editor,
+    glide::Glides,
+    guess::VennGuess,
+    input::{self, VennInput},
+    keyboard::Keyboard,
+    layer::{Layer, Stamps},
+    leaderboard::{self, Entry, Leaderboard},
+    locale,
+    model::{Mode, Options, Placement, Preset, VennModel, HEIGHT, LEVEL_COUNT, WIDTH},
+    net::{Link, Message},
+    notebook::Notebook,
+    pack::{self, Pack, Progress},
+    palette::Style,
+    profile::{self, Profile},
+    puzzle,
+    recorder::Recorder,
+    region::Region,
+    render::Canvas,
+    save,
+    screenshot::{self, Raster},
+    seat::HotSeat,
+    solver::Hint,
+    sound::{Effect, Sound},
+    speech::Speech,
+    stats::{self, Stats},
+    svg,
+    theme::Theme,
+    trail::Trails,
+    tutorial::Tutorial,
+    ui::{self, Ui},
+    watch::Watcher,
+};
+use coffee::{
+    graphics::{Frame, Mesh, Point, Rectangle, Shape, Transformation, Window, WindowSettings},
+    input::keyboard::KeyCode,
+    load::{Join, Task},
+    Game, Result, Timer,
+};
+use log::{debug, warn};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver},
+        Mutex, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+// Seconds a finished round stays on screen before the results are shown.
+const LEVEL_DELAY: f32 = 1.0;
+
+// The entries of the pause menu, by their keys in the locale bundles.
+const MENU: [&str; 7] = [
+    "menu.resume",
+    "menu.new_puzzle",
+    "menu.restart",
+    "menu.campaign",
+    "menu.statistics",
+    "menu.settings",
+    "menu.quit",
+];
+
+// The longest name the leaderboard takes.
+const NAME_LENGTH: usize = 16;
+// The longest label of a circle, which has to fit across it.
+const LABEL_LENGTH: usize = 24;
+
+// The entries of the settings screen after the settings themselves.
+const SAVE: usize = 16;
+const CANCEL: usize = 17;
+
+// What the frontend is showing.
+#[derive(PartialEq, Copy, Clone)]
+enum GameState {
+    Playing,
+    // The pause menu with the index of the selected entry.
+    Paused { selected: usize },
+    // The results of the round that just ended.
+    Results,
+    // The board of the round that just ended, with why the guess clicked
+    // is right or wrong.
+    Explaining { guess: Option<usize> },
+    // The statistics, opened from the pause menu entry that is still
+    // selected.
+    Stats { selected: usize },
+    // Choosing who is playing, before the first round.
+    Picking { selected: usize },
+    // Changing the settings, opened from the pause menu entry in pause.
+    Settings { selected: usize, pause: usize },
+    // Building a card for an answer box.
+    Building { builder: Builder },
+    // Typing a name for the leaderboard after a round that made it on.
+    Naming,
+    // The best rounds at the difficulty of the round that just ended.
+    Leaderboard,
+    // Keeping notes on what the hidden cards could be.
+    Notebook,
+    // Picking cards to add to the sandbox.
+    Palette,
+    // Choosing a pack to play through, opened from the pause menu entry in
+    // pause.
+    Packs { selected: usize, pause: usize },
+    // Paused while the window is in the background.
+    Away,
+    // Editing a puzzle file.
+    Editing,
+    // Building the hidden card or compound rule of a circle in the editor.
+    EditingCard { builder: Builder },
+    // Typing the label of a circle in the editor.
+    EditingLabel,
+}
+
+// Everything the diagram is drawn from.
+#[derive(PartialEq)]
+struct DiagramKey {
+    // Where the pointer is, which decides what is highlighted.
+    region: Region,
+    hover: Option<usize>,
+    revealed: bool,
+    style: Style,
+    circles: Vec<VennSet>,
+}
+
+// The guess moved to the origin, where every guess that looks the same is
+// alike.
+fn stamp(guess: &VennGuess) -> VennGuess {
+    let origin = Point::new(0.0, 0.0);
+    VennGuess {
+        center: origin,
+        home: origin,
+        scored: false,
+        z: 0,
+        ..guess.clone()
+    }
+}
+
+// The pack being played through and which of its puzzles is up.
+struct Campaign {
+    pack: Pack,
+    puzzle: usize,
+}
+
+// The coffee frontend: feeds mouse input to the model and draws it.
+struct Venn {
+    state: GameState,
+    model: VennModel,
+    // The round as it was when it started, for replaying it.
+    start: VennModel,
+    // What the solver takes to solve the round that just ended.
+    par: Option<u32>,
+    // The par of the round being played, on its way from another thread.
+    upcoming_par: Option<Receiver<Option<u32>>>,
+    rng: StdRng,
+    cursor: Point,
+    // Which round this is since the game started, from 1.
+    round: u32,
+    // Counts down once the level is over, then the results are shown.
+    solved_delay: Option<f32>,
+    // How far the game is to moving on again. Every tick of the game loop
+    // adds the tick rate, and the game moves on each time that makes a
+    // second's worth of ticks, so rates that don't divide the loop's keep
+    // their pace too.
+    owed: u16,
+    // When the last frame was drawn, to keep to the frame cap.
+    last_frame: Instant,
+    // Shown until the player picks something up.
+    hint: Option<Hint>,
+    ui: Ui,
+    keyboard: Keyboard,
+    // Kept in the data directory of the profile if there is one.
+    stats: Stats,
+    profile: Option<Profile>,
+    // The profiles to pick from at the start, followed by playing as a
+    // guest.
+    profiles: Vec<String>,
+    config: Config,
+    // The settings as changed on the settings screen, until they are saved.
+    draft: Config,
+    // Where the settings are saved to.
+    config_path: Option<PathBuf>,
+    // How much bigger than the model's coordinates the window is.
+    scale: f32,
+    sound: Sound,
+    speech: Speech,
+    // The guess read out last, so it is said once while the pointer stays
+    // on it.
+    spoken: Option<usize>,
+    trails: Trails,
+    glides: Glides,
+    // The guess clicked last without dragging it, whose details are shown.
+    selected: Option<usize>,
+    // Whether the trails are drawn.
+    show_trails: bool,
+    show_notation: bool,
+    // Walks a new player through the first moves, until it is done.
+    tutorial: Option<Tutorial>,
+    leaderboard: Leaderboard,
+    // The name being typed for the leaderboard.
+    name: String,
+    // The label being typed for a circle in the editor.
+    label: String,
+    // Two players taking turns, if playing hot-seat or over the network.
+    seat: Option<HotSeat>,
+    // The other machine, when playing over the network.
+    link: Option<Link>,
+    // The round being recorded as a GIF, while recording is on.
+    recorder: Option<Recorder>,
+    // The parts of the board kept between frames.
+    diagram: Layer<DiagramKey>,
+    stamps: Stamps<(Style, VennGuess)>,
+    // The pictures of the cards of puzzles with their own, and the sounds.
+    assets: Assets,
+    // What the player noted about the hidden cards this round.
+    notebook: Notebook,
+    // The packs on the pack screen, read when it opens.
+    packs: Vec<Pack>,
+    campaign: Option<Campaign>,
+    // The puzzles solved in each pack by whoever is playing.
+    progress: Progress,
+    // The puzzle being edited, when the game was started to edit one. Its
+    // board is played to try it out.
+    editor: Option<Editor>,
+    // What was running when the window went into the background, to carry
+    // on with once it comes back.
+    away: Option<GameState>,
+    // What the debug overlay shows, while it is on.
+    diagnostics: Option<Diagnostics>,
+    // Notices the puzzle file and the settings being changed by hand.
+    watcher: Option<Watcher>,
+}
+
+impl Game for Venn {
+    type Input = VennInput;
+    type LoadingScreen = Loading;
+    // The game itself moves on at the tick rate of the settings, on some of
+    // these.
+    const TICKS_PER_SECOND: u16 = config::MAX_TICK_RATE;
+    // F12 takes screenshots instead.
+    const DEBUG_KEY: Option<KeyCode> = None;
+
+    fn load(_window: &Window) -> Task<Venn> {
+        let settings = SETTINGS.get().unwrap();
+        locale::set(settings.config.language);
+        input::set_buttons(settings.config.buttons);
+        let options = &settings.options;
+        // The pictures of the first round's cards are loaded with the rest.
+        let pictures = FIRST
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|model| model.tokens())
+            .map(|tokens| {
+                tokens
+                    .tokens
+                    .iter()
+                    .filter_map(|t| t.image.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let model = Task::new(move || {
+            let mut rng = StdRng::seed_from_u64(options.seed);
+            let model = match FIRST.lock().unwrap().take() {
+                Some(model) => model,
+                None => VennModel::new(0, options, &mut rng),
+            };
+            (model, rng)
+        });
+        let model = Task::stage(locale::text("loading.board"), model);
+        // With no profile asked for, whoever is playing picks one, unless
+        // a particular game or puzzle was asked for.
+        let profiles = if settings.profile.is_none()
+            && settings.load.is_none()
+            && settings.puzzle.is_none()
+            && settings.edit.is_none()
+        {
+            profile::list()
+        } else {
+            vec![]
+        };
+        let state = if settings.edit.is_some() {
+            GameState::Editing
+        } else if profiles.is_empty() {
+            GameState::Playing
+        } else {
+            GameState::Picking { selected: 0 }
+        };
+        let assets = assets::load(pictures);
+        (model, assets)
+            .join()
+            .map(move |((model, rng), (font, assets))| {
+                let mut venn = Venn {
+                    state,
+                    start: model.clone(),
+                    par: None,
+                    upcoming_par: Some(work_out_par(&model)),
+                    model,
+                    rng,
+                    cursor: Point::new(0.0, 0.0),
+                    round: 1,
+                    solved_delay: None,
+                    owed: 0,
+                    last_frame: Instant::now(),
+                    hint: None,
+                    ui: Ui::new(font),
+                    keyboard: Keyboard::new(),
+                    stats: Stats::default(),
+                    profile: None,
+                    profiles: profiles.clone(),
+                    config: settings.config.clone(),
+                    draft: settings.config.clone(),
+                    config_path: settings.config_path.clone(),
+                    scale: settings.config.window_scale(),
+                    sound: Sound::new(settings.config.volume),
+                    speech: Speech::new(),
+                    spoken: None,
+                    trails: Trails::default(),
+                    glides: Glides::default(),
+                    selected: None,
+                    show_trails: false,
+                    show_notation: false,
+                    tutorial: if settings.tutorial {
+                        Some(Tutorial::new())
+                    } else {
+                        None
+                    },
+                    leaderboard: Leaderboard::default(),
+                    name: String::new(),
+                    label: String::new(),
+                    seat: None,
+                    link: LINK.lock().unwrap().take(),
+                    recorder: None,
+                    diagram: Layer::default(),
+                    stamps: Stamps::default(),
+                    assets,
+                    notebook: Notebook::default(),
+                    packs: vec![],
+                    campaign: None,
+                    progress: Progress::default(),
+                    editor: EDITOR.lock().unwrap().take(),
+                    away: None,
+                    diagnostics: None,
+                    watcher: Watcher::new()
+                        .map_err(|error| warn!("could not watch for changes: {}", error))
+                        .ok(),
+                };
+                if settings.hot_seat || venn.link.is_some() {
+                    venn.seat = Some(HotSeat::new());
+                }
+                // run has checked the name already.
+                venn.profile = settings
+                    .profile
+                    .as_ref()
+                    .and_then(|name| Profile::new(name).ok());
+                venn.stats = venn.read_stats();
+                venn.progress = venn.read_progress();
+                venn.leaderboard = read_leaderboard();
+                let watched = settings.puzzle.iter().chain(&venn.config_path);
+                for path in watched.cloned().collect::<Vec<_>>() {
+                    venn.watch(&path);
+                }
+                venn
+            })
+    }
+
+    fn draw(&mut self, frame: &mut Frame<'_>, timer: &Timer) {
+        // Frames come as often as the screen refreshes, so a cap waits out
+        // what is left of the frame before drawing the next one.
+        if let Some(cap) = self.config.frame_cap.filter(|&cap| cap > 0) {
+            let frame = Duration::from_secs_f32(1.0 / cap as f32);
+            if let Some(rest) = frame.checked_sub(self.last_frame.elapsed()) {
+                thread::sleep(rest);
+            }
+        }
+        self.last_frame = Instant::now();
+        let style = &self.style();
+        frame.clear(style.theme.background);
+        self.ui.set_style(*style);
+        // The round's own cards name the attributes, whichever way the
+        // round was started.
+        ui::speak(self.model.tokens());
+        self.assets.load_pictures(frame.gpu(), self.model.tokens());
+        // The pointer is followed every frame, but time only moves on with
+        // the ticks, so what fades is drawn as far along as the frame is
+        // between them.
+        let since = if self.is_running() {
+            let ticks = self.owed as f32 / self.config.tick_rate() as f32;
+            (ticks + timer.next_tick_proximity()) / Self::TICKS_PER_SECOND as f32
+        } else {
+            0.0
+        };
+        let point = self.pointer();
+        let revealed = self.revealed();
+        let mut target = frame.as_target();
+        let target = &mut target.transform(Transformation::scale(self.scale));
+        // The diagram only changes with what the pointer is over and what
+        // is in the boxes.
+        let building = Instant::now();
+        let model = &self.model;
+        let key = DiagramKey {
+            region: model.region(&point),
+            hover: model.answer_at(&point),
+            revealed,
+            style: *style,
+            circles: model.circles().cloned().collect(),
+        };
+        self.diagram.draw(key, target, |mesh| {
+            model.draw_diagram(mesh, style, point, revealed)
+        });
+        // Pictured cards show in the answer boxes as built, and the hidden
+        // ones once the rules do.
+        if let Some(tokens) = model.tokens() {
+            let shown = model.is_forfeited() || model.shows_rules();
+            for circle in model.circles().filter(|c| c.compound.is_none()) {
+                let answer = &circle.answer;
+                let card = if shown {
+                    Some(answer.target)
+                } else {
+                    answer.built
+                };
+                let image = card.and_then(|card| tokens.image(&card));
+                if let Some(picture) = image.and_then(|image| self.assets.picture(image)) {
+                    let size = answer.width.min(answer.height) - 8.0;
+                    self.assets.draw(picture, answer.center, size, target);
+                }
+            }
+        }
+        let mut under = Mesh::new();
+        self.draw_under(&mut under, style, since);
+        if !under.is_empty() {
+            under.draw(target);
+        }
+        // The guess being dragged goes over the others.
+        let dragging = model.dragging();
+        let (held, still): (Vec<_>, Vec<_>) = model
+            .stacking()
+            .into_iter()
+            .partition(|&i| dragging == Some(i));
+        for i in still.into_iter().chain(held) {
+            let guess = &model.guesses()[i];
+            let stamp = stamp(guess);
+            let at = self.glides.at(i, guess.center, since);
+            self.stamps
+                .draw((*style, stamp.clone()), at, target, |mesh| {
+                    stamp.draw(mesh, style)
+                });
+            let image = model.tokens().and_then(|t| t.image(&guess.target));
+            if let Some(picture) = image.and_then(|image| self.assets.picture(image)) {
+                self.assets.draw(picture, at, guess.radius * 1.6, target);
+            }
+        }
+        let mut over = Mesh::new();
+        self.draw_over(&mut over, style);
+        if !over.is_empty() {
+            over.draw(target);
+        }
+        if let Some(diagnostics) = &mut self.diagnostics {
+            diagnostics.built(building.elapsed());
+        }
+        // The editor shows the rules instead of the score.
+        match &self.editor {
+            Some(editor) if self.is_editing() => {
+                self.ui
+                    .draw_editor(target, &self.model, editor, self.cursor)
+            }
+            _ => {
+                self.ui.draw(target, &self.model, self.round);
+                self.ui
+                    .draw_regions(target, &self.model, self.show_notation);
+            }
+        }
+        if let Some(seat) = &self.seat {
+            self.ui.draw_players(target, seat);
+        }
+        if self.recorder.is_some() {
+            self.ui.draw_recording(target);
+        }
+        if let Some(diagnostics) = &self.diagnostics {
+            self.ui
+                .draw_diagnostics(target, &self.model, diagnostics, self.cursor);
+        }
+        match self.state {
+            GameState::Playing => {
+                if let Some(tutorial) = &self.tutorial {
+                    self.ui.draw_tutorial(target, tutorial, &self.model);
+                }
+                if self.model.options().mode == Mode::Sandbox && self.model.dragging().is_some() {
+                    self.ui.draw_trash(target, &self.model, self.cursor);
+                }
+                self.ui.draw_overlap(target, &self.model, point);
+                self.ui.draw_tooltip(target, &self.model, self.cursor);
+                if let Some(guess) = self.selected() {
+                    self.ui.draw_inspection(target, &self.model, guess);
+                }
+            }
+            GameState::Paused { selected } => {
+                self.ui.draw_menu(target, &MENU.map(locale::text), selected)
+            }
+            GameState::Results => {
+                self.ui.draw_results(target, &self.model, self.par);
+                if let Some(seat) = &self.seat {
+                    self.ui.draw_winner(target, seat);
+                }
+            }
+            GameState::Explaining { guess } => {
+                self.ui.draw_explanation(target, &self.model, guess);
+                self.ui.draw_tooltip(target, &self.model, self.cursor);
+            }
+            GameState::Stats { .. } => self.ui.draw_stats(target, &self.stats),
+            GameState::Naming => self.ui.draw_naming(target, &self.name),
+            GameState::EditingLabel => self.ui.draw_labelling(target, &self.label),
+            GameState::Leaderboard => {
+                let difficulty = self.model.options().difficulty;
+                let board = self.leaderboard.board(difficulty);
+                self.ui.draw_leaderboard(target, &board, difficulty);
+            }
+            GameState::Picking { selected } => {
+                let mut entries: Vec<&str> = self.profiles.iter().map(String::as_str).collect();
+                entries.push(locale::text("profile.guest"));
+                self.ui.draw_menu(target, &entries, selected);
+            }
+            GameState::Settings { selected, .. } => {
+                let entries = ui::settings_entries(&self.draft);
+                let entries: Vec<&str> = entries.iter().map(String::as_str).collect();
+                self.ui.draw_menu(target, &entries, selected);
+            }
+            GameState::Building { builder } => self.ui.draw_builder(target, &builder, &self.model),
+            GameState::Notebook => self.ui.draw_notebook(target, &self.model, &self.notebook),
+            GameState::Palette => self.ui.draw_palette(target, &self.model, self.cursor),
+            GameState::Packs { selected, .. } => {
+                let entries = ui::pack_entries(&self.packs, &self.progress);
+                let entries: Vec<&str> = entries.iter().map(String::as_str).collect();
+                self.ui.draw_menu(target, &entries, selected);
+            }
+            GameState::Away => self.ui.draw_away(target),
+            GameState::Editing => {}
+            GameState::EditingCard { builder } => {
+                self.ui.draw_builder(target, &builder, &self.model)
+            }
+        }
+    }
+
+    fn interact(&mut self, input: &mut Self::Input, _window: &mut Window) {
+        self.cursor = Point::new(
+            input.cursor_position.x / self.scale,
+            input.cursor_position.y / self.scale,
+        );
+        self.receive();
+        self.reload();
+        self.fit_board();
+        if !input.is_focused && self.is_running() {
+            self.go_away();
+        } else if input.is_focused && self.state == GameState::Away {
+            // The click that brought the window back isn't a move.
+            self.state = self.away.take().unwrap_or(GameState::Playing);
+            return;
+        }
+        if input.keys_pressed.contains(&KeyCode::F12) {
+            self.screenshot();
+        }
+        if input.keys_pressed.contains(&KeyCode::F3) {
+            self.diagnostics = match self.diagnostics {
+                Some(_) => None,
+                None => Some(Diagnostics::new()),
+            };
+        }
+        match self.state {
+            GameState::Playing => {
+                let held = self.model.dragging();
+                let scored = held.map(|i| self.model.guesses()[i].scored);
+                let from = held.map(|i| self.model.guesses()[i].center);
+                self.play(input);
+                self.play_effects(held);
+                self.speak(held);
+                if let (Some(index), Some(scored), None) = (held, scored, self.model.dragging()) {
+                    // Dropped guesses slide into the spot they snapped to.
+                    if let Some(from) = from {
+                        if from != self.model.guesses()[index].center {
+                            self.glides.start(index, from);
+                        }
+                    }
+                    self.take_turn(index, scored);
+                    let to = self.model.guesses()[index].center;
+                    self.send(&Message::Move { guess: index, to });
+                    // A guess let go of over the rail leaves the sandbox,
+                    // unless it was only clicked, which selects it.
+                    if self.model.options().mode == Mode::Sandbox
+                        && self.selected != Some(index)
+                        && self.cursor.x < self.model.rail_right()
+                    {
+                        self.remove_guess(index);
+                    }
+                }
+                if let Some(tutorial) = &mut self.tutorial {
+                    // Not Enter, which picks up and drops guesses with the
+                    // keyboard.
+                    let next = input.keys_pressed.contains(&KeyCode::Space);
+                    if !tutorial.advance(&self.model, next) {
+                        self.tutorial = None;
+                    }
+                }
+            }
+            GameState::Paused { selected } => self.choose(input, selected),
+            GameState::Results if input.keys_pressed.contains(&KeyCode::X) => {
+                self.state = GameState::Explaining { guess: None };
+            }
+            // Over the network, the host starts the next round for both.
+            GameState::Results if self.link.as_ref().is_some_and(|link| !link.is_host()) => {}
+            GameState::Results => {
+                if input.keys_pressed.contains(&KeyCode::Return) {
+                    self.rank();
+                } else if input.keys_pressed.contains(&KeyCode::R) {
+                    self.next_round(true);
+                } else if input.keys_pressed.contains(&KeyCode::E) {
+                    self.export_svg(None);
+                }
+            }
+            GameState::Explaining { guess } => {
+                if input.is_mouse_clicked {
+                    let clicked = self.model.guess_at(&self.cursor);
+                    self.state = GameState::Explaining {
+                        guess: clicked.or(guess),
+                    };
+                } else if input.keys_pressed.contains(&KeyCode::Escape) {
+                    self.state = GameState::Results;
+                }
+            }
+            GameState::Stats { selected } => {
+                if !input.keys_pressed.is_empty() || input.is_mouse_clicked {
+                    self.state = GameState::Paused { selected };
+                }
+            }
+            GameState::Picking { selected } => self.pick_profile(input, selected),
+            GameState::Settings { selected, pause } => self.change_settings(input, selected, pause),
+            GameState::Building { builder } => self.build(input, builder),
+            GameState::Notebook => self.take_notes(input),
+            GameState::Palette => self.add_cards(input),
+            GameState::Packs { selected, pause } => self.pick_pack(input, selected, pause),
+            GameState::Away => {}
+            GameState::Editing => self.edit(input),
+            GameState::EditingCard { builder } => self.edit_card(input, builder),
+            GameState::Naming => self.type_name(input),
+            GameState::EditingLabel => self.type_label(input),
+            GameState::Leaderboard => {
+                if input.keys_pressed.contains(&KeyCode::Return) || input.is_mouse_clicked {
+                    self.next_round(false);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, _window: &Window) {
+        let rate = self.config.tick_rate();
+        self.owed += rate;
+        if self.owed < Self::TICKS_PER_SECOND {
+            return;
+        }
+        self.owed -= Self::TICKS_PER_SECOND;
+        if let Some(diagnostics) = &mut self.diagnostics {
+            diagnostics.tick();
+        }
+        if !self.is_running() {
+            return;
+        }
+        // Time moves on as much at any tick rate.
+        let seconds = 1.0 / rate as f32;
+        self.model.advance(seconds);
+        self.model.spread(seconds);
+        self.trails.update(self.model.guesses(), seconds);
+        self.glides.update(seconds);
+        if let Some(mut recorder) = self.recorder.take() {
+            let style = self.style();
+            match recorder.tick(seconds, |raster| self.draw_board(raster, &style)) {
+                Ok(()) => self.recorder = Some(recorder),
+                Err(error) => warn!("could not record the round: {}", error),
+            }
+        }
+        if !self.model.is_finished() {
+            self.solved_delay = None;
+            return;
+        }
+        match self.solved_delay {
+            None => {
+                self.solved_delay = Some(LEVEL_DELAY);
+                self.sound_off(Effect::RoundOver);
+            }
+            Some(left) if left <= 0.0 => {
+                self.state = GameState::Results;
+                self.record();
+                if let Some(recorder) = self.recorder.take() {
+                    match recorder.finish() {
+                        Ok(path) => eprintln!("venn: saved a recording to {}", path.display()),
+                        Err(error) => warn!("could not record the round: {}", error),
+                    }
+                    self.start_recording();
+                }
+                if let Some(path) = &SETTINGS.get().unwrap().export_svg {
+                    self.export_svg(Some(path.clone()));
+                }
+            }
+            Some(left) => self.solved_delay = Some(left - seconds),
+        }
+    }
+
+    fn on_close_request(&mut self) -> bool {
+        self.autosave();
+        self.stop_recording();
+        true
+    }
+}
+
+impl Venn {
+    // Where the player is working: the guess moved with the keyboard, which
+    // is away from the cursor, or else the cursor.
+    fn pointer(&self) -> Point {
+        self.model
+            .dragging()
+            .map(|i| self.model.guesses()[i].center)
+            .unwrap_or(self.cursor)
+    }
+
+    // Draws everything but the text, in the window or for a screenshot.
+    fn draw_board(&self, canvas: &mut impl Canvas, style: &Style) {
+        canvas.fill(
+            Shape::Rectangle(Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: WIDTH,
+                height: HEIGHT,
+            }),
+            style.theme.background,
+        );
+        self.model
+            .draw_diagram(canvas, style, self.pointer(), self.revealed());
+        self.draw_under(canvas, style, 0.0);
+        let dragging = self.model.dragging();
+        let draw = |canvas: &mut _, i: usize| {
+            let mut guess = self.model.guesses()[i].clone();
+            guess.center = self.glides.at(i, guess.center, 0.0);
+            guess.draw(canvas, style);
+        };
+        for i in self.model.stacking() {
+            if dragging != Some(i) {
+                draw(canvas, i);
+            }
+        }
+        if let Some(index) = dragging {
+            draw(canvas, index);
+        }
+        self.draw_over(canvas, style);
+    }
+
+    // The selected guess, if the puzzle still has it after the other
+    // machine sent a new one.
+    fn selected(&self) -> Option<usize> {
+        self.selected
+            .filter(|&guess| guess < self.model.guesses().len())
+    }
+
+    // Whether the hidden cards show in their boxes, as they do once the
+    // round is looked back on.
+    fn revealed(&self) -> bool {
+        self.model.is_forfeited()
+            || self.model.shows_rules()
+            || matches!(self.state, GameState::Explaining { .. })
+    }
+
+    // Time stands still in the menu and once the round is over, but not
+    // while building a card.
+    fn is_running(&self) -> bool {
+        matches!(
+            self.state,
+            GameState::Playing | GameState::Building { .. } | GameState::Notebook
+        )
+    }
+
+    // Plays the sound of what happened on the board.
+    fn sound_off(&self, effect: Effect) {
+        self.sound
+            .play(self.assets.clip(self.assets.effect(effect)));
+    }
+
+    // How to draw, with lines and the shapes on cards as big as the board
+    // is laid out for.
+    fn style(&self) -> Style {
+        Style {
+            scale: self.model.ui_scale(),
+            ..self.config.style()
+        }
+    }
+
+    // Lays the board out again once the UI scale changed. Over the network
+    // the board keeps its usual size, so a move lands in the same place on
+    // both machines, and so does a puzzle being edited, which keeps where
+    // its circles are.
+    fn fit_board(&mut self) {
+        let scale = self.config.ui_scale();
+        if self.model.ui_scale() == scale
+            || self.link.is_some()
+            || self.editor.is_some()
+            || self.model.dragging().is_some()
+        {
+            return;
+        }
+        self.model.rescale(scale);
+        self.trails.clear();
+        self.glides.clear();
+        self.hint = None;
+    }
+
+    // Pauses what is running while the window is in the background, so the
+    // clock stops when a teacher switches to something else. A guess being
+    // held goes back to the rail, as its button is let go of elsewhere.
+    fn go_away(&mut self) {
+        if let Some(index) = self.model.dragging() {
+            let home = self.model.guesses()[index].home;
+            self.model.drag(&home);
+            self.model.drop(&home);
+            self.keyboard.clear();
+            self.send(&Message::Move {
+                guess: index,
+                to: home,
+            });
+        }
+        self.away = Some(self.state);
+        self.state = GameState::Away;
+    }
+
+    // What goes between the diagram and the guesses, and changes while
+    // dragging, with the trails faded by the seconds since the last tick.
+    fn draw_under(&self, canvas: &mut impl Canvas, style: &Style, since: f32) {
+        // Easy games show where the guess being dragged belongs.
+        if let Some(index) = self.model.dragging() {
+            if self.model.options().difficulty == Some(Preset::Easy) {
+                self.model.draw_right_regions(canvas, style, index);
+            }
+        }
+        if self.show_trails {
+            self.trails.draw(canvas, style, self.model.guesses(), since);
+        }
+    }
+
+    // The rings around things, over the guesses.
+    fn draw_over(&self, canvas: &mut impl Canvas, style: &Style) {
+        if let Some(hint) = self.hint {
+            hint.draw(canvas, style, &self.model);
+        }
+        if let Some(focus) = self.keyboard.focus() {
+            self.model.guesses()[focus].draw_focus(canvas, style);
+        }
+        if let Some(selected) = self.selected() {
+            self.model.guesses()[selected].draw_focus(canvas, style);
+        }
+        if let Some(tutorial) = &self.tutorial {
+            tutorial.draw(canvas, style, &self.model);
+        }
+        if let (Some(seat), GameState::Playing) = (&self.seat, self.state) {
+            seat.draw(canvas, self.pointer());
+        }
+        if self.state == GameState::Playing {
+            if let Some(cursor) = Cursor::at(&self.model, &self.pointer()) {
+                cursor.draw(canvas, style, self.cursor);
+            }
+        }
+    }
+
+    // Records from now until the round ends.
+    fn start_recording(&mut self) {
+        match Recorder::new(self.scale) {
+            Ok(recorder) => self.recorder = Some(recorder),
+            Err(error) => warn!("could not record the round: {}", error),
+        }
+    }
+
+    // Throws away the round recorded so far, if recording.
+    fn stop_recording(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            if let Err(error) = recorder.discard() {
+                warn!("could not delete the recording: {}", error);
+            }
+        }
+    }
+
+    // Saves a picture of the board, reporting where it went.
+    fn screenshot(&self) {
+        let mut raster = Raster::new(self.scale);
+        self.draw_board(&mut raster, &self.style());
+        let path = screenshot::path();
+        match raster.write(&path) {
+            Ok(()) => eprintln!("venn: saved a screenshot to {}", path.display()),
+            Err(error) => warn!("could not save to {}: {}", path.display(), error),
+        }
+    }
+
+    fn play(&mut self, input: &VennInput) {
+        let my_turn = self.is_my_turn();
+        let sandbox = self.model.options().mode == Mode::Sandbox;
+        for &key in &input.keys_pressed {
+            let moved = match (key, input.is_control_pressed, input.is_shift_pressed) {
+                (KeyCode::H, false, _) => {
+                    self.hint = self.model.hint();
+                    false
+                }
+                (KeyCode::G, false, _) => {
+                    self.model.give_up();
+                    self.send(&Message::GiveUp);
+                    true
+                }
+                (KeyCode::M, false, _) => {
+                    self.sound.toggle_mute();
+                    false
+                }
+                (KeyCode::V, false, _) => {
+                    if self.recorder.is_some() {
+                        self.stop_recording();
+                    } else {
+                        self.start_recording();
+                    }
+                    false
+                }
+                (KeyCode::T, false, _) => {
+                    self.show_trails = !self.show_trails;
+                    false
+                }
+                (KeyCode::N, false, _) => {
+                    self.show_notation = !self.show_notation;
+                    false
+                }
+                (KeyCode::P, false, _) if sandbox && self.model.dragging().is_none() => {
+                    self.state = GameState::Palette;
+                    return;
+                }
+                (KeyCode::L, false, shift) if sandbox && self.model.dragging().is_none() => {
+                    let level = self.model.level();
+                    self.start_round(if shift {
+                        (level + LEVEL_COUNT - 1) % LEVEL_COUNT
+                    } else {
+                        (level + 1) % LEVEL_COUNT
+                    });
+                    return;
+                }
+                (KeyCode::Delete | KeyCode::Back, false, _)
+                    if sandbox && self.model.dragging().is_none() =>
+                {
+                    if let Some(index) = self.selected().or(self.keyboard.focus()) {
+                        self.remove_guess(index);
+                    }
+                    return;
+                }
+                (KeyCode::B, false, _) if self.model.dragging().is_none() => {
+                    self.notebook.assist(&self.model);
+                    self.state = GameState::Notebook;
+                    return;
+                }
+                (KeyCode::Equals | KeyCode::Add, true, _) => {
+                    self.config.zoom(true);
+                    self.save_config();
+                    false
+                }
+                (KeyCode::Minus | KeyCode::Subtract, true, _) => {
+                    self.config.zoom(false);
+                    self.save_config();
+                    false
+                }
+                (KeyCode::Key1, false, _) => self.open_builder(0),
+                (KeyCode::Key2, false, _) => self.open_builder(1),
+                (KeyCode::Key3, false, _) => self.open_builder(2),
+                // Taking back a move would take it from the other player in
+                // a hot-seat game.
+                (KeyCode::Z, true, false) if self.seat.is_none() => self.model.undo(),
+                (KeyCode::Z, true, true) | (KeyCode::Y, true, _) if self.seat.is_none() => {
+                    self.model.redo().is_some()
+                }
+                // Escape goes back to editing a puzzle being tried out, or
+                // pauses, unless it is putting back a guess held with the
+                // keyboard.
+                (KeyCode::Escape, false, _)
+                    if self.editor.is_some()
+                        && !self.keyboard.is_holding()
+                        && self.model.dragging().is_none() =>
+                {
+                    self.open_editor();
+                    return;
+                }
+                (KeyCode::Escape, false, _)
+                    if !self.keyboard.is_holding() && self.model.dragging().is_none() =>
+                {
+                    self.state = GameState::Paused { selected: 0 };
+                    return;
+                }
+                (key, false, shift) if my_turn => self.keyboard.press(key, shift, &mut self.model),
+                _ => false,
+            };
+            // A hint is only good for the board it was given for.
+            if moved {
+                self.hint = None;
+            }
+        }
+        if input.is_hint_clicked {
+            self.hint = self.model.hint();
+        }
+        // A right click takes a guess out of the sandbox.
+        if input.is_home_clicked && sandbox && self.model.dragging().is_none() {
+            if let Some(index) = self.model.guess_at(&self.cursor) {
+                self.remove_guess(index);
+            }
+            return;
+        }
+        // Sending a guess home isn't shared over the network.
+        if input.is_home_clicked
+            && self.link.is_none()
+            && self.config.right_click_home
+            && self.model.send_home(&self.cursor).is_some()
+        {
+            self.hint = None;
+        }
+        // The mouse leaves a guess held with the keyboard alone.
+        if self.keyboard.is_holding() && self.model.dragging().is_some() {
+            return;
+        }
+        if input.is_double_clicked && my_turn && self.auto_place() {
+            return;
+        }
+        // A carried guess follows the cursor until the next click, a held
+        // one until the button is let go.
+        let (pick, drop) = if self.config.click_to_carry {
+            (input.is_mouse_clicked, input.is_mouse_clicked)
+        } else {
+            (input.is_mouse_pressed, !input.is_mouse_pressed)
+        };
+        match self.model.dragging() {
+            None if pick && my_turn && self.model.pick(&self.cursor).is_some() => {
+                self.hint = None;
+                self.keyboard.clear();
+                self.selected = None;
+            }
+            None if input.is_mouse_clicked => {
+                self.selected = None;
+                if let Some(circle) = self.model.answer_at(&self.cursor) {
+                    self.open_builder(circle);
+                }
+            }
+            None => {}
+            // Letting go of a guess without moving it selects it.
+            Some(index) if drop => {
+                let placement = self.model.drop(&self.cursor);
+                if placement.is_none() {
+                    self.selected = Some(index);
+                }
+            }
+            Some(_) => self.model.drag(&self.cursor),
+        }
+    }
+
+    // Takes the guess out of the sandbox. The guesses after it move up the
+    // rail, so whatever is kept by index starts over.
+    fn remove_guess(&mut self, index: usize) {
+        self.model.remove(index);
+        self.hint = None;
+        self.selected = None;
+        self.keyboard.clear();
+        self.trails.clear();
+        self.glides.clear();
+    }
+
+    // Adds a guess of the card clicked on the palette to the sandbox, and
+    // goes back to the board once done.
+    fn add_cards(&mut self, input: &VennInput) {
+        let done = [KeyCode::Escape, KeyCode::P, KeyCode::Return];
+        if input.keys_pressed.iter().any(|key| done.contains(key)) {
+            self.state = GameState::Playing;
+            return;
+        }
+        if !input.is_mouse_clicked {
+            return;
+        }
+        let palette = self.model.palette();
+        match ui::palette_at(&self.cursor, palette.len()) {
+            Some(index) => {
+                self.model.add(palette[index]);
+                self.trails.clear();
+                self.glides.clear();
+            }
+            None => self.state = GameState::Playing,
+        }
+    }
+
+    // In easy games and the tutorial, a double click on a guess moves it to
+    // where it belongs, for a price. Playing with someone else, moves only
+    // count when made by hand. Returns whether a guess was moved.
+    fn auto_place(&mut self) -> bool {
+        let teaching =
+            self.model.options().difficulty == Some(Preset::Easy) || self.tutorial.is_some();
+        if !teaching || self.seat.is_some() || self.link.is_some() {
+            return false;
+        }
+        // The first click picked the guess up, and letting go of it plays
+        // its effect already.
+        let held = self.model.dragging().is_some();
+        let index = match self.model.dragging() {
+            Some(index) => {
+                self.model.drop(&self.cursor);
+                index
+            }
+            None => match self.model.guess_at(&self.cursor) {
+                Some(index) => index,
+                None => return false,
+            },
+        };
+        let from = self.model.guesses()[index].center;
+        if self.model.place_for(index).is_none() {
+            return false;
+        }
+        self.glides.start(index, from);
+        self.hint = None;
+        self.keyboard.clear();
+        if !held {
+            let effect = match self.model.guesses()[index].matches {
+                Some(true) => Effect::Right,
+                Some(false) => Effect::Wrong,
+                None => Effect::Drop,
+            };
+            self.sound_off(effect);
+            self.speak_drop(index);
+        }
+        true
+    }
+
+    // Plays the effect for picking up or putting down a guess, going by
+    // the guess held before the input was handled.
+    fn play_effects(&self, held: Option<usize>) {
+        let effect = match (held, self.model.dragging()) {
+            (None, Some(_)) => Effect::PickUp,
+            (Some(index), None) => match self.model.guesses()[index].matches {
+                Some(true) => Effect::Right,
+                Some(false) => Effect::Wrong,
+                None => Effect::Drop,
+            },
+            _ => return,
+        };
+        self.sound_off(effect);
+    }
+
+    // Reads out the guess under the pointer, or the selected one, when
+    // that changes, and how it went once a guess held before the input was
+    // handled is let go of.
+    fn speak(&mut self, held: Option<usize>) {
+        if !self.config.speech {
+            return;
+        }
+        if let (Some(index), None) = (held, self.model.dragging()) {
+            self.speak_drop(index);
+            return;
+        }
+        let under = match self.model.dragging() {
+            Some(_) => None,
+            None => self
+                .selected()
+                .or_else(|| self.model.guess_at(&self.cursor)),
+        };
+        if under == self.spoken {
+            return;
+        }
+        self.spoken = under;
+        if let Some(index) = under {
+            let text = ui::card_name(&self.model, &self.model.guesses()[index].target);
+            self.speech.say(&text, self.config.language);
+        }
+    }
+
+    // Reads out what the guess is, where it was let go of and whether that
+    // was right.
+    fn speak_drop(&mut self, index: usize) {
+        if !self.config.speech {
+            return;
+        }
+        let guess = &self.model.guesses()[index];
+        let place = self.model.results()[index].place;
+        let card = ui::card_name(&self.model, &guess.target);
+        let text = ui::spoken_drop(&card, place, guess.matches);
+        self.speech.say(&text, self.config.language);
+        self.spoken = Some(index);
+    }
+
+    // With two players, a guess let go of where it turned green or red
+    // counts for the player whose turn it was, going by whether it had been
+    // scored before.
+    fn take_turn(&mut self, index: usize, scored: bool) {
+        let seat = match &mut self.seat {
+            Some(seat) => seat,
+            None => return,
+        };
+        let guess = &self.model.guesses()[index];
+        if let Some(matches) = guess.matches {
+            let first = guess.scored && !scored;
+            seat.place(matches, first, self.model.current_level());
+        }
+    }
+
+    // Marks the value under a click for its circle. A turns the assistant
+    // on or off, B or Escape goes back to the board.
+    fn take_notes(&mut self, input: &VennInput) {
+        if input.keys_pressed.contains(&KeyCode::A) {
+            self.notebook.toggle_assistant(&self.model);
+        }
+        if input.keys_pressed.contains(&KeyCode::B) || input.keys_pressed.contains(&KeyCode::Escape)
+        {
+            self.state = GameState::Playing;
+            return;
+        }
+        if input.is_mouse_clicked {
+            if let Some((circle, value)) = ui::note_at(&self.cursor, &self.model) {
+                self.notebook.cycle(circle, value);
+            }
+        }
+    }
+
+    // Starts building a card for the answer box of the circle, if it can
+    // take one. Returns false, as the board doesn't change yet.
+    fn open_builder(&mut self, circle: usize) -> bool {
+        if self.model.can_answer(circle) {
+            self.state = GameState::Building {
+                builder: Builder::new(circle, &self.model),
+            };
+        }
+        false
+    }
+
+    // Picks chips with the mouse or the arrow keys. Submitting answers the
+    // box with the card or rule, Escape or cancelling goes back to the
+    // board.
+    fn build(&mut self, input: &VennInput, mut builder: Builder) {
+        let rows = builder::rows(&self.model, builder.circle);
+        let mut submit = None;
+        for &key in &input.keys_pressed {
+            match key {
+                KeyCode::Escape => submit = Some(false),
+                KeyCode::Return => submit = Some(true),
+                key => builder.press(key, &rows),
+            }
+        }
+        if input.is_mouse_clicked {
+            if let Some((row, column)) = ui::chip_at(&self.cursor, &rows) {
+                builder.choose(row, rows[row][column]);
+            }
+            submit = submit.or_else(|| ui::builder_button_at(&self.cursor));
+        }
+        self.state = match submit {
+            Some(_) => GameState::Playing,
+            None => GameState::Building { builder },
+        };
+        if submit != Some(true) {
+            return;
+        }
+        self.hint = None;
+        let circle = builder.circle;
+        let answer = if builder.is_naming() {
+            Message::Kind {
+                circle,
+                kind: builder.kind,
+            }
+        } else if builder.is_compound() {
+            match builder.rule(&rows) {
+                Some(rule) => Message::Rule { circle, rule },
+                // Nothing to submit yet.
+                None => {
+                    self.state = GameState::Building { builder };
+                    return;
+                }
+            }
+        } else {
+            Message::Card {
+                circle,
+                card: builder.card,
+            }
+        };
+        let placement = self.answer(&answer);
+        if placement.is_some() {
+            self.send(&answer);
+        }
+        let effect = match placement {
+            Some(Placement::Answer { matches: true, .. }) => Effect::Right,
+            Some(Placement::Answer { matches: false, .. }) => Effect::Wrong,
+            Some(_) => Effect::Drop,
+            None => return,
+        };
+        self.sound_off(effect);
+    }
+
+    // Builds the answer in its box, whichever kind of answer it is.
+    fn answer(&mut self, answer: &Message) -> Option<Placement> {
+        match answer {
+            Message::Card { circle, card } => self.model.answer(*circle, *card),
+            Message::Rule { circle, rule } => self.model.answer_rule(*circle, rule.clone()),
+            Message::Kind { circle, kind } => self.model.answer_kind(*circle, *kind),
+            _ => None,
+        }
+    }
+
+    // Whether the player at this machine may move a guess: always, unless
+    // it is the other player's turn over the network.
+    fn is_my_turn(&self) -> bool {
+        match (&self.seat, &self.link) {
+            (Some(seat), Some(link)) => seat.turn() == link.player(),
+            _ => true,
+        }
+    }
+
+    fn send(&mut self, message: &Message) {
+        if let Some(link) = &mut self.link {
+            if let Err(error) = link.send(message) {
+                warn!("could not send to the other player: {}", error);
+                self.link = None;
+            }
+        }
+    }
+
+    // Plays what the other machine did since the last frame.
+    fn receive(&mut self) {
+        let messages = match self.link.as_mut().map(Link::receive) {
+            Some(Ok(messages)) => messages,
+            Some(Err(error)) => {
+                warn!("lost the connection: {}", error);
+                self.link = None;
+                return;
+            }
+            None => return,
+        };
+        for message in messages {
+            match message {
+                Message::Puzzle(model) => {
+                    self.model = *model;
+                    self.start = self.model.clone();
+                    // Starting it here would send it straight back.
+                    let link = self.link.take();
+                    self.reset();
+                    self.link = link;
+                }
+                Message::Move { guess, to } => {
+                    let scored = match self.model.guesses().get(guess) {
+                        Some(shape) => shape.scored,
+                        None => continue,
+                    };
+                    if self.model.place(guess, &to).is_some() {
+                        self.take_turn(guess, scored);
+                    }
+                }
+                Message::GiveUp => self.model.give_up(),
+                answer => {
+                    self.answer(&answer);
+                }
+            }
+            self.hint = None;
+        }
+    }
+
+    // Moves through the pause menu and acts on the chosen entry.
+    fn choose(&mut self, input: &VennInput, mut selected: usize) {
+        if input.keys_pressed.contains(&KeyCode::Escape) {
+            self.state = GameState::Playing;
+            return;
+        }
+        let chosen = self.navigate(input, &mut selected, MENU.len());
+        self.state = GameState::Paused { selected };
+        if !chosen {
+            return;
+        }
+        match selected {
+            0 => self.state = GameState::Playing,
+            1 => {
+                let level = self.model.level();
+                self.start_round(level);
+            }
+            2 => {
+                let options = *self.model.options();
+                self.rng = StdRng::seed_from_u64(options.seed);
+                self.model = VennModel::new(0, &options, &mut self.rng);
+                self.start = self.model.clone();
+                self.campaign = None;
+                self.round = 0;
+                self.reset();
+            }
+            3 => {
+                self.packs = pack::list();
+                self.state = GameState::Packs {
+                    selected: 0,
+                    pause: selected,
+                };
+            }
+            4 => self.state = GameState::Stats { selected },
+            5 => {
+                self.draft = self.config.clone();
+                self.state = GameState::Settings {
+                    selected: 0,
+                    pause: selected,
+                };
+            }
+            _ => {
+                self.autosave();
+                std::process::exit(0);
+            }
+        }
+    }
+
+    // Moves through the packs and starts the chosen one where it was left
+    // off, or from the start once every puzzle in it is solved. Escape goes
+    // back to the pause menu.
+    fn pick_pack(&mut self, input: &VennInput, mut selected: usize, pause: usize) {
+        let back = GameState::Paused { selected: pause };
+        if input.keys_pressed.contains(&KeyCode::Escape) {
+            self.state = back;
+            return;
+        }
+        let chosen = self.navigate(input, &mut selected, self.packs.len().max(1));
+        self.state = GameState::Packs { selected, pause };
+        if !chosen {
+            return;
+        }
+        if selected >= self.packs.len() {
+            self.state = back;
+            return;
+        }
+        let pack = self.packs.remove(selected);
+        let puzzle = pack.next(&self.progress).unwrap_or(0);
+        self.play_campaign(Campaign { pack, puzzle });
+    }
+
+    // Starts the puzzle of the campaign, keeping the score.
+    fn play_campaign(&mut self, campaign: Campaign) {
+        let puzzle = match campaign.pack.puzzle(campaign.puzzle) {
+            Ok(puzzle) => puzzle,
+            Err(error) => {
+                warn!("could not read pack {}: {}", campaign.pack.name, error);
+                self.state = GameState::Playing;
+                return;
+            }
+        };
+        let score = self.model.score();
+        self.model = VennModel::from_puzzle(&puzzle, self.model.options()).with_score(score);
+        self.start = self.model.clone();
+        self.campaign = Some(campaign);
+        self.reset();
+    }
+
+    fn watch(&mut self, path: &Path) {
+        if let Some(watcher) = &mut self.watcher {
+            if let Err(error) = watcher.watch(path) {
+                warn!("could not watch {}: {}", path.display(), error);
+            }
+        }
+    }
+
+    // Plays the puzzle file again once it is saved, keeping the score, and
+    // applies the settings once they are. A file that doesn't read is left
+    // for the next save.
+    fn reload(&mut self) {
+        let changed = match &self.watcher {
+            Some(watcher) => watcher.changed(),
+            None => return,
+        };
+        // The watcher gives the paths as they are on disk.
+        let canonical = |path: &Option<PathBuf>| path.as_ref().and_then(|p| p.canonicalize().ok());
+        let puzzle = canonical(&SETTINGS.get().unwrap().puzzle);
+        let config = canonical(&self.config_path);
+        for path in changed {
+            if puzzle.as_ref() == Some(&path) {
+                let puzzle = match puzzle::read(&path) {
+                    Ok(puzzle) => puzzle,
+                    Err(error) => {
+                        warn!("could not reload {}: {}", path.display(), error);
+                        continue;
+                    }
+                };
+                eprintln!("venn: reloaded {}", path.display());
+                let score = self.model.score();
+                self.model =
+                    VennModel::from_puzzle(&puzzle, self.model.options()).with_score(score);
+                self.start = self.model.clone();
+                self.campaign = None;
+                self.reset();
+            } else if config.as_ref() == Some(&path) {
+                match config::read(&path) {
+                    Ok(config) => self.apply(config),
+                    Err(error) => {
+                        warn!("could not reload {}: {}", path.display(), error)
+                    }
+                }
+            }
+        }
+    }
+
+    // Whether the editor is showing rather than a puzzle being tried out.
+    fn is_editing(&self) -> bool {
+        matches!(
+            self.state,
+            GameState::Editing | GameState::EditingCard { .. } | GameState::EditingLabel
+        )
+    }
+
+    // Shows the board of the puzzle being edited again.
+    fn open_editor(&mut self) {
+        if let Some(editor) = &self.editor {
+            self.model = editor.model(self.model.options());
+            self.start = self.model.clone();
+            self.reset();
+            self.state = GameState::Editing;
+        }
+    }
+
+    // Drags circles about and changes the puzzle with the keys. Clicking a
+    // guess leaves it out of the puzzle or puts it back.
+    fn edit(&mut self, input: &VennInput) {
+        let editor = match &mut self.editor {
+            Some(editor) => editor,
+            None => return,
+        };
+        let model = &self.model;
+        let mut changed = false;
+        for &key in &input.keys_pressed {
+            match (key, input.is_control_pressed) {
+                (KeyCode::S, true) => match editor.save() {
+                    Ok(()) => eprintln!("venn: saved the puzzle to {}", editor.path().display()),
+                    Err(error) => warn!("could not save to {}: {}", editor.path().display(), error),
+                },
+                (KeyCode::Tab, false) => editor.select_next(),
+                (KeyCode::L, false) => editor.step_level(!input.is_shift_pressed),
+                (KeyCode::R, false) => editor.step_rule(),
+                (KeyCode::K, false) => {
+                    let values = model.current_level().values(model.in_play());
+                    editor.make_compound(values[0][0]);
+                }
+                (KeyCode::Key3, false) => editor.toggle_third(),
+                (KeyCode::Equals, false) | (KeyCode::Add, false) => editor.resize(model, true),
+                (KeyCode::Minus, false) | (KeyCode::Subtract, false) => editor.resize(model, false),
+                (KeyCode::O, false) => editor.step_outline(),
+                (KeyCode::LBracket, false) => editor.turn(false),
+                (KeyCode::RBracket, false) => editor.turn(true),
+                (KeyCode::N, false) => {
+                    let circle = &editor.puzzle.circles[editor.selected];
+                    self.label = circle.label.clone().unwrap_or_default();
+                    self.state = GameState::EditingLabel;
+                    return;
+                }
+                (KeyCode::C, false) => {
+                    let mut builder = Builder::new(editor.selected, model);
+                    builder.card = editor.puzzle.circles[editor.selected].card;
+                    self.state = GameState::EditingCard { builder };
+                    return;
+                }
+                // Tries the puzzle out.
+                (KeyCode::Return, false) => {
+                    self.model = editor.play(model.options());
+                    self.start = self.model.clone();
+                    self.reset();
+                    return;
+                }
+                _ => continue,
+            }
+            changed = true;
+        }
+        // Delete takes the guess under the pointer off the board.
+        let delete = [KeyCode::Delete, KeyCode::Back];
+        if input.keys_pressed.iter().any(|key| delete.contains(key)) {
+            if let Some(index) = model.guess_at(&self.cursor) {
+                editor.remove(model.guesses()[index].target, &model.palette());
+                changed = true;
+            }
+        }
+        if input.is_mouse_clicked {
+            match model.guess_at(&self.cursor) {
+                Some(index) => {
+                    editor.toggle(model.guesses()[index].target, &model.palette());
+                }
+                None => editor.grab(model, &self.cursor),
+            }
+        } else if !input.is_mouse_pressed && editor.dragged(&self.cursor).is_some() {
+            editor.drop(&self.cursor);
+            changed = true;
+        }
+        if changed {
+            self.model = editor.model(model.options());
+        }
+    }
+
+    // Builds the hidden card or compound rule of the circle being edited,
+    // the way an answer is built.
+    fn edit_card(&mut self, input: &VennInput, mut builder: Builder) {
+        let rows = builder::rows(&self.model, builder.circle);
+        let mut submit = None;
+        for &key in &input.keys_pressed {
+            match key {
+                KeyCode::Escape => submit = Some(false),
+                KeyCode::Return => submit = Some(true),
+                key => builder.press(key, &rows),
+            }
+        }
+        if input.is_mouse_clicked {
+            if let Some((row, column)) = ui::chip_at(&self.cursor, &rows) {
+                builder.choose(row, rows[row][column]);
+            }
+            submit = submit.or_else(|| ui::builder_button_at(&self.cursor));
+        }
+        let rule = builder.rule(&rows);
+        self.state = match submit {
+            // A compound rule needs a value.
+            Some(true) if builder.is_compound() && rule.is_none() => {
+                GameState::EditingCard { builder }
+            }
+            Some(_) => GameState::Editing,
+            None => GameState::EditingCard { builder },
+        };
+        if let (Some(true), GameState::Editing, Some(editor)) =
+            (submit, self.state, &mut self.editor)
+        {
+            editor.set_card(builder.card, rule);
+            self.model = editor.model(self.model.options());
+        }
+    }
+
+    // Moves the selection of a menu with the mouse or the arrow keys.
+    // Returns whether the selected entry was chosen.
+    fn navigate(&self, input: &VennInput, selected: &mut usize, count: usize) -> bool {
+        let mut chosen = false;
+        if let Some(hovered) = ui::menu_entry_at(&self.cursor, count) {
+            *selected = hovered;
+            chosen = input.is_mouse_clicked;
+        }
+        for &key in &input.keys_pressed {
+            match key {
+                KeyCode::Up => *selected = (*selected + count - 1) % count,
+                KeyCode::Down | KeyCode::Tab => *selected = (*selected + 1) % count,
+                KeyCode::Return => chosen = true,
+                _ => {}
+            }
+        }
+        chosen
+    }
+
+    // Steps the selected setting with the arrow keys, Enter or a click.
+    // Saving writes the settings to the config file and applies them.
+    fn change_settings(&mut self, input: &VennInput, mut selected: usize, pause: usize) {
+        let back = GameState::Paused { selected: pause };
+        if input.keys_pressed.contains(&KeyCode::Escape) {
+            self.state = back;
+            return;
+        }
+        let chosen = self.navigate(input, &mut selected, CANCEL + 1);
+        self.state = GameState::Settings { selected, pause };
+        for &key in &input.keys_pressed {
+            match key {
+                KeyCode::Left => self.draft.adjust(selected, false),
+                KeyCode::Right => self.draft.adjust(selected, true),
+                _ => {}
+            }
+        }
+        if !chosen {
+            return;
+        }
+        match selected {
+            SAVE => {
+                self.state = back;
+                self.apply(self.draft.clone());
+                self.save_config();
+            }
+            CANCEL => self.state = back,
+            _ => self.draft.adjust(selected, true),
+        }
+    }
+
+    fn save_config(&self) {
+        if let Some(path) = &self.config_path {
+            if let Err(error) = config::write(path, &self.config) {
+                warn!("could not save to {}: {}", path.display(), error);
+            }
+        }
+    }
+
+    // Switches to the settings. A different difficulty starts a new puzzle
+    // at it, and the window size only changes the next time the game starts.
+    fn apply(&mut self, config: Config) {
+        let changed = config.difficulty != self.config.difficulty;
+        self.sound.set_volume(config.volume);
+        locale::set(config.language);
+        input::set_buttons(config.buttons);
+        self.config = config;
+        if changed {
+            let options = Options {
+                difficulty: self.config.difficulty,
+                ..*self.model.options()
+            };
+            let score = self.model.score();
+            self.model =
+                VennModel::new(self.model.level(), &options, &mut self.rng).with_score(score);
+            self.start = self.model.clone();
+            self.reset();
+        }
+    }
+
+    // Moves through the profiles and switches to the chosen one, picking
+    // up its saved game. Escape plays as a guest.
+    fn pick_profile(&mut self, input: &VennInput, mut selected: usize) {
+        if input.keys_pressed.contains(&KeyCode::Escape) {
+            self.state = GameState::Playing;
+            return;
+        }
+        let chosen = self.navigate(input, &mut selected, self.profiles.len() + 1);
+        self.state = GameState::Picking { selected };
+        if !chosen {
+            return;
+        }
+        self.state = GameState::Playing;
+        let name = match self.profiles.get(selected) {
+            Some(name) => name,
+            None => return,
+        };
+        let profile = match Profile::new(name) {
+            Ok(profile) => profile,
+            Err(error) => {
+                warn!("could not use profile {}: {}", name, error);
+                return;
+            }
+        };
+        let path = profile.save_path();
+        let mut resumed = false;
+        if path.exists() {
+            match save::read(&path) {
+                Ok(model) => {
+                    self.rng = StdRng::seed_from_u64(model.options().seed);
+                    self.model = model;
+                    self.start = self.model.clone();
+                    self.upcoming_par = Some(work_out_par(&self.start));
+                    resumed = true;
+                }
+                Err(error) => warn!("could not load {}: {}", path.display(), error),
+            }
+        }
+        // A resumed game brings its own difficulty.
+        let path = profile.config_path();
+        let config = config::read(&path).unwrap_or_else(|error| {
+            warn!("could not read {}: {}", path.display(), error);
+            Config::default()
+        });
+        if resumed {
+            locale::set(config.language);
+            input::set_buttons(config.buttons);
+            self.config = config;
+        } else {
+            self.apply(config);
+        }
+        self.watch(&path);
+        self.config_path = Some(path);
+        self.profile = Some(profile);
+        self.stats = self.read_stats();
+        self.progress = self.read_progress();
+    }
+
+    // Saves to the save file, back to the file the game was loaded from or
+    // to the profile.
+    fn autosave(&self) {
+        let settings = SETTINGS.get().unwrap();
+        let path = match settings.save.as_ref().or(settings.load.as_ref()) {
+            Some(path) => Some(path.clone()),
+            None => self.profile.as_ref().map(Profile::save_path),
+        };
+        if let Some(path) = path {
+            if let Err(error) = save::write(&path, &self.model) {
+                warn!("could not save to {}: {}", path.display(), error);
+            }
+        }
+    }
+
+    // Where the progress through the packs of whoever is playing is kept.
+    fn progress_path(&self) -> Option<PathBuf> {
+        match &self.profile {
+            Some(profile) => Some(profile.progress_path()),
+            None => pack::progress_path(),
+        }
+    }
+
+    // Reads the progress of whoever is playing, starting afresh if it can't
+    // be read.
+    fn read_progress(&self) -> Progress {
+        let path = match self.progress_path() {
+            Some(path) => path,
+            None => return Progress::default(),
+        };
+        pack::read_progress(&path).unwrap_or_else(|error| {
+            warn!("could not read {}: {}", path.display(), error);
+            Progress::default()
+        })
+    }
+
+    // Where the statistics of whoever is playing are kept.
+    fn stats_path(&self) -> Option<PathBuf> {
+        match &self.profile {
+            Some(profile) => Some(profile.stats_path()),
+            None => stats::path(),
+        }
+    }
+
+    fn read_stats(&self) -> Stats {
+        let path = match self.stats_path() {
+            Some(path) => path,
+            None => return Stats::default(),
+        };
+        stats::read(&path).unwrap_or_else(|error| {
+            warn!("could not read {}: {}", path.display(), error);
+            Stats::default()
+        })
+    }
+
+    // The round that just ended as it would go on the leaderboard.
+    fn entry(&self) -> Entry {
+        Entry {
+            name: self.name.trim().to_string(),
+            seconds: self.model.elapsed(),
+            // Nothing dropped means nothing dropped wrong.
+            accuracy: self.model.accuracy().unwrap_or(100),
+        }
+    }
+
+    // Moves on from the results to the leaderboard, asking for a name first
+    // if the round makes it on.
+    fn rank(&mut self) {
+        let board = self.leaderboard.board(self.model.options().difficulty);
+        self.state = if self.model.is_won() && board.qualifies(&self.entry()) {
+            if self.name.is_empty() {
+                if let Some(profile) = &self.profile {
+                    self.name = profile.name.clone();
+                }
+            }
+            GameState::Naming
+        } else {
+            GameState::Leaderboard
+        };
+    }
+
+    // Takes typed letters for the name. Enter puts the round on the
+    // leaderboard, Escape leaves it off.
+    fn type_name(&mut self, input: &VennInput) {
+        for c in input.text.chars() {
+            if !c.is_control() && self.name.chars().count() < NAME_LENGTH {
+                self.name.push(c);
+            }
+        }
+        for &key in &input.keys_pressed {
+            match key {
+                KeyCode::Back => {
+                    self.name.pop();
+                }
+                KeyCode::Return if !self.name.trim().is_empty() => {
+                    let difficulty = self.model.options().difficulty;
+                    self.leaderboard.add(difficulty, self.entry());
+                    if let Some(path) = leaderboard::path() {
+                        if let Err(error) = leaderboard::write(&path, &self.leaderboard) {
+                            warn!("could not save to {}: {}", path.display(), error);
+                        }
+                    }
+                    self.state = GameState::Leaderboard;
+                }
+                KeyCode::Escape => self.state = GameState::Leaderboard,
+                _ => {}
+            }
+        }
+    }
+
+    // Types the label of the circle selected in the editor. Enter gives it
+    // the label, or takes it away if it was all deleted.
+    fn type_label(&mut self, input: &VennInput) {
+        for c in input.text.chars() {
+            if !c.is_control() && self.label.chars().count() < LABEL_LENGTH {
+                self.label.push(c);
+            }
+        }
+        for &key in &input.keys_pressed {
+            match key {
+                KeyCode::Back => {
+                    self.label.pop();
+                }
+                KeyCode::Return => {
+                    if let Some(editor) = &mut self.editor {
+                        editor.set_label(&self.label);
+                        self.model = editor.model(self.model.options());
+                    }
+                    self.state = GameState::Editing;
+                }
+                KeyCode::Escape => self.state = GameState::Editing,
+                _ => {}
+            }
+        }
+    }
+
+    // Adds the round that just ended to the statistics and saves them, and
+    // to the progress through the pack when it was a campaign puzzle.
+    fn record(&mut self) {
+        // Only waits if the round ended before its par was worked out.
+        let par = self.upcoming_par.take().and_then(|par| par.recv().ok());
+        self.par = par.flatten();
+        self.stats.record(&self.model, self.par);
+        if let Some(path) = self.stats_path() {
+            if let Err(error) = stats::write(&path, &self.stats) {
+                warn!("could not save to {}: {}", path.display(), error);
+            }
+        }
+        let campaign = match &self.campaign {
+            Some(campaign) if self.model.is_won() => campaign,
+            _ => return,
+        };
+        let pack = &campaign.pack;
+        self.progress
+            .solve(&pack.id, &pack.entries[campaign.puzzle].file);
+        if let Some(path) = self.progress_path() {
+            if let Err(error) = pack::write_progress(&path, &self.progress) {
+                warn!("could not save to {}: {}", path.display(), error);
+            }
+        }
+    }
+
+    // Writes the round that just ended as SVG, named after its code and
+    // level in the working directory unless a path is given.
+    fn export_svg(&self, path: Option<PathBuf>) {
+        let path = path.unwrap_or_else(|| {
+            let options = self.model.options();
+            PathBuf::from(format!(
+                "venn-{}-{}.svg",
+                code::encode(options.seed, options.difficulty),
+                self.model.level() + 1
+            ))
+        });
+        if let Err(error) = svg::write(&path, &self.model, &self.style()) {
+            warn!("could not export to {}: {}", path.display(), error);
+        }
+    }
+
+    // Starts a new puzzle at the level, keeping the score. A generated
+    // puzzle leaves the campaign.
+    fn start_round(&mut self, level: usize) {
+        self.campaign = None;
+        let score = self.model.score();
+        let options = *self.model.options();
+        self.model = VennModel::new(level, &options, &mut self.rng).with_score(score);
+        self.start = self.model.clone();
+        self.reset();
+    }
+
+    // Clears what belonged to the round before.
+    fn reset(&mut self) {
+        // A recording covers a round from the start.
+        if self.recorder.is_some() {
+            self.stop_recording();
+            self.start_recording();
+        }
+        self.state = GameState::Playing;
+        self.spoken = None;
+        self.round += 1;
+        debug!(
+            "round number={} level={} seed={}",
+            self.round,
+            self.model.level(),
+            self.model.options().seed
+        );
+        self.solved_delay = None;
+        self.upcoming_par = Some(work_out_par(&self.start));
+        self.hint = None;
+        self.keyboard.clear();
+        self.trails.clear();
+        self.glides.clear();
+        self.selected = None;
+        self.notebook.clear();
+        self.stamps.clear();
+        if let Some(seat) = &mut self.seat {
+            seat.new_round();
+        }
+        // The other machine plays the same round.
+        if self.link.is_some() {
+            let puzzle = Message::Puzzle(Box::new(self.model.clone()));
+            self.send(&puzzle);
+        }
+    }
+
+    // Moves on from the results, either to the next puzzle or to the same
+    // one again. The score carries on either way.
+    fn next_round(&mut self, replay: bool) {
+        if replay {
+            self.model = self.start.clone().with_score(self.model.score());
+            self.reset();
+        } else if self.editor.is_some() {
+            self.open_editor();
+        } else if let Some(campaign) = self.campaign.take() {
+            // A campaign goes on with the next open puzzle of the pack,
+            // which is the same one after a loss.
+            match campaign.pack.next(&self.progress) {
+                Some(puzzle) => self.play_campaign(Campaign { puzzle, ..campaign }),
+                None => {
+                    let level = self.model.next_level();
+                    self.start_round(level);
+                }
+            }
+        } else {
+            let level = if self.model.options().adaptive {
+                self.stats.adaptive.next_level(&self.model)
+            } else {
+                self.model.next_level()
+            };
+            self.start_round(level);
+        }
+    }
+}
+
+// Works out the par of the round on another thread, as following the
+// hints through it takes a while.
+fn work_out_par(start: &VennModel) -> Receiver<Option<u32>> {
+    let (sender, par) = mpsc::channel();
+    let start = start.clone();
+    thread::spawn(move || {
+        // Nobody waits for it any more once another round has started.
+        let _ = sender.send(start.par());
+    });
+    par
+}
+
+// Reads the leaderboard, starting afresh if it can't be read.
+fn read_leaderboard() -> Leaderboard {
+    let path = match leaderboard::path() {
+        Some(path) => path,
+        None => return Leaderboard::default(),
+    };
+    leaderboard::read(&path).unwrap_or_else(|error| {
+        warn!("could not read {}: {}", path.display(), error);
+        Leaderboard::default()
+    })
+}
+
+/// How to start the frontend.
+pub struct Settings {
+    /// Used for a new game, a resumed game brings its own.
+    pub options: Options,
+    /// Where to save the game when the window is closed. Defaults to the
+    /// file the game was loaded from.
+    pub save: Option<PathBuf>,
+    /// A saved game to resume.
+    pub load: Option<PathBuf>,
+    /// A puzzle file to start with instead of a generated first level.
+    pub puzzle: Option<PathBuf>,
+    /// The preferences to start with, which apply to new games.
+    pub config: Config,
+    /// Where the settings screen saves the preferences.
+    pub config_path: Option<PathBuf>,
+    /// Whether to walk a new player through the first moves.
+    pub tutorial: bool,
+    /// Whether two players take turns placing guesses.
+    pub hot_seat: bool,
+    /// The port to wait for another machine on, to play against it.
+    pub host: Option<u16>,
+    /// The address of a machine hosting a game to join.
+    pub join: Option<String>,
+    /// Where to write each finished round as SVG, replacing the one before.
+    pub export_svg: Option<PathBuf>,
+    /// Who is playing. Their statistics and game are kept apart from
+    /// everyone else's, and the game picks up where they left off.
+    pub profile: Option<String>,
+    /// A puzzle file to write in the editor instead of playing, which
+    /// doesn't have to exist yet.
+    pub edit: Option<PathBuf>,
+}
+
+/// The colors of the settings, for the loading screen, which comes up
+/// before the game does.
+pub(crate) fn theme() -> Theme {
+    SETTINGS
+        .get()
+        .map_or(Theme::LIGHT, |settings| settings.config.theme.theme())
+}
+
+// Game::load takes no arguments, so run leaves what it needs here.
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+// The resumed game or the puzzle from a file, if not starting afresh.
+static FIRST: Mutex<Option<VennModel>> = Mutex::new(None);
+// The puzzle to edit, if the game was started to edit one.
+static EDITOR: Mutex<Option<Editor>> = Mutex::new(None);
+// The other machine, when playing over the network.
+static LINK: Mutex<Option<Link>> = Mutex::new(None);
+
+/// Opens the game window and plays until it is closed.
+pub fn run(settings: Settings) -> Result<()> {
+    let mut seed = settings.options.seed;
+    let mut load = settings.load.clone();
+    if let Some(name) = &settings.profile {
+        let profile = Profile::new(name)?;
+        profile.create()?;
+        if load.is_none() && settings.puzzle.is_none() && profile.save_path().exists() {
+            load = Some(profile.save_path());
+        }
+    }
+    if let Some(path) = &load {
+        let model = save::read(path)?;
+        seed = model.options().seed;
+        *FIRST.lock().unwrap() = Some(model);
+    } else if let Some(path) = &settings.puzzle {
+        let puzzle = puzzle::read(path)?;
+        *FIRST.lock().unwrap() = Some(VennModel::from_puzzle(&puzzle, &settings.options));
+    } else if let Some(path) = &settings.edit {
+        let editor = Editor::open(path, &settings.options)?;
+        *FIRST.lock().unwrap() = Some(editor.model(&settings.options));
+        *EDITOR.lock().unwrap() = Some(editor);
+    }
+    // The host sends the first round, and whoever joins plays it.
+    if let Some(port) = settings.host {
+        eprintln!("venn: waiting for another player on port {}", port);
+        let mut link = Link::host(port)?;
+        let mut first = FIRST.lock().unwrap();
+        let model = first.get_or_insert_with(|| {
+            VennModel::new(0, &settings.options, &mut StdRng::seed_from_u64(seed))
+        });
+        link.send(&Message::Puzzle(Box::new(model.clone())))?;
+        *LINK.lock().unwrap() = Some(link);
+    } else if let Some(address) = &settings.join {
+        let mut link = Link::join(address)?;
+        match link.wait()? {
+            Message::Puzzle(model) => {
+                seed = model.options().seed;
+                *FIRST.lock().unwrap() = Some(*model);
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the host did not send a puzzle",
+                )
+                .into())
+            }
+        }
+        *LINK.lock().unwrap() = Some(link);
+    }
+    if SETTINGS.set(settings).is_err() {
+        panic!("venn::run can only be called once");
+    }
+    let scale = SETTINGS.get().unwrap().config.window_scale();
+    Venn::run(WindowSettings {
+        title: format!("Venn Deduction (seed {})", seed),
+        size: ((WIDTH * scale) as u32, (HEIGHT * scale) as u32),
+        resizable: false,
+        fullscreen: false,
+    })
+}
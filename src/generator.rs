@@ -0,0 +1,228 @@
+//! Random puzzles that can be solved and deduced.
+
+use crate::{
+    circle::compare,
+    model::Level,
+    region::{Region, Semantics},
+    rule::{Rule, RuleExpr, Secret},
+    shapes::{Value, VennShape, VennTarget},
+};
+use log::debug;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+/// Knobs for how hard a generated puzzle is.
+#[derive(Copy, Clone)]
+pub struct Difficulty {
+    /// How many guesses have to be right in each region of the diagram.
+    pub per_region: usize,
+    /// How many guesses have to belong in no circle at all.
+    pub decoys: usize,
+}
+
+// How many random puzzles to try before giving up on the level.
+const ATTEMPTS: usize = 2000;
+
+/// Picks a rule and a hidden card for each circle, left, right and then
+/// bottom. Levels with compound rules give some circles one of those
+/// instead.
+///
+/// Cards are drawn from the guesses so every answer box can be filled. They
+/// always differ in the attributes the level cares about, and no other card
+/// would make the same guesses match a circle, so the hidden cards can be
+/// deduced from the placements. No two circles pick out the same guesses.
+/// Every region gets enough guesses that belong in it. Returns None if no
+/// such puzzle turns up within a number of attempts, which the level's
+/// tables should make unlikely.
+///
+/// In Set mode each circle gets one value of the same attribute instead.
+pub fn generate(
+    level: &Level,
+    circles: usize,
+    guesses: &[VennTarget],
+    outside: bool,
+    rng: &mut StdRng,
+) -> Option<Vec<Secret>> {
+    let regions = regions(circles, outside);
+    let mut shapes: Vec<VennShape> = Vec::new();
+    for guess in guesses {
+        if !shapes.contains(&guess.shape) {
+            shapes.push(guess.shape);
+        }
+    }
+    if level.set {
+        return sort(level, circles, guesses, &shapes, rng);
+    }
+    let compounds = if level.compound {
+        compounds(level, &shapes)
+    } else {
+        vec![]
+    };
+    for attempt in 0..ATTEMPTS {
+        let puzzle: Vec<Secret> = (0..circles)
+            .map(|_| {
+                if !compounds.is_empty() && rng.gen_bool(0.5) {
+                    return Secret::Compound(compounds[rng.gen_range(0, compounds.len())].clone());
+                }
+                let rule = level.rules[rng.gen_range(0, level.rules.len())];
+                Secret::Card(rule, guesses[rng.gen_range(0, guesses.len())])
+            })
+            .collect();
+        if !distinct(&puzzle, level, guesses) || !deducible(&puzzle, level, guesses) {
+            continue;
+        }
+        let filled = regions
+            .iter()
+            .filter(|&&region| {
+                belonging(&puzzle, level, guesses, region) >= level.difficulty.per_region
+            })
+            .count();
+        let decoys = guesses
+            .iter()
+            .filter(|guess| puzzle.iter().all(|secret| !secret.matches(guess, level)))
+            .count();
+        if decoys >= level.difficulty.decoys && filled == regions.len() {
+            debug!(
+                "generate attempt={} decoys={} puzzle={:?}",
+                attempt, decoys, puzzle
+            );
+            return Some(puzzle);
+        }
+    }
+    debug!("generate gave up after {} attempts", ATTEMPTS);
+    None
+}
+
+// Picks an attribute and a different value of it for each circle, so the
+// circles sort the guesses by it, with enough of the guesses dealt to
+// every circle.
+fn sort(
+    level: &Level,
+    circles: usize,
+    guesses: &[VennTarget],
+    shapes: &[VennShape],
+    rng: &mut StdRng,
+) -> Option<Vec<Secret>> {
+    let attributes: Vec<Vec<Value>> = level
+        .values(shapes)
+        .into_iter()
+        .filter(|values| values.len() >= circles)
+        .collect();
+    if attributes.is_empty() {
+        return None;
+    }
+    for _ in 0..ATTEMPTS {
+        let values = &attributes[rng.gen_range(0, attributes.len())];
+        let puzzle: Vec<Secret> = values
+            .choose_multiple(rng, circles)
+            .map(|&value| Secret::Compound(RuleExpr::Is(value)))
+            .collect();
+        let filled = puzzle.iter().all(|secret| {
+            guesses
+                .iter()
+                .filter(|guess| secret.matches(guess, level))
+                .count()
+                >= level.difficulty.per_region
+        });
+        if filled {
+            debug!("generate set puzzle={:?}", puzzle);
+            return Some(puzzle);
+        }
+    }
+    None
+}
+
+// The regions of a diagram with this many circles that guesses can be
+// right in.
+pub(crate) fn regions(circles: usize, outside: bool) -> Vec<Region> {
+    let mut regions = vec![Region::LeftOnly, Region::RightOnly, Region::Both];
+    if circles == 3 {
+        regions.extend(&[
+            Region::BottomOnly,
+            Region::LeftBottom,
+            Region::RightBottom,
+            Region::All,
+        ]);
+    }
+    if outside {
+        regions.push(Region::Outside);
+    }
+    regions
+}
+
+/// Every compound rule of two values of different attributes the level
+/// cares about, joined by and or by or, and everything except each value
+/// on levels with negated rules. In Set mode, every single value.
+pub(crate) fn compounds(level: &Level, shapes: &[VennShape]) -> Vec<RuleExpr> {
+    let values = level.values(shapes);
+    if level.set {
+        return values.into_iter().flatten().map(RuleExpr::Is).collect();
+    }
+    let mut compounds = Vec::new();
+    for (i, first) in values.iter().enumerate() {
+        for second in &values[i + 1..] {
+            for &a in first {
+                for &b in second {
+                    for &or in &[false, true] {
+                        compounds.extend(RuleExpr::join(&[a, b], or));
+                    }
+                }
+            }
+        }
+    }
+    if level.negated {
+        let negations = values.iter().flatten();
+        compounds.extend(negations.map(|&value| RuleExpr::Not(Box::new(RuleExpr::Is(value)))));
+    }
+    compounds
+}
+
+// How many guesses belong in the region, inside every circle they fit and
+// outside the others. A guess that is also green somewhere else under
+// inclusive semantics still has to end up there to solve the round.
+fn belonging(puzzle: &[Secret], level: &Level, guesses: &[VennTarget], region: Region) -> usize {
+    guesses
+        .iter()
+        .filter(|guess| {
+            let mut matches = [None; 3];
+            for (i, secret) in puzzle.iter().enumerate() {
+                matches[i] = Some(secret.matches(guess, level));
+            }
+            Semantics::Exclusive.evaluate(region, matches)
+        })
+        .count()
+}
+
+// Whether no two cards are the same in the attributes in play, and no
+// compound rule picks out the same guesses as another circle.
+fn distinct(puzzle: &[Secret], level: &Level, guesses: &[VennTarget]) -> bool {
+    puzzle.iter().enumerate().all(|(i, a)| {
+        puzzle[i + 1..].iter().all(|b| match (a, b) {
+            (Secret::Card(_, a), Secret::Card(_, b)) => !Rule::All.evaluate(&compare(a, b, level)),
+            _ => guesses
+                .iter()
+                .any(|guess| a.matches(guess, level) != b.matches(guess, level)),
+        })
+    })
+}
+
+// Whether every circle's card is the only one that makes its rule pick out
+// the guesses it does. A compound rule is answered by any rule that picks
+// out the same guesses, so it always can be.
+fn deducible(puzzle: &[Secret], level: &Level, guesses: &[VennTarget]) -> bool {
+    let picked = |rule: Rule, card: &VennTarget| -> Vec<bool> {
+        guesses
+            .iter()
+            .map(|guess| rule.evaluate(&compare(card, guess, level)))
+            .collect()
+    };
+    puzzle.iter().all(|secret| {
+        let (rule, card) = match *secret {
+            Secret::Card(rule, card) => (rule, card),
+            Secret::Compound(_) => return true,
+        };
+        let expected = picked(rule, &card);
+        guesses.iter().all(|other| {
+            Rule::All.evaluate(&compare(&card, other, level)) || picked(rule, other) != expected
+        })
+    })
+}
@@ -0,0 +1,58 @@
+//! Guesses sliding into the spot they snapped to when dropped. The model
+//! moves a guess there at once, a glide only changes where it is drawn on
+//! the way.
+
+use crate::graphics::Point;
+
+// How many seconds a glide takes.
+const DURATION: f32 = 0.15;
+
+struct Glide {
+    guess: usize,
+    from: Point,
+    // Seconds until the guess is where it snapped to.
+    left: f32,
+}
+
+/// The guesses gliding into place.
+#[derive(Default)]
+pub struct Glides {
+    glides: Vec<Glide>,
+}
+
+impl Glides {
+    /// Starts the guess gliding from where it was let go of.
+    pub fn start(&mut self, guess: usize, from: Point) {
+        self.glides.retain(|glide| glide.guess != guess);
+        self.glides.push(Glide {
+            guess,
+            from,
+            left: DURATION,
+        });
+    }
+
+    /// Moves the glides on by the seconds, ending those that arrived.
+    pub fn update(&mut self, seconds: f32) {
+        for glide in &mut self.glides {
+            glide.left -= seconds;
+        }
+        self.glides.retain(|glide| glide.left > 0.0);
+    }
+
+    /// Where the guess on its way to the point is drawn, the seconds since
+    /// the last update later. Guesses that aren't gliding are drawn there.
+    pub fn at(&self, guess: usize, to: Point, since: f32) -> Point {
+        let glide = match self.glides.iter().find(|glide| glide.guess == guess) {
+            Some(glide) => glide,
+            None => return to,
+        };
+        // Fast at first, slowing down as it arrives.
+        let t = (1.0 - (glide.left - since) / DURATION).clamp(0.0, 1.0);
+        let t = 1.0 - (1.0 - t) * (1.0 - t);
+        glide.from + (to - glide.from) * t
+    }
+
+    pub fn clear(&mut self) {
+        self.glides.clear();
+    }
+}
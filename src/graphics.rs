@@ -0,0 +1,96 @@
+//! The points, colors and shapes the board is drawn with.
+//!
+//! With the coffee feature these are coffee's own, so the coffee frontend
+//! hands them to its meshes as they are. Without it they are copies of them
+//! that need nothing but nalgebra, so the game builds for other frontends
+//! and for the browser.
+
+#[cfg(feature = "coffee")]
+pub use coffee::graphics::{Color, Point, Rectangle, Shape, Vector};
+
+/// A point on the board.
+#[cfg(not(feature = "coffee"))]
+pub type Point = nalgebra::Point2<f32>;
+
+/// A distance and a direction on the board.
+#[cfg(not(feature = "coffee"))]
+pub type Vector = nalgebra::Vector2<f32>;
+
+/// A color with its red, green, blue and alpha from 0 to 1.
+#[cfg(not(feature = "coffee"))]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+#[cfg(not(feature = "coffee"))]
+impl Color {
+    pub const WHITE: Color = Color {
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+        a: 1.0,
+    };
+
+    pub const BLACK: Color = Color {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Color {
+        Color { r, g, b, a }
+    }
+
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> Color {
+        Color {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+            a: 1.0,
+        }
+    }
+
+    pub fn to_rgba(&self) -> [u8; 4] {
+        [
+            (self.r * 255.0).round() as u8,
+            (self.g * 255.0).round() as u8,
+            (self.b * 255.0).round() as u8,
+            (self.a * 255.0).round() as u8,
+        ]
+    }
+}
+
+/// A rectangle by its top left corner and its size.
+#[cfg(not(feature = "coffee"))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Rectangle<T> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T,
+}
+
+/// Something to fill or stroke.
+#[cfg(not(feature = "coffee"))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Rectangle(Rectangle<f32>),
+    Circle {
+        center: Point,
+        radius: f32,
+    },
+    Ellipse {
+        center: Point,
+        horizontal_radius: f32,
+        vertical_radius: f32,
+        rotation: f32,
+    },
+    Polyline {
+        points: Vec<Point>,
+    },
+}
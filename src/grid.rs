@@ -0,0 +1,127 @@
+//! A grid laid over the board that the guesses are sorted into by where they
+//! are, so finding the guess under a point only looks at the few guesses
+//! nearby instead of every one of them.
+
+use crate::{
+    graphics::Point,
+    guess::VennGuess,
+    model::{HEIGHT, WIDTH},
+};
+
+// The width and height of a cell, about the size of a guess.
+const CELL: f32 = 50.0;
+
+/// The guesses each cell of the board overlaps.
+#[derive(Clone)]
+pub struct Grid {
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+    // The cells each guess is in, to take it out again when it moves.
+    spans: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    pub fn new(guesses: &[VennGuess]) -> Grid {
+        let (columns, rows) = (
+            (WIDTH / CELL).ceil() as usize,
+            (HEIGHT / CELL).ceil() as usize,
+        );
+        let mut grid = Grid {
+            columns,
+            rows,
+            cells: vec![Vec::new(); columns * rows],
+            spans: vec![Vec::new(); guesses.len()],
+        };
+        for (index, guess) in guesses.iter().enumerate() {
+            grid.insert(index, guess);
+        }
+        grid
+    }
+
+    // The column and row of the point. Points off the board go in the cells
+    // along its edge.
+    fn cell(&self, x: f32, y: f32) -> (usize, usize) {
+        let column = ((x / CELL).max(0.0) as usize).min(self.columns - 1);
+        let row = ((y / CELL).max(0.0) as usize).min(self.rows - 1);
+        (column, row)
+    }
+
+    fn insert(&mut self, index: usize, guess: &VennGuess) {
+        let (center, radius) = (guess.center, guess.radius);
+        let (left, top) = self.cell(center.x - radius, center.y - radius);
+        let (right, bottom) = self.cell(center.x + radius, center.y + radius);
+        for row in top..=bottom {
+            for column in left..=right {
+                let cell = row * self.columns + column;
+                self.cells[cell].push(index);
+                self.spans[index].push(cell);
+            }
+        }
+    }
+
+    /// Sorts the guess into the cells of where it is now.
+    pub fn moved(&mut self, index: usize, guess: &VennGuess) {
+        for cell in std::mem::take(&mut self.spans[index]) {
+            self.cells[cell].retain(|&i| i != index);
+        }
+        self.insert(index, guess);
+    }
+
+    /// The guesses that may be under the point.
+    pub fn near(&self, point: &Point) -> &[usize] {
+        let (column, row) = self.cell(point.x, point.y);
+        &self.cells[row * self.columns + column]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{
+        VennColor, VennCount, VennFill, VennOrientation, VennPattern, VennShape, VennSize,
+        VennTarget,
+    };
+    use proptest::prelude::*;
+
+    fn guess(x: f32, y: f32) -> VennGuess {
+        let target = VennTarget {
+            color: VennColor::Blue,
+            shape: VennShape::Circle,
+            size: VennSize::Large,
+            pattern: VennPattern::Solid,
+            count: VennCount(1),
+            orientation: VennOrientation::Upright,
+            fill: VennFill::Filled,
+        };
+        VennGuess::new(Point::new(x, y), 20.0, target)
+    }
+
+    proptest! {
+        // The grid finds every guess a point is in, wherever the guesses
+        // were put and moved to.
+        #[test]
+        fn near_finds_every_guess_under_a_point(
+            places in prop::collection::vec((-50.0..WIDTH + 50.0, -50.0..HEIGHT + 50.0), 1..40),
+            moves in prop::collection::vec((0usize..40, 0.0..WIDTH, 0.0..HEIGHT), 0..20),
+            x in -50.0..WIDTH + 50.0,
+            y in -50.0..HEIGHT + 50.0,
+        ) {
+            let mut guesses: Vec<VennGuess> =
+                places.iter().map(|&(x, y)| guess(x, y)).collect();
+            let mut grid = Grid::new(&guesses);
+            for &(index, x, y) in &moves {
+                let index = index % guesses.len();
+                guesses[index].center = Point::new(x, y);
+                grid.moved(index, &guesses[index]);
+            }
+            let point = Point::new(x, y);
+            let near = grid.near(&point);
+            for (index, guess) in guesses.iter().enumerate() {
+                if guess.contains(&point) {
+                    prop_assert!(near.contains(&index));
+                }
+            }
+        }
+    }
+}
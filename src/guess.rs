@@ -0,0 +1,55 @@
+use crate::graphics::Point;
+use crate::shapes::VennTarget;
+use serde::{Deserialize, Serialize};
+
+/// A piece the player drags onto the diagram.
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct VennGuess {
+    #[serde(with = "crate::save::point")]
+    pub center: Point,
+    // Where the guess starts out and returns to when replaced.
+    #[serde(with = "crate::save::point")]
+    pub home: Point,
+    pub radius: f32,
+    pub dragged: bool,
+    pub target: VennTarget,
+    pub matches: Option<bool>,
+    // Points for a correct placement are only awarded once per guess.
+    pub scored: bool,
+    // Where the guess is in the stack of guesses, drawn over those with a
+    // lower one and under those with a higher one.
+    #[serde(default)]
+    pub z: u32,
+    // The name of the token the guess stands for, in a puzzle with its own
+    // cards, which is written on it instead of its shapes being drawn.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl VennGuess {
+    pub fn new(home: Point, radius: f32, target: VennTarget) -> VennGuess {
+        VennGuess {
+            center: home,
+            home,
+            radius,
+            dragged: false,
+            target,
+            matches: None,
+            scored: false,
+            z: 0,
+            name: None,
+        }
+    }
+
+    pub fn drag_to(&mut self, point: &Point) {
+        self.dragged = true;
+        self.center = *point;
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        if nalgebra::distance(point, &self.center) < self.radius {
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,97 @@
+//! Undo and redo for moving guesses around.
+
+use crate::graphics::Point;
+
+/// A finished drag of one guess.
+#[derive(Copy, Clone, Debug)]
+pub struct Move {
+    pub guess: usize,
+    pub from: Point,
+    pub to: Point,
+}
+
+/// The moves made so far, and the ones undone since.
+#[derive(Default, Clone)]
+pub struct History {
+    done: Vec<Move>,
+    undone: Vec<Move>,
+    // The guess being dragged and where it was picked up.
+    started: Option<(usize, Point)>,
+}
+
+impl History {
+    /// Remembers where a guess was when it was picked up.
+    pub fn start(&mut self, guess: usize, from: Point) {
+        self.started = Some((guess, from));
+    }
+
+    /// Records the move of the guess picked up last. A new move means the
+    /// undone ones can't be redone.
+    pub fn finish(&mut self, to: Point) {
+        if let Some((guess, from)) = self.started.take() {
+            self.done.push(Move { guess, from, to });
+            self.undone.clear();
+        }
+    }
+
+    /// The last move, which is now undone.
+    pub fn undo(&mut self) -> Option<Move> {
+        let last = self.done.pop()?;
+        self.undone.push(last);
+        Some(last)
+    }
+
+    /// The last undone move, which is now done again.
+    pub fn redo(&mut self) -> Option<Move> {
+        let next = self.undone.pop()?;
+        self.done.push(next);
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drag(history: &mut History, guess: usize, from: f32, to: f32) {
+        history.start(guess, Point::new(from, 0.0));
+        history.finish(Point::new(to, 0.0));
+    }
+
+    #[test]
+    fn moves_undo_and_redo_in_order() {
+        let mut history = History::default();
+        drag(&mut history, 0, 1.0, 2.0);
+        drag(&mut history, 1, 3.0, 4.0);
+        let last = history.undo().unwrap();
+        assert_eq!((last.guess, last.from.x, last.to.x), (1, 3.0, 4.0));
+        assert_eq!(history.undo().unwrap().guess, 0);
+        assert!(history.undo().is_none());
+        assert_eq!(history.redo().unwrap().guess, 0);
+        assert_eq!(history.redo().unwrap().guess, 1);
+        assert!(history.redo().is_none());
+    }
+
+    #[test]
+    fn a_new_move_forgets_the_undone_ones() {
+        let mut history = History::default();
+        drag(&mut history, 0, 1.0, 2.0);
+        history.undo();
+        drag(&mut history, 2, 5.0, 6.0);
+        assert!(history.redo().is_none());
+        assert_eq!(history.undo().unwrap().guess, 2);
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    fn only_started_drags_are_moves() {
+        let mut history = History::default();
+        history.finish(Point::new(1.0, 1.0));
+        assert!(history.undo().is_none());
+        drag(&mut history, 3, 1.0, 2.0);
+        // Dropped once, so a second drop without a pick up is no move.
+        history.finish(Point::new(9.0, 9.0));
+        assert_eq!(history.undo().unwrap().to.x, 2.0);
+        assert!(history.undo().is_none());
+    }
+}
@@ -0,0 +1,163 @@
+use crate::buttons::{Button, Buttons};
+use coffee::{
+    graphics::Point,
+    input::{keyboard, keyboard::KeyCode, mouse, window, ButtonState, Event, Input},
+};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// How soon and how close to the first click the second one has to come to
+// make a double click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+const DOUBLE_CLICK_DISTANCE: f32 = 6.0;
+
+static BUTTONS: Mutex<Buttons> = Mutex::new(Buttons::USUAL);
+
+/// Switches which button does what.
+pub fn set_buttons(buttons: Buttons) {
+    *BUTTONS.lock().unwrap() = buttons;
+}
+
+// Whether the button given a job is the one coffee saw.
+fn is(button: Button, pressed: mouse::Button) -> bool {
+    matches!(
+        (button, pressed),
+        (Button::Left, mouse::Button::Left)
+            | (Button::Right, mouse::Button::Right)
+            | (Button::Middle, mouse::Button::Middle)
+    )
+}
+
+// Copy of KeyboardAndMouse in order to get access to mouse_pressed
+pub struct VennInput {
+    pub cursor_position: Point,
+    is_cursor_taken: bool,
+    // Whether the drag button is down.
+    pub is_mouse_pressed: bool,
+    // Whether the drag button went down since the last interact.
+    pub is_mouse_clicked: bool,
+    // Whether that was the second click of a double click.
+    pub is_double_clicked: bool,
+    // When and where the drag button last went down.
+    last_click: Option<(Instant, Point)>,
+    // Whether the home button was pressed since the last interact.
+    pub is_home_clicked: bool,
+    // Whether the hint button was pressed since the last interact.
+    pub is_hint_clicked: bool,
+    // Keys pressed since the last interact.
+    pub keys_pressed: Vec<KeyCode>,
+    pub is_control_pressed: bool,
+    pub is_shift_pressed: bool,
+    // Text typed since the last interact.
+    pub text: String,
+    // Whether the window has the keyboard and the mouse.
+    pub is_focused: bool,
+}
+
+impl Input for VennInput {
+    fn new() -> VennInput {
+        VennInput {
+            cursor_position: Point::new(0.0, 0.0),
+            is_cursor_taken: false,
+            is_mouse_pressed: false,
+            is_mouse_clicked: false,
+            is_double_clicked: false,
+            last_click: None,
+            is_home_clicked: false,
+            is_hint_clicked: false,
+            keys_pressed: Vec::new(),
+            is_control_pressed: false,
+            is_shift_pressed: false,
+            text: String::new(),
+            is_focused: true,
+        }
+    }
+
+    fn update(&mut self, event: Event) {
+        match event {
+            Event::Mouse(mouse_event) => match mouse_event {
+                mouse::Event::CursorMoved { x, y } => {
+                    self.cursor_position = Point::new(x, y);
+                }
+                mouse::Event::CursorTaken => {
+                    self.is_cursor_taken = true;
+                }
+                mouse::Event::CursorReturned => {
+                    self.is_cursor_taken = false;
+                }
+                mouse::Event::Input { button, state } => {
+                    let buttons = *BUTTONS.lock().unwrap();
+                    if is(buttons.drag, button) {
+                        self.drag_button(state);
+                    } else if state == ButtonState::Pressed && is(buttons.home, button) {
+                        self.is_home_clicked = !self.is_cursor_taken;
+                    } else if state == ButtonState::Pressed && is(buttons.hint, button) {
+                        self.is_hint_clicked = !self.is_cursor_taken;
+                    }
+                }
+                _ => {}
+            },
+            Event::Keyboard(keyboard::Event::TextEntered { character }) => {
+                self.text.push(character);
+            }
+            Event::Keyboard(keyboard::Event::Input { state, key_code }) => {
+                let pressed = state == ButtonState::Pressed;
+                match key_code {
+                    KeyCode::LControl | KeyCode::RControl => self.is_control_pressed = pressed,
+                    KeyCode::LShift | KeyCode::RShift => self.is_shift_pressed = pressed,
+                    _ if pressed => self.keys_pressed.push(key_code),
+                    _ => {}
+                }
+            }
+            Event::Window(window::Event::Focused) => self.is_focused = true,
+            // Buttons and keys let go of elsewhere are never seen letting go.
+            Event::Window(window::Event::Unfocused) => {
+                self.is_focused = false;
+                self.is_mouse_pressed = false;
+                self.is_control_pressed = false;
+                self.is_shift_pressed = false;
+            }
+            _ => {}
+        }
+    }
+
+    fn clear(&mut self) {
+        self.keys_pressed.clear();
+        self.text.clear();
+        self.is_mouse_clicked = false;
+        self.is_double_clicked = false;
+        self.is_home_clicked = false;
+        self.is_hint_clicked = false;
+    }
+}
+
+impl VennInput {
+    // Follows the drag button going down, which may make a double click,
+    // or up.
+    fn drag_button(&mut self, state: ButtonState) {
+        match state {
+            ButtonState::Pressed => {
+                self.is_mouse_pressed = !self.is_cursor_taken;
+                self.is_mouse_clicked = self.is_mouse_pressed;
+                let now = Instant::now();
+                let point = self.cursor_position;
+                self.is_double_clicked = self.is_mouse_clicked
+                    && self.last_click.is_some_and(|(time, last)| {
+                        now - time <= DOUBLE_CLICK_TIME
+                            && nalgebra::distance(&point, &last) <= DOUBLE_CLICK_DISTANCE
+                    });
+                // A third click starts over.
+                self.last_click = if self.is_double_clicked {
+                    None
+                } else {
+                    Some((now, point))
+                };
+            }
+            ButtonState::Released => {
+                self.is_mouse_pressed = false;
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Playing without a mouse. Tab or the arrow keys move the focus between
+//! guesses and Enter picks the focused one up. The arrow keys then move it
+//! between the parts of the diagram, Enter drops it and Escape puts it back.
+
+use crate::{generator, model::VennModel};
+use coffee::{graphics::Point, input::keyboard::KeyCode};
+
+pub struct Keyboard {
+    focus: Option<usize>,
+    // Whether the guess being dragged was picked up with the keyboard.
+    holding: bool,
+}
+
+impl Keyboard {
+    pub fn new() -> Keyboard {
+        Keyboard {
+            focus: None,
+            holding: false,
+        }
+    }
+
+    /// The guess keys act on, if the keyboard has been used.
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    /// Whether the keyboard is moving a guess around.
+    pub fn is_holding(&self) -> bool {
+        self.holding
+    }
+
+    /// Hands control back to the mouse.
+    pub fn clear(&mut self) {
+        self.focus = None;
+        self.holding = false;
+    }
+
+    /// Acts on a key. Returns whether the board changed.
+    pub fn press(&mut self, key: KeyCode, shift: bool, model: &mut VennModel) -> bool {
+        if self.holding {
+            return self.move_held(key, model);
+        }
+        // The mouse is busy.
+        if model.dragging().is_some() {
+            return false;
+        }
+        // The sandbox can be empty.
+        let count = model.guesses().len();
+        if count == 0 {
+            return false;
+        }
+        let step = match (key, shift) {
+            (KeyCode::Tab, false) | (KeyCode::Right, _) | (KeyCode::Down, _) => 1,
+            (KeyCode::Tab, true) | (KeyCode::Left, _) | (KeyCode::Up, _) => count - 1,
+            (KeyCode::Return, _) => {
+                if let Some(index) = self.focus {
+                    self.holding = model.pick_guess(index).is_some();
+                }
+                return self.holding;
+            }
+            _ => return false,
+        };
+        self.focus = Some(match self.focus {
+            Some(index) => (index + step) % count,
+            None => 0,
+        });
+        false
+    }
+
+    fn move_held(&mut self, key: KeyCode, model: &mut VennModel) -> bool {
+        let index = match model.dragging() {
+            Some(index) => index,
+            // The round ended while holding it.
+            None => {
+                self.holding = false;
+                return false;
+            }
+        };
+        let guess = &model.guesses()[index];
+        let at = guess.center;
+        let direction = match key {
+            KeyCode::Left => (-1.0, 0.0),
+            KeyCode::Right => (1.0, 0.0),
+            KeyCode::Up => (0.0, -1.0),
+            KeyCode::Down => (0.0, 1.0),
+            KeyCode::Return => {
+                self.holding = false;
+                model.drop(&at);
+                return true;
+            }
+            KeyCode::Escape => {
+                self.holding = false;
+                let home = guess.home;
+                model.drag(&home);
+                model.drop(&home);
+                return true;
+            }
+            _ => return false,
+        };
+        if let Some(spot) = next_spot(model, index, at, direction) {
+            model.drag(&spot);
+        }
+        true
+    }
+}
+
+// The closest place to drop the guess in the direction, favouring ones
+// straight ahead.
+fn next_spot(model: &VennModel, index: usize, at: Point, (dx, dy): (f32, f32)) -> Option<Point> {
+    let circles = model.circles().count();
+    let mut spots: Vec<Point> = generator::regions(circles, model.options().outside)
+        .into_iter()
+        .filter_map(|region| model.region_center(region))
+        .collect();
+    spots.push(model.guesses()[index].home);
+    spots
+        .into_iter()
+        .filter_map(|spot| {
+            let (x, y) = (spot.x - at.x, spot.y - at.y);
+            let ahead = x * dx + y * dy;
+            let aside = (x * dy - y * dx).abs();
+            if ahead <= 1.0 {
+                return None;
+            }
+            Some((ahead + aside * 2.0, spot))
+        })
+        .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+        .map(|(_, spot)| spot)
+}
@@ -0,0 +1,78 @@
+//! Meshes kept from one frame to the next. Most of the board stays still
+//! most of the time, so it is only tessellated again when it changes, and
+//! the guesses, of which there can be dozens, are tessellated once for each
+//! way a guess can look.
+
+use coffee::graphics::{Mesh, Point, Target, Transformation, Vector};
+
+/// A mesh drawn again only when what it is drawn from, the key, changes.
+pub struct Layer<K> {
+    key: Option<K>,
+    mesh: Mesh,
+}
+
+impl<K> Default for Layer<K> {
+    fn default() -> Layer<K> {
+        Layer {
+            key: None,
+            mesh: Mesh::new(),
+        }
+    }
+}
+
+impl<K: PartialEq> Layer<K> {
+    /// Draws the layer on the target, filling a new mesh with the function
+    /// first if the key differs from the last frame's.
+    pub fn draw(&mut self, key: K, target: &mut Target<'_>, fill: impl FnOnce(&mut Mesh)) {
+        if self.key.as_ref() != Some(&key) {
+            let mut mesh = Mesh::new();
+            fill(&mut mesh);
+            self.mesh = mesh;
+            self.key = Some(key);
+        }
+        if !self.mesh.is_empty() {
+            self.mesh.draw(target);
+        }
+    }
+}
+
+/// Meshes of things drawn many times over, each made once at the origin and
+/// moved to where it is drawn.
+pub struct Stamps<K> {
+    meshes: Vec<(K, Mesh)>,
+}
+
+impl<K> Default for Stamps<K> {
+    fn default() -> Stamps<K> {
+        Stamps { meshes: Vec::new() }
+    }
+}
+
+impl<K: PartialEq> Stamps<K> {
+    /// Draws the stamp for the key centered on the point, filling its mesh
+    /// around the origin with the function first if there is none yet.
+    pub fn draw(
+        &mut self,
+        key: K,
+        at: Point,
+        target: &mut Target<'_>,
+        fill: impl FnOnce(&mut Mesh),
+    ) {
+        let index = match self.meshes.iter().position(|(k, _)| *k == key) {
+            Some(index) => index,
+            None => {
+                let mut mesh = Mesh::new();
+                fill(&mut mesh);
+                self.meshes.push((key, mesh));
+                self.meshes.len() - 1
+            }
+        };
+        let target = &mut target.transform(Transformation::translate(Vector::new(at.x, at.y)));
+        self.meshes[index].1.draw(target);
+    }
+
+    /// Forgets every stamp, for when what is drawn won't come back.
+    pub fn clear(&mut self) {
+        self.meshes.clear();
+    }
+}
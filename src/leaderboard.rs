@@ -0,0 +1,187 @@
+//! The best rounds played on this machine, kept for each difficulty in the
+//! data directory.
+
+use crate::{model::Preset, profile};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// How many rounds each list keeps.
+const PLACES: usize = 10;
+
+/// A round that made it onto a list.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub seconds: f32,
+    /// The share of guesses that turned green where they were dropped, in
+    /// percent.
+    pub accuracy: u32,
+}
+
+/// The best rounds played at one difficulty.
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Board {
+    /// Quickest first.
+    pub fastest: Vec<Entry>,
+    /// Most accurate first, the quicker first when they are as accurate.
+    pub accurate: Vec<Entry>,
+}
+
+impl Board {
+    // Where the entry would go in each list, if it makes it onto them.
+    fn places(&self, entry: &Entry) -> (Option<usize>, Option<usize>) {
+        let fastest = self
+            .fastest
+            .iter()
+            .position(|other| entry.seconds < other.seconds)
+            .unwrap_or(self.fastest.len());
+        let accurate = self
+            .accurate
+            .iter()
+            .position(|other| (entry.accuracy, -entry.seconds) > (other.accuracy, -other.seconds))
+            .unwrap_or(self.accurate.len());
+        (
+            Some(fastest).filter(|&place| place < PLACES),
+            Some(accurate).filter(|&place| place < PLACES),
+        )
+    }
+
+    /// Whether the round would make it onto either list.
+    pub fn qualifies(&self, entry: &Entry) -> bool {
+        self.places(entry) != (None, None)
+    }
+
+    /// Puts the round on the lists it makes it onto.
+    pub fn add(&mut self, entry: Entry) {
+        let (fastest, accurate) = self.places(&entry);
+        if let Some(place) = fastest {
+            self.fastest.insert(place, entry.clone());
+            self.fastest.truncate(PLACES);
+        }
+        if let Some(place) = accurate {
+            self.accurate.insert(place, entry);
+            self.accurate.truncate(PLACES);
+        }
+    }
+}
+
+/// The lists of every difficulty, by its name.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Leaderboard {
+    boards: BTreeMap<String, Board>,
+}
+
+impl Leaderboard {
+    pub fn board(&self, difficulty: Option<Preset>) -> Board {
+        self.boards
+            .get(name(difficulty))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn add(&mut self, difficulty: Option<Preset>, entry: Entry) {
+        self.boards
+            .entry(name(difficulty).to_string())
+            .or_default()
+            .add(entry);
+    }
+}
+
+/// What a difficulty is called, the levels if there is no preset.
+pub fn name(difficulty: Option<Preset>) -> &'static str {
+    match difficulty {
+        None => "levels",
+        Some(Preset::Easy) => "easy",
+        Some(Preset::Medium) => "medium",
+        Some(Preset::Hard) => "hard",
+    }
+}
+
+/// Where the leaderboard is kept, shared by every profile, if there is a
+/// data directory to keep it in.
+pub fn path() -> Option<PathBuf> {
+    profile::root().map(|root| root.join("leaderboard.json"))
+}
+
+/// Reads the leaderboard, which starts out empty if there is none yet.
+pub fn read(path: &Path) -> io::Result<Leaderboard> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(serde_json::from_str(&contents)?),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Leaderboard::default()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Writes the leaderboard, making the directory for it if needed.
+pub fn write(path: &Path, leaderboard: &Leaderboard) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(leaderboard)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, seconds: f32, accuracy: u32) -> Entry {
+        Entry {
+            name: name.to_string(),
+            seconds,
+            accuracy,
+        }
+    }
+
+    fn names(entries: &[Entry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn lists_keep_the_best_in_order() {
+        let mut board = Board::default();
+        board.add(entry("slow", 90.0, 100));
+        board.add(entry("fast", 30.0, 60));
+        board.add(entry("steady", 60.0, 100));
+        assert_eq!(names(&board.fastest), ["fast", "steady", "slow"]);
+        // As accurate, the quicker goes first.
+        assert_eq!(names(&board.accurate), ["steady", "slow", "fast"]);
+    }
+
+    #[test]
+    fn a_full_list_only_takes_better_rounds() {
+        let mut board = Board::default();
+        for i in 0..PLACES {
+            board.add(entry(&i.to_string(), 10.0 + i as f32, 90));
+        }
+        let worse = entry("worse", 100.0, 50);
+        assert!(!board.qualifies(&worse));
+        let faster = entry("faster", 1.0, 50);
+        assert!(board.qualifies(&faster));
+        board.add(faster);
+        assert_eq!(board.fastest.len(), PLACES);
+        assert_eq!(board.fastest[0].name, "faster");
+        assert_eq!(board.fastest[PLACES - 1].name, (PLACES - 2).to_string());
+        assert!(!names(&board.accurate).contains(&"faster"));
+    }
+
+    #[test]
+    fn difficulties_have_lists_of_their_own() {
+        let path =
+            std::env::temp_dir().join(format!("venn-leaderboard-{}.json", std::process::id()));
+        let mut leaderboard = read(&path).unwrap();
+        leaderboard.add(None, entry("levels", 20.0, 80));
+        leaderboard.add(Some(Preset::Hard), entry("hard", 40.0, 70));
+        write(&path, &leaderboard).unwrap();
+        let read = read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(names(&read.board(None).fastest), ["levels"]);
+        assert_eq!(names(&read.board(Some(Preset::Hard)).accurate), ["hard"]);
+        assert!(read.board(Some(Preset::Easy)).fastest.is_empty());
+    }
+}
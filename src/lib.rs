@@ -0,0 +1,95 @@
+//! Venn diagram based game of deduction.
+//!
+//! [`VennModel`] holds the rules and the state of a puzzle and can be played
+//! without opening a window, for tests, simulations or other frontends.
+//! [`run`] starts the coffee frontend, which comes with the `coffee`
+//! feature. Without it the game builds for the browser.
+
+// Much of the crate is only there for the window, and goes unused without it.
+#![cfg_attr(not(feature = "coffee"), allow(dead_code))]
+
+mod adaptive;
+#[cfg(feature = "coffee")]
+mod assets;
+pub mod autoplay;
+pub mod backend;
+#[cfg(feature = "coffee")]
+mod builder;
+mod buttons;
+mod circle;
+pub mod code;
+mod colors;
+pub mod config;
+mod cursor;
+mod diagnostics;
+mod editor;
+#[cfg(feature = "coffee")]
+mod game;
+mod generator;
+mod glide;
+pub mod graphics;
+mod grid;
+mod guess;
+mod history;
+#[cfg(feature = "coffee")]
+mod input;
+#[cfg(feature = "coffee")]
+mod keyboard;
+#[cfg(feature = "coffee")]
+mod layer;
+pub mod leaderboard;
+pub mod locale;
+pub mod logging;
+mod model;
+mod net;
+mod notebook;
+pub mod pack;
+mod palette;
+pub mod profile;
+pub mod puzzle;
+mod rail;
+mod recorder;
+mod region;
+mod render;
+mod rule;
+pub mod save;
+mod screenshot;
+mod seat;
+mod shapes;
+pub mod solver;
+#[cfg(feature = "coffee")]
+mod sound;
+mod speech;
+pub mod stats;
+mod svg;
+mod theme;
+pub mod tokens;
+mod trail;
+mod tutorial;
+#[cfg(feature = "coffee")]
+mod ui;
+#[cfg(feature = "coffee")]
+mod watch;
+mod words;
+
+pub use buttons::{Button, Buttons};
+#[cfg(feature = "coffee")]
+pub use game::{run, Settings};
+pub use generator::Difficulty;
+pub use guess::VennGuess;
+pub use model::{
+    GuessResult, Mode, Options, Place, Placement, Preset, Score, Scoring, VennModel, HEIGHT,
+    LEVEL_COUNT, WIDTH,
+};
+pub use palette::{Palette, Style};
+pub use region::{
+    classify, in_box, in_circle, in_ellipse, in_rounded, Boundary, Outline, Region, Semantics,
+};
+pub use render::Canvas;
+pub use rule::{Rule, RuleExpr, Secret};
+pub use shapes::{
+    Attribute, Kind, Value, VennColor, VennCount, VennFill, VennOrientation, VennPattern,
+    VennShape, VennSize, VennTarget,
+};
+pub use solver::Hint;
+pub use theme::{Theme, ThemeName};
@@ -0,0 +1,157 @@
+//! The words the game shows, in the languages it speaks. Each language is
+//! a bundle of `key = value` lines under `resources/locale`, built into the
+//! program. Values take their arguments as `{0}`, `{1}` and so on, so a
+//! language can put them in its own order.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+/// A language the game can be played in.
+#[derive(PartialEq, Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    /// The language's name in itself, so it can be found whatever the game
+    /// is showing.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    /// The ISO 639-1 code of the language, for speech.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../resources/locale/en.txt"),
+            Language::Spanish => include_str!("../resources/locale/es.txt"),
+        }
+    }
+}
+
+type Bundle = HashMap<&'static str, String>;
+
+static LANGUAGE: Mutex<Language> = Mutex::new(Language::English);
+static BUNDLES: OnceLock<Vec<Bundle>> = OnceLock::new();
+static WORDS: Mutex<Vec<(&'static str, &'static str)>> = Mutex::new(Vec::new());
+
+/// Switches what the game shows to the language.
+pub fn set(language: Language) {
+    *LANGUAGE.lock().unwrap() = language;
+}
+
+pub fn current() -> Language {
+    *LANGUAGE.lock().unwrap()
+}
+
+/// Shows the words instead of the texts of their keys in every language,
+/// until renamed again, like the values of a puzzle's own cards. No words
+/// go back to the texts of the language.
+pub fn rename(words: &[(&'static str, String)]) {
+    let mut current = WORDS.lock().unwrap();
+    let same = current.len() == words.len()
+        && current
+            .iter()
+            .zip(words)
+            .all(|(&(key, word), (other, new))| key == *other && word == new);
+    if same {
+        return;
+    }
+    // The words stay around like the bundles, as texts are handed out for
+    // as long as the game runs.
+    *current = words
+        .iter()
+        .map(|(key, word)| (*key, &*Box::leak(word.clone().into_boxed_str())))
+        .collect();
+}
+
+/// The text of the key in the current language, or in English if it has
+/// no translation yet, or the key itself if there is no such text.
+pub fn text(key: &'static str) -> &'static str {
+    let words = WORDS.lock().unwrap();
+    if let Some(&(_, word)) = words.iter().find(|&&(k, _)| k == key) {
+        return word;
+    }
+    drop(words);
+    lookup(current(), key)
+        .or_else(|| lookup(Language::English, key))
+        .unwrap_or(key)
+}
+
+/// The text of the key with its arguments filled in.
+pub fn fill(key: &'static str, args: &[&dyn ToString]) -> String {
+    let mut text = text(key).to_string();
+    for (i, arg) in args.iter().enumerate() {
+        text = text.replace(&format!("{{{}}}", i), &arg.to_string());
+    }
+    text
+}
+
+fn lookup(language: Language, key: &str) -> Option<&'static str> {
+    let bundles = BUNDLES.get_or_init(|| Language::ALL.iter().map(|l| parse(l.source())).collect());
+    let index = Language::ALL.iter().position(|&l| l == language)?;
+    bundles[index].get(key).map(String::as_str)
+}
+
+// Reads a bundle. Lines starting with # are comments, and \n in a value
+// starts a new line.
+fn parse(source: &'static str) -> Bundle {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim().replace("\\n", "\n")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every language has every text, with the same arguments.
+    #[test]
+    fn bundles_match_english() {
+        let placeholders = |text: &str| {
+            let mut found: Vec<String> = (0..10)
+                .map(|i| format!("{{{}}}", i))
+                .filter(|p| text.contains(p.as_str()))
+                .collect();
+            found.sort();
+            found
+        };
+        let english = parse(Language::English.source());
+        for language in Language::ALL {
+            let bundle = parse(language.source());
+            assert_eq!(bundle.len(), english.len(), "{:?}", language);
+            for (key, text) in &english {
+                let translated = bundle
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{:?} {}", language, key));
+                assert_eq!(
+                    placeholders(translated),
+                    placeholders(text),
+                    "{:?} {}",
+                    language,
+                    key
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+//! Logging what happens in a game, to find out why a guess turned out the
+//! way it did. Events are logged with their details as `key=value` pairs.
+
+use log::LevelFilter;
+
+/// Logs warnings to stderr, and with verbose every pick, drop, answer and
+/// generated puzzle too. `RUST_LOG` overrides both, like
+/// `RUST_LOG=venn=debug`.
+pub fn init(verbose: bool) {
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(LevelFilter::Warn);
+    if verbose {
+        builder.filter_module("venn", LevelFilter::Debug);
+    }
+    builder.parse_default_env().init();
+}
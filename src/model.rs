@@ -0,0 +1,2646 @@
+use crate::{
+    autoplay,
+    circle::{VennAnswer, VennSet},
+    generator::{self, Difficulty},
+    graphics::{Point, Vector},
+    grid::Grid,
+    guess::VennGuess,
+    history::History,
+    puzzle::Puzzle,
+    rail::Rail,
+    region::{self, Boundary, Outline, Region, Semantics},
+    rule::{Rule, RuleExpr, Secret},
+    shapes::{
+        Attribute, Kind, Value, VennColor, VennCount, VennFill, VennOrientation, VennPattern,
+        VennShape, VennSize, VennTarget,
+    },
+    solver::{Hint, Observation, Solver},
+    tokens::Tokens,
+};
+use log::debug;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+
+pub const WIDTH: f32 = 800.0;
+pub const HEIGHT: f32 = 600.0;
+
+// How far a guess has to be dragged before it leaves its place, so a click
+// doesn't move it.
+const PICK_UP_DISTANCE: f32 = 4.0;
+
+// The room left between guesses that snapped into a circle next to each
+// other.
+const SNAP_GAP: f32 = 4.0;
+
+// The share of the overlap of two guesses in the diagram they are nudged
+// apart by every sixtieth of a second.
+const NUDGE: f32 = 0.2;
+
+// The size of an answer box at the usual scale.
+const ANSWER_WIDTH: f32 = 100.0;
+const ANSWER_HEIGHT: f32 = 80.0;
+
+// The furthest the diagram shrinks to make room for bigger guesses.
+const MIN_ZOOM: f32 = 0.3;
+
+// A puzzle in the progression. Later levels bring in more attributes, more
+// guesses and stricter circle rules.
+pub struct Level {
+    // Whether the circles care about color or only about shape.
+    pub color: bool,
+    // How many colors the guesses come in.
+    pub colors: usize,
+    // Whether the circles care about size.
+    pub size: bool,
+    // How many sizes the guesses come in.
+    pub sizes: usize,
+    // The shapes the guesses and hidden cards come in.
+    pub shapes: &'static [VennShape],
+    // Whether the circles care about how shapes are filled in.
+    pub pattern: bool,
+    // How many patterns the guesses come in.
+    pub patterns: usize,
+    // Whether the circles care about how many copies of the shape there are.
+    pub count: bool,
+    // How many different counts the guesses come in.
+    pub counts: usize,
+    // Whether the circles care about which way up shapes stand.
+    pub orientation: bool,
+    // How many orientations the shapes that turn come in.
+    pub orientations: usize,
+    // Whether the circles care about shapes being filled in or outlined.
+    pub fill: bool,
+    // How many fills the guesses come in.
+    pub fills: usize,
+    // The rules circles may be given, one is picked for each circle.
+    pub rules: &'static [Rule],
+    // What a guess has to satisfy to be right in a region.
+    pub regions: Semantics,
+    pub score: ScoreRules,
+    // What the puzzle generator aims for.
+    pub difficulty: Difficulty,
+    // Whether the hidden cards are shown from the start.
+    pub visible: bool,
+    // Whether circles may have a compound rule of two values instead of a
+    // hidden card, like "blue and triangle".
+    pub compound: bool,
+    // Whether a compound rule may be everything except a value, like
+    // "everything except blue".
+    pub negated: bool,
+    // Whether this is Set mode: a hand of the cards is dealt and each
+    // circle holds one value of the same hidden attribute.
+    pub set: bool,
+}
+
+impl Level {
+    // The values the cards can have in each attribute the level cares
+    // about, shape first.
+    pub(crate) fn values(&self, shapes: &[VennShape]) -> Vec<Vec<Value>> {
+        fn take<A: Attribute>(count: usize, value: fn(A) -> Value) -> Vec<Value> {
+            A::variants()
+                .iter()
+                .take(count)
+                .map(|&a| value(a))
+                .collect()
+        }
+        let mut values = vec![shapes.iter().map(|&s| Value::Shape(s)).collect()];
+        if self.color {
+            values.push(take(self.colors, Value::Color));
+        }
+        if self.size {
+            values.push(take(self.sizes, Value::Size));
+        }
+        if self.pattern {
+            values.push(take(self.patterns, Value::Pattern));
+        }
+        if self.count {
+            values.push(take(self.counts, Value::Count));
+        }
+        if self.orientation {
+            values.push(take(self.orientations, Value::Orientation));
+        }
+        if self.fill {
+            values.push(take(self.fills, Value::Fill));
+        }
+        values
+    }
+}
+
+// The shapes the game started out with, which the levels stick to.
+const CLASSIC_SHAPES: &[VennShape] = &[VennShape::Circle, VennShape::Square, VennShape::Triangle];
+
+// The first levels add a diamond, so there are kinds of guess enough for
+// every region of three circles and the outside.
+const FIRST_SHAPES: &[VennShape] = &[
+    VennShape::Circle,
+    VennShape::Square,
+    VennShape::Triangle,
+    VennShape::Diamond,
+];
+
+/// How many levels the progression has.
+pub const LEVEL_COUNT: usize = LEVELS.len();
+
+pub const LEVELS: [Level; 10] = [
+    Level {
+        color: true,
+        colors: 3,
+        size: false,
+        sizes: 1,
+        shapes: FIRST_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: false,
+        counts: 1,
+        orientation: false,
+        orientations: 1,
+        fill: false,
+        fills: 1,
+        rules: &[Rule::Any],
+        regions: Semantics::Inclusive,
+        score: ScoreRules {
+            correct: 10,
+            incorrect: 2,
+            streak_bonus: 1,
+        },
+        difficulty: Difficulty {
+            per_region: 1,
+            decoys: 0,
+        },
+        visible: false,
+        compound: false,
+        negated: false,
+        set: false,
+    },
+    Level {
+        color: true,
+        colors: 3,
+        size: false,
+        sizes: 2,
+        shapes: FIRST_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: false,
+        counts: 1,
+        orientation: false,
+        orientations: 1,
+        fill: false,
+        fills: 1,
+        rules: &[Rule::Any],
+        regions: Semantics::Inclusive,
+        score: ScoreRules {
+            correct: 10,
+            incorrect: 3,
+            streak_bonus: 2,
+        },
+        difficulty: Difficulty {
+            per_region: 2,
+            decoys: 0,
+        },
+        visible: false,
+        compound: false,
+        negated: false,
+        set: false,
+    },
+    Level {
+        color: true,
+        colors: 3,
+        size: false,
+        sizes: 1,
+        shapes: FIRST_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: false,
+        counts: 1,
+        orientation: false,
+        orientations: 1,
+        fill: false,
+        fills: 1,
+        rules: &[Rule::Any, Rule::Not],
+        regions: Semantics::Inclusive,
+        score: ScoreRules {
+            correct: 10,
+            incorrect: 4,
+            streak_bonus: 2,
+        },
+        difficulty: Difficulty {
+            per_region: 1,
+            decoys: 0,
+        },
+        visible: false,
+        compound: false,
+        negated: false,
+        set: false,
+    },
+    Level {
+        color: true,
+        colors: 3,
+        size: false,
+        sizes: 1,
+        shapes: FIRST_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: false,
+        counts: 1,
+        orientation: false,
+        orientations: 1,
+        fill: false,
+        fills: 1,
+        rules: &[Rule::Any, Rule::All],
+        regions: Semantics::Exclusive,
+        score: ScoreRules {
+            correct: 15,
+            incorrect: 5,
+            streak_bonus: 3,
+        },
+        difficulty: Difficulty {
+            per_region: 1,
+            decoys: 1,
+        },
+        visible: false,
+        compound: false,
+        negated: false,
+        set: false,
+    },
+    Level {
+        color: true,
+        colors: 3,
+        size: true,
+        sizes: 3,
+        shapes: CLASSIC_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: false,
+        counts: 1,
+        orientation: false,
+        orientations: 1,
+        fill: false,
+        fills: 1,
+        rules: &[Rule::Any],
+        regions: Semantics::Exclusive,
+        score: ScoreRules {
+            correct: 15,
+            incorrect: 5,
+            streak_bonus: 3,
+        },
+        difficulty: Difficulty {
+            per_region: 1,
+            decoys: 1,
+        },
+        visible: false,
+        compound: false,
+        negated: false,
+        set: false,
+    },
+    Level {
+        color: true,
+        colors: 3,
+        size: true,
+        sizes: 3,
+        shapes: CLASSIC_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: false,
+        counts: 1,
+        orientation: false,
+        orientations: 1,
+        fill: false,
+        fills: 1,
+        rules: &[Rule::All, Rule::Exactly(2)],
+        regions: Semantics::Exclusive,
+        score: ScoreRules {
+            correct: 20,
+            incorrect: 6,
+            streak_bonus: 4,
+        },
+        difficulty: Difficulty {
+            per_region: 1,
+            decoys: 2,
+        },
+        visible: false,
+        compound: false,
+        negated: false,
+        set: false,
+    },
+    Level {
+        color: true,
+        colors: 3,
+        size: true,
+        sizes: 3,
+        shapes: CLASSIC_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: false,
+        counts: 1,
+        orientation: false,
+        orientations: 1,
+        fill: false,
+        fills: 1,
+        rules: &[Rule::Any, Rule::All, Rule::Not, Rule::Exactly(2)],
+        regions: Semantics::Exclusive,
+        score: ScoreRules {
+            correct: 20,
+            incorrect: 6,
+            streak_bonus: 4,
+        },
+        difficulty: Difficulty {
+            per_region: 1,
+            decoys: 2,
+        },
+        visible: false,
+        compound: false,
+        negated: false,
+        set: false,
+    },
+    Level {
+        color: true,
+        colors: 3,
+        size: false,
+        sizes: 1,
+        shapes: CLASSIC_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: true,
+        counts: 3,
+        orientation: false,
+        orientations: 1,
+        fill: false,
+        fills: 1,
+        rules: &[Rule::Any, Rule::All, Rule::Not, Rule::Exactly(2)],
+        regions: Semantics::Exclusive,
+        score: ScoreRules {
+            correct: 25,
+            incorrect: 8,
+            streak_bonus: 5,
+        },
+        difficulty: Difficulty {
+            per_region: 1,
+            decoys: 2,
+        },
+        visible: false,
+        compound: true,
+        negated: true,
+        set: false,
+    },
+    Level {
+        color: true,
+        colors: 3,
+        size: false,
+        sizes: 1,
+        shapes: CLASSIC_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: false,
+        counts: 1,
+        orientation: true,
+        orientations: 2,
+        fill: false,
+        fills: 1,
+        rules: &[Rule::Any, Rule::All, Rule::Not, Rule::Exactly(2)],
+        regions: Semantics::Exclusive,
+        score: ScoreRules {
+            correct: 25,
+            incorrect: 8,
+            streak_bonus: 5,
+        },
+        difficulty: Difficulty {
+            per_region: 1,
+            decoys: 2,
+        },
+        visible: false,
+        compound: false,
+        negated: false,
+        set: false,
+    },
+    Level {
+        color: true,
+        colors: 3,
+        size: false,
+        sizes: 1,
+        shapes: CLASSIC_SHAPES,
+        pattern: false,
+        patterns: 1,
+        count: false,
+        counts: 1,
+        orientation: false,
+        orientations: 1,
+        fill: true,
+        fills: 2,
+        rules: &[Rule::Any, Rule::All, Rule::Not, Rule::Exactly(2)],
+        regions: Semantics::Exclusive,
+        score: ScoreRules {
+            correct: 25,
+            incorrect: 8,
+            streak_bonus: 5,
+        },
+        difficulty: Difficulty {
+            per_region: 1,
+            decoys: 2,
+        },
+        visible: false,
+        compound: false,
+        negated: false,
+        set: false,
+    },
+];
+
+/// Fixed settings to play every round with, instead of working through the
+/// levels.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Preset {
+    /// Shapes and colors count, every rule is an any rule and the cards
+    /// are shown.
+    Easy,
+    /// Shapes, colors and patterns count and the cards are hidden.
+    Medium,
+    /// Shapes, colors and sizes count, with every kind of rule including
+    /// compound ones, and many more guesses.
+    Hard,
+}
+
+impl Preset {
+    /// The level every round is played at.
+    pub fn level(self) -> &'static Level {
+        match self {
+            Preset::Easy => &EASY,
+            Preset::Medium => &MEDIUM,
+            Preset::Hard => &HARD,
+        }
+    }
+}
+
+static EASY: Level = Level {
+    color: true,
+    colors: 3,
+    size: false,
+    sizes: 1,
+    shapes: &[
+        VennShape::Circle,
+        VennShape::Square,
+        VennShape::Triangle,
+        VennShape::Diamond,
+        VennShape::Pentagon,
+        VennShape::Hexagon,
+        VennShape::Star,
+    ],
+    pattern: false,
+    patterns: 1,
+    count: false,
+    counts: 1,
+    orientation: false,
+    orientations: 1,
+    fill: false,
+    fills: 1,
+    rules: &[Rule::Any],
+    regions: Semantics::Inclusive,
+    score: ScoreRules {
+        correct: 10,
+        incorrect: 2,
+        streak_bonus: 1,
+    },
+    difficulty: Difficulty {
+        per_region: 1,
+        decoys: 0,
+    },
+    visible: true,
+    compound: false,
+    negated: false,
+    set: false,
+};
+
+static MEDIUM: Level = Level {
+    color: true,
+    colors: 3,
+    size: false,
+    sizes: 1,
+    shapes: CLASSIC_SHAPES,
+    pattern: true,
+    patterns: 4,
+    count: false,
+    counts: 1,
+    orientation: false,
+    orientations: 1,
+    fill: false,
+    fills: 1,
+    rules: &[Rule::Any, Rule::All],
+    regions: Semantics::Exclusive,
+    score: ScoreRules {
+        correct: 15,
+        incorrect: 5,
+        streak_bonus: 3,
+    },
+    difficulty: Difficulty {
+        per_region: 1,
+        decoys: 1,
+    },
+    visible: false,
+    compound: false,
+    negated: false,
+    set: false,
+};
+
+static HARD: Level = Level {
+    color: true,
+    colors: 3,
+    size: true,
+    sizes: 3,
+    shapes: CLASSIC_SHAPES,
+    pattern: false,
+    patterns: 1,
+    count: false,
+    counts: 1,
+    orientation: false,
+    orientations: 1,
+    fill: false,
+    fills: 1,
+    rules: &[Rule::Any, Rule::All, Rule::Not, Rule::Exactly(2)],
+    regions: Semantics::Exclusive,
+    score: ScoreRules {
+        correct: 20,
+        incorrect: 6,
+        streak_bonus: 4,
+    },
+    difficulty: Difficulty {
+        per_region: 1,
+        decoys: 2,
+    },
+    visible: false,
+    compound: true,
+    negated: true,
+    set: false,
+};
+
+// Set mode plays with cards like the ones of the card game Set: three
+// shapes, colors, shadings and counts.
+static SET: Level = Level {
+    color: true,
+    colors: 3,
+    size: false,
+    sizes: 1,
+    shapes: &[VennShape::Diamond, VennShape::Circle, VennShape::Star],
+    pattern: true,
+    patterns: 3,
+    count: true,
+    counts: 3,
+    orientation: false,
+    orientations: 1,
+    fill: false,
+    fills: 1,
+    rules: &[Rule::Any],
+    regions: Semantics::Exclusive,
+    score: ScoreRules {
+        correct: 15,
+        incorrect: 5,
+        streak_bonus: 3,
+    },
+    difficulty: Difficulty {
+        per_region: 2,
+        decoys: 0,
+    },
+    visible: false,
+    compound: false,
+    negated: false,
+    set: true,
+};
+
+// How many of the cards are dealt in Set mode, out of all 81.
+const HAND: usize = 18;
+
+/// The level played at the index into the progression, unless a preset
+/// or Set mode fixes it.
+pub(crate) fn level_at(index: usize, options: &Options) -> &'static Level {
+    if options.mode == Mode::Set {
+        return &SET;
+    }
+    match options.difficulty {
+        Some(preset) => preset.level(),
+        None => &LEVELS[index],
+    }
+}
+
+// How many points a placement is worth at a given level.
+pub struct ScoreRules {
+    pub correct: i32,
+    pub incorrect: i32,
+    // Added for every correct placement already in the current streak.
+    pub streak_bonus: i32,
+}
+
+/// Points and streaks, carried from one level to the next.
+#[derive(Default, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Score {
+    pub points: i32,
+    pub streak: u32,
+    pub best_streak: u32,
+    /// Rounds given up on.
+    pub forfeits: u32,
+}
+
+impl Score {
+    pub(crate) fn record(&mut self, correct: bool, rules: &ScoreRules) {
+        if correct {
+            self.points += rules.correct + rules.streak_bonus * self.streak as i32;
+        } else {
+            self.points -= rules.incorrect;
+        }
+        self.count(correct);
+    }
+
+    // Scores a placement by the bits it told, keeping the streak as usual.
+    pub(crate) fn learn(&mut self, correct: bool, bits: f32, rules: &ScoreRules) {
+        self.points += (bits * rules.correct as f32).round() as i32;
+        self.count(correct);
+    }
+
+    fn count(&mut self, correct: bool) {
+        if correct {
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+        } else {
+            self.streak = 0;
+        }
+    }
+}
+
+/// How the boxes above the circles behave.
+#[derive(PartialEq, Copy, Clone, Default, Serialize, Deserialize)]
+pub enum Mode {
+    /// A card built in an answer box gets immediate feedback.
+    #[default]
+    Classic,
+    /// The card built in each answer box is the player's deduction of the
+    /// circle's hidden card. The round is won or lost once all are built.
+    Deduction,
+    /// The guesses are a hand of Set cards and every circle holds one value
+    /// of the same hidden attribute. The round is won by naming that
+    /// attribute in an answer box.
+    Set,
+    /// Free play, for showing how rules sort cards: the rules show from the
+    /// start, any card of the level can be added as often as wanted and
+    /// taken away again, nothing is scored and the round doesn't end.
+    Sandbox,
+}
+
+/// Settings for a game, usually taken from the command line. The default
+/// is a classic game of two circles through the levels, from seed 0.
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+pub struct Options {
+    /// Play with a third circle.
+    pub three: bool,
+    pub mode: Mode,
+    /// Guesses dropped outside of every circle are checked too: they are
+    /// right there if they match no circle's rule.
+    pub outside: bool,
+    /// Every puzzle is generated from this so a game can be replayed.
+    pub seed: u64,
+    /// Time attack: seconds to solve each round in.
+    #[serde(default)]
+    pub time_limit: Option<u32>,
+    /// Play every round at a preset instead of working through the levels.
+    #[serde(default)]
+    pub difficulty: Option<Preset>,
+    /// Co-op: placements the table shares to get every answer box right
+    /// with. Wrong answers use one up too.
+    #[serde(default)]
+    pub budget: Option<u32>,
+    /// Lives: guesses each round may turn red before it is lost.
+    #[serde(default)]
+    pub lives: Option<u32>,
+    /// Pick the next level by how the latest rounds went instead of moving
+    /// up after every win.
+    #[serde(default)]
+    pub adaptive: bool,
+    #[serde(default)]
+    pub scoring: Scoring,
+}
+
+/// What placements in the diagram earn points for.
+#[derive(PartialEq, Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub enum Scoring {
+    /// Turning green, with a bonus for a streak, while turning red costs.
+    #[default]
+    Classic,
+    /// Telling something new about the circles, green or red: as many
+    /// points as a green guess is usually worth for every bit the
+    /// placement narrows down what their rules and cards could be.
+    Information,
+}
+
+/// What happened when a guess was dropped.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Placement {
+    /// Dropped inside one or more circles. Whether it matches depends on the
+    /// level's region semantics.
+    Region { region: Region, matches: bool },
+    /// A card built in the answer box of a circle in classic mode, which has
+    /// to be the hidden card to match.
+    Answer { circle: usize, matches: bool },
+    /// A card built as the deduction for a circle. Once every circle has
+    /// one, the outcome of the round is known.
+    Submitted {
+        circle: usize,
+        outcome: Option<bool>,
+    },
+    /// Dropped away from every circle and answer box.
+    Outside,
+}
+
+/// Where a guess ended up.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Place {
+    /// Still in, or back in, its home slot.
+    Rail,
+    Region(Region),
+}
+
+/// How one guess ended up in a round.
+#[derive(Copy, Clone)]
+pub struct GuessResult {
+    pub target: VennTarget,
+    pub place: Place,
+    pub right: bool,
+}
+
+// Where the guess being dragged was grabbed.
+#[derive(Clone)]
+struct Grab {
+    from: Point,
+    // From the point it was grabbed at to its center.
+    offset: Vector,
+    // Whether it has been dragged far enough to leave its place.
+    moved: bool,
+}
+
+// How the diagram was shrunk and moved to make room for bigger guesses and
+// answer boxes, kept to lay it out again from the usual size.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+struct Fit {
+    // How much bigger guesses, boxes and text are than usual.
+    scale: f32,
+    // How much smaller the diagram is, around the middle of the window.
+    zoom: f32,
+    shift: (f32, f32),
+}
+
+impl Default for Fit {
+    fn default() -> Fit {
+        Fit {
+            scale: 1.0,
+            zoom: 1.0,
+            shift: (0.0, 0.0),
+        }
+    }
+}
+
+impl Fit {
+    // Where a point of the diagram at the usual size is now.
+    fn apply(&self, point: Point) -> Point {
+        Point::new(
+            WIDTH / 2.0 + (point.x - WIDTH / 2.0) * self.zoom + self.shift.0,
+            HEIGHT / 2.0 + (point.y - HEIGHT / 2.0) * self.zoom + self.shift.1,
+        )
+    }
+
+    // Where a point of the diagram was at the usual size.
+    fn undo(&self, point: Point) -> Point {
+        Point::new(
+            WIDTH / 2.0 + (point.x - self.shift.0 - WIDTH / 2.0) / self.zoom,
+            HEIGHT / 2.0 + (point.y - self.shift.1 - HEIGHT / 2.0) / self.zoom,
+        )
+    }
+}
+
+/// The state of one puzzle, independent of how it is drawn or controlled.
+///
+/// A frontend calls [`pick`], [`drag`] and [`drop`] as the player moves a
+/// guess around.
+///
+/// [`pick`]: #method.pick
+/// [`drag`]: #method.drag
+/// [`drop`]: #method.drop
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VennModel {
+    left: VennSet,
+    right: VennSet,
+    // Only present when playing with three sets.
+    bottom: Option<VennSet>,
+    shapes: Vec<VennGuess>,
+    // Where the guesses are, made the first time a guess is looked for.
+    #[serde(skip)]
+    grid: OnceCell<Grid>,
+    #[serde(skip)]
+    drag_index: Option<usize>,
+    #[serde(skip)]
+    grab: Option<Grab>,
+    #[serde(skip)]
+    history: History,
+    level: usize,
+    options: Options,
+    score: Score,
+    // The shapes the guesses and hidden cards come in, which the player
+    // takes into account when deducing the cards.
+    #[serde(default = "classic_shapes")]
+    in_play: Vec<VennShape>,
+    // Whether the submitted deductions were right, once all are in.
+    outcome: Option<bool>,
+    #[serde(default)]
+    forfeited: bool,
+    #[serde(default)]
+    elapsed: f32,
+    #[serde(default)]
+    timed_out: bool,
+    // Placements and wrong answers so far, in co-op.
+    #[serde(default)]
+    spent: u32,
+    #[serde(default)]
+    out_of_placements: bool,
+    #[serde(default)]
+    out_of_lives: bool,
+    // Guesses dropped where they turned green or red, and how many of
+    // those turned red.
+    #[serde(default)]
+    placed: u32,
+    #[serde(default)]
+    missed: u32,
+    #[serde(default)]
+    fit: Fit,
+    // The fewest bits of the circles' rules and cards left unknown so far
+    // this round, when scoring by information.
+    #[serde(default)]
+    least_unknown: Option<f32>,
+    // Every card dropped where it turned green or red this round, and
+    // where, which still tells the solver something once the guess moves.
+    #[serde(default)]
+    seen: Vec<(VennTarget, Region, bool)>,
+    // The puzzle's own cards, which the guesses and hidden cards stand for,
+    // if it has them.
+    #[serde(default)]
+    tokens: Option<Tokens>,
+}
+
+impl VennModel {
+    /// Generates a puzzle for the given index into the level progression.
+    /// If no puzzle of the level fills every region, the one before it is
+    /// played instead.
+    pub fn new(level: usize, options: &Options, rng: &mut StdRng) -> VennModel {
+        let x_margin = 10.0;
+        let y_margin = 10.0;
+        let remaining_x = WIDTH - x_margin * 2.0;
+        let remaining_y = HEIGHT - y_margin * 2.0;
+        let circles = if options.three { 3 } else { 2 };
+        let (level, definition, targets, puzzle) = (0..=level)
+            .rev()
+            .find_map(|level| {
+                let definition = level_at(level, options);
+                let mut targets = targets(definition, definition.shapes);
+                if definition.set {
+                    targets.shuffle(rng);
+                    targets.truncate(HAND);
+                }
+                let puzzle =
+                    generator::generate(definition, circles, &targets, options.outside, rng)?;
+                Some((level, definition, targets, puzzle))
+            })
+            .expect("every level has puzzles that fill each region");
+        // The sandbox starts out empty, for cards to be added to.
+        let shapes = if options.mode == Mode::Sandbox {
+            Vec::new()
+        } else {
+            line_up(&targets)
+        };
+        let (radius, left_center, right_center) = if options.three {
+            (
+                130.0,
+                Point::new(WIDTH / 2.0 - 70.0, 240.0),
+                Point::new(WIDTH / 2.0 + 70.0, 240.0),
+            )
+        } else {
+            (
+                200.0,
+                Point::new(x_margin + remaining_x / 3.0, y_margin + remaining_y / 2.0),
+                Point::new(
+                    WIDTH - x_margin - remaining_x / 3.0,
+                    HEIGHT - y_margin - remaining_y / 2.0,
+                ),
+            )
+        };
+        let mut left_answer_center = left_center;
+        left_answer_center.y -= answer_offset(radius, 1.0);
+        let mut right_answer_center = right_center;
+        right_answer_center.y -= answer_offset(radius, 1.0);
+        let mut puzzle = puzzle.into_iter();
+        let mut circle = |center: Point, answer_center: Point, color: VennColor| {
+            // A compound rule has no hidden card, so any will do.
+            let (rule, target, compound) = match puzzle.next().unwrap() {
+                Secret::Card(rule, target) => (rule, target, None),
+                Secret::Compound(expr) => (Rule::All, targets[0], Some(expr)),
+            };
+            VennSet {
+                center,
+                radius,
+                outline: Outline::Circle,
+                color,
+                rule,
+                answer: answer(answer_center, target),
+                compound,
+                label: None,
+            }
+        };
+        let left = circle(left_center, left_answer_center, VennColor::Blue);
+        let right = circle(right_center, right_answer_center, VennColor::Yellow);
+        let bottom = if options.three {
+            let center = Point::new(WIDTH / 2.0, 360.0);
+            let mut answer_center = center;
+            answer_center.y += answer_offset(radius, 1.0);
+            Some(circle(center, answer_center, VennColor::Purple))
+        } else {
+            None
+        };
+        VennModel {
+            left,
+            right,
+            bottom,
+            shapes,
+            grid: OnceCell::new(),
+            drag_index: None,
+            grab: None,
+            history: History::default(),
+            level,
+            options: *options,
+            score: Score::default(),
+            in_play: definition.shapes.to_vec(),
+            outcome: None,
+            forfeited: false,
+            elapsed: 0.0,
+            timed_out: false,
+            spent: 0,
+            out_of_placements: false,
+            out_of_lives: false,
+            placed: 0,
+            missed: 0,
+            fit: Fit::default(),
+            least_unknown: None,
+            seen: Vec::new(),
+            tokens: None,
+        }
+    }
+
+    /// Sets up a hand written puzzle. Whether there is a third circle is
+    /// up to the puzzle rather than the options.
+    pub fn from_puzzle(puzzle: &Puzzle, options: &Options) -> VennModel {
+        let options = Options {
+            three: puzzle.circles.len() == 3,
+            ..*options
+        };
+        // Start from the usual layout and replace what the puzzle describes.
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let mut model = VennModel::new(puzzle.level, &options, &mut rng);
+        for (circle, described) in model.circles_mut().zip(&puzzle.circles) {
+            circle.rule = described.rule;
+            circle.answer.target = described.card;
+            circle.compound = described.compound.clone();
+            circle.label = described.label.clone();
+            // The bottom answer box hangs below its circle, the others above.
+            let direction = (circle.answer.center.y - circle.center.y).signum();
+            if let Some((x, y)) = described.center {
+                circle.center = Point::new(x, y);
+            }
+            if let Some(radius) = described.radius {
+                circle.radius = radius;
+            }
+            if let Some(outline) = described.outline {
+                circle.outline = outline;
+            }
+            let (_, reach) = circle.boundary().extent();
+            circle.answer.center = circle.center;
+            circle.answer.center.y += direction * answer_offset(reach, 1.0);
+        }
+        if let Some(shapes) = &puzzle.shapes {
+            model.shapes = line_up(&targets(model.current_level(), shapes));
+            model.in_play = shapes.clone();
+        }
+        if let Some(guesses) = &puzzle.guesses {
+            model.shapes = line_up(guesses);
+        }
+        if let Some(tokens) = &puzzle.table {
+            if puzzle.guesses.is_none() {
+                model.shapes = line_up(&tokens.targets());
+            }
+            model.in_play = tokens.shapes();
+            for circle in model.circles_mut() {
+                circle.answer.written = true;
+            }
+            model.tokens = Some(tokens.clone());
+            model.name_guesses();
+        }
+        model
+    }
+
+    /// The puzzle's own cards, if it was read with some.
+    pub fn tokens(&self) -> Option<&Tokens> {
+        self.tokens.as_ref()
+    }
+
+    // Writes the names of the tokens the guesses stand for on them.
+    fn name_guesses(&mut self) {
+        if let Some(tokens) = &self.tokens {
+            for guess in &mut self.shapes {
+                guess.name = tokens.named(&guess.target).map(str::to_string);
+            }
+        }
+    }
+
+    /// How many times as big as usual guesses and answer boxes are.
+    pub fn ui_scale(&self) -> f32 {
+        self.fit.scale
+    }
+
+    /// Lays the board out again with guesses and answer boxes scale times
+    /// as big as usual. The diagram shrinks and moves over as far as it
+    /// takes for everything to fit the window, and placed guesses move with
+    /// it so they stay in their regions. Moves made before can't be undone.
+    pub fn rescale(&mut self, scale: f32) {
+        let old = self.fit;
+        // The circles at the usual size, and which way their boxes hang.
+        let circles: Vec<(Boundary, f32)> = self
+            .circles()
+            .map(|circle| {
+                let direction = (circle.answer.center.y - circle.center.y).signum();
+                let usual = Boundary {
+                    center: old.undo(circle.center),
+                    radius: circle.radius / old.zoom,
+                    outline: circle.outline,
+                };
+                (usual, direction)
+            })
+            .collect();
+        let rail = Rail::new(self.shapes.len(), HEIGHT, scale);
+        let usual = Rail::new(self.shapes.len(), HEIGHT, 1.0);
+        let fit = fit(&circles, scale, rail.right(), usual.right());
+        for (circle, &(usual, direction)) in self.circles_mut().zip(&circles) {
+            circle.center = fit.apply(usual.center);
+            circle.radius = usual.radius * fit.zoom;
+            let (_, reach) = circle.boundary().extent();
+            circle.answer.center = circle.center;
+            circle.answer.center.y += direction * answer_offset(reach, scale);
+            circle.answer.width = ANSWER_WIDTH * scale;
+            circle.answer.height = ANSWER_HEIGHT * scale;
+        }
+        for (i, guess) in self.shapes.iter_mut().enumerate() {
+            let home = rail.slot(i);
+            if guess.center == guess.home {
+                guess.center = home;
+            } else {
+                guess.center = fit.apply(old.undo(guess.center));
+            }
+            guess.home = home;
+            guess.radius = rail.radius();
+        }
+        self.fit = fit;
+        self.grid = OnceCell::new();
+        self.history = History::default();
+    }
+
+    pub fn guesses(&self) -> &[VennGuess] {
+        &self.shapes
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    // What the current level asks for.
+    pub(crate) fn current_level(&self) -> &'static Level {
+        level_at(self.level, &self.options)
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    /// Continues with the score of an earlier puzzle.
+    pub fn with_score(self, score: Score) -> VennModel {
+        VennModel { score, ..self }
+    }
+
+    /// How many guesses are not where they belong yet.
+    pub fn left_to_place(&self) -> usize {
+        self.shapes.iter().filter(|s| !self.is_right(s)).count()
+    }
+
+    /// Whether the submitted deductions were all right, once every circle
+    /// has one.
+    pub fn outcome(&self) -> Option<bool> {
+        self.outcome
+    }
+
+    /// The index of the guess being dragged, if any.
+    pub fn dragging(&self) -> Option<usize> {
+        self.drag_index
+    }
+
+    pub(crate) fn circles(&self) -> impl Iterator<Item = &VennSet> {
+        std::iter::once(&self.left)
+            .chain(std::iter::once(&self.right))
+            .chain(self.bottom.iter())
+    }
+
+    // The shapes the guesses and hidden cards of this game come in.
+    pub(crate) fn in_play(&self) -> &[VennShape] {
+        &self.in_play
+    }
+
+    fn circles_mut(&mut self) -> impl Iterator<Item = &mut VennSet> {
+        std::iter::once(&mut self.left)
+            .chain(std::iter::once(&mut self.right))
+            .chain(self.bottom.iter_mut())
+    }
+
+    /// The indices of the guesses in the order they are drawn, from the
+    /// bottom of the stack to the top.
+    pub fn stacking(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.shapes.len()).collect();
+        order.sort_by_key(|&i| (self.shapes[i].z, i));
+        order
+    }
+
+    /// The topmost guess under the point, if any.
+    pub fn guess_at(&self, point: &Point) -> Option<usize> {
+        let grid = self.grid.get_or_init(|| Grid::new(&self.shapes));
+        grid.near(point)
+            .iter()
+            .copied()
+            .filter(|&i| self.shapes[i].contains(point))
+            .max_by_key(|&i| (self.shapes[i].z, i))
+    }
+
+    // Puts the guess at the top of the stack, unless it already is.
+    fn raise(&mut self, index: usize) {
+        let top = self.shapes.iter().map(|s| s.z).max().unwrap_or(0);
+        if self.stacking().last() != Some(&index) {
+            self.shapes[index].z = top + 1;
+        }
+    }
+
+    // Moves the guess, keeping the grid up to date.
+    fn move_guess(&mut self, index: usize, to: Point) {
+        self.shapes[index].center = to;
+        self.reindex(index);
+    }
+
+    // Sorts the guess into the grid again after it moved.
+    fn reindex(&mut self, index: usize) {
+        if let Some(grid) = self.grid.get_mut() {
+            grid.moved(index, &self.shapes[index]);
+        }
+    }
+
+    /// Picks up the topmost guess under the point. Returns its index, or
+    /// None if there is nothing there or the board is frozen.
+    pub fn pick(&mut self, point: &Point) -> Option<usize> {
+        let index = self.guess_at(point)?;
+        self.pick_at(index, point)
+    }
+
+    /// Picks up a guess by its index, as if grabbed by its center.
+    pub fn pick_guess(&mut self, index: usize) -> Option<usize> {
+        let center = self.shapes.get(index)?.center;
+        self.pick_at(index, &center)
+    }
+
+    fn pick_at(&mut self, index: usize, point: &Point) -> Option<usize> {
+        // The board is frozen while the result of a round is shown, and in
+        // co-op once there are no placements left.
+        if self.outcome.is_some() || self.forfeited || self.placements_left() == Some(0) {
+            return None;
+        }
+        // The guess goes over every other one and stays there once dropped.
+        self.raise(index);
+        // The guess stays where it is until it is dragged far enough.
+        self.grab = Some(Grab {
+            from: *point,
+            offset: self.shapes[index].center - point,
+            moved: false,
+        });
+        self.drag_index = Some(index);
+        debug!(
+            "pick guess={} target={:?}",
+            index, self.shapes[index].target
+        );
+        Some(index)
+    }
+
+    /// Sends the topmost guess under the point back to its home slot without
+    /// a result. Returns its index, or None if there is nothing there, a
+    /// guess is being dragged or the board is frozen.
+    pub fn send_home(&mut self, point: &Point) -> Option<usize> {
+        if self.drag_index.is_some() || self.outcome.is_some() || self.forfeited {
+            return None;
+        }
+        let index = self.guess_at(point)?;
+        let shape = &mut self.shapes[index];
+        self.history.start(index, shape.center);
+        self.history.finish(shape.home);
+        shape.matches = None;
+        let home = shape.home;
+        self.move_guess(index, home);
+        Some(index)
+    }
+
+    /// Moves the guess being dragged, if any, keeping it where it was
+    /// grabbed relative to the point.
+    pub fn drag(&mut self, point: &Point) {
+        let (index, grab) = match (self.drag_index, self.grab.as_mut()) {
+            (Some(index), Some(grab)) => (index, grab),
+            _ => return,
+        };
+        let offset = grab.offset;
+        if !grab.moved {
+            if nalgebra::distance(point, &grab.from) < PICK_UP_DISTANCE {
+                return;
+            }
+            grab.moved = true;
+            let shape = &mut self.shapes[index];
+            self.history.start(index, shape.center);
+            shape.matches = None;
+        }
+        self.shapes[index].drag_to(&(point + offset));
+        self.reindex(index);
+    }
+
+    /// Lets go of the guess being dragged at the point and classifies it.
+    /// Returns None if nothing was being dragged, or it was only clicked.
+    pub fn drop(&mut self, point: &Point) -> Option<Placement> {
+        let index = self.drag_index.take()?;
+        let grab = self.grab.take()?;
+        if !grab.moved {
+            return None;
+        }
+        let point = &self.snap(index, point + grab.offset);
+        self.history.finish(*point);
+        self.move_guess(index, *point);
+        self.shapes[index].dragged = false;
+        let target = &self.shapes[index].target;
+        let region = self.region(point);
+        let placement = match self.region_matches(region, target) {
+            Some(matches) => Placement::Region { region, matches },
+            None => Placement::Outside,
+        };
+        let matches = match placement {
+            Placement::Region { matches, .. } => Some(matches),
+            _ => None,
+        };
+        debug!(
+            "drop guess={} target={:?} region={:?} circles={:?} semantics={:?} matches={:?}",
+            index,
+            target,
+            region,
+            self.circles()
+                .map(|c| c.matches(target, self.current_level()))
+                .collect::<Vec<_>>(),
+            self.semantics(region),
+            matches
+        );
+        let before = self.unknown();
+        let rules = &self.current_level().score;
+        let sandbox = self.options.mode == Mode::Sandbox;
+        let shape = &mut self.shapes[index];
+        match matches {
+            _ if before.is_some() || sandbox => {}
+            Some(true) if !shape.scored => {
+                shape.scored = true;
+                self.score.record(true, rules);
+            }
+            Some(false) => self.score.record(false, rules),
+            _ => {}
+        }
+        shape.matches = matches;
+        if let Placement::Region { region, matches } = placement {
+            let seen = (shape.target, region, matches);
+            if !self.seen.contains(&seen) {
+                self.seen.push(seen);
+            }
+        }
+        if let (Some(before), Some(matches)) = (before, matches) {
+            let bits = self.learned(before);
+            self.score.learn(matches, bits, &self.current_level().score);
+        }
+        if let Some(matches) = matches {
+            self.placed += 1;
+            if !matches {
+                self.missed += 1;
+            }
+            self.spend();
+            // The last life lost ends the round as if it was given up on.
+            if self.lives_left() == Some(0) && !self.forfeited {
+                self.out_of_lives = true;
+                self.reveal();
+            }
+        }
+        Some(placement)
+    }
+
+    /// Whether a card can be built in the answer box of the circle: the
+    /// round isn't over and, in classic mode, the box isn't right already.
+    /// The sandbox shows the cards, so there is nothing to build.
+    pub fn can_answer(&self, circle: usize) -> bool {
+        if self.drag_index.is_some()
+            || self.outcome.is_some()
+            || self.forfeited
+            || self.options.mode == Mode::Sandbox
+        {
+            return false;
+        }
+        match self.circles().nth(circle) {
+            Some(c) => c.answer.matches != Some(true),
+            None => false,
+        }
+    }
+
+    /// The circle whose answer box is under the point, if a card can be
+    /// built in it.
+    pub fn answer_at(&self, point: &Point) -> Option<usize> {
+        self.circles()
+            .position(|c| c.answer.contains(point))
+            .filter(|&circle| self.can_answer(circle))
+    }
+
+    /// Answers the box above the circle with the card. In classic mode it
+    /// is checked against the hidden card straight away, in deduction mode
+    /// once every box has a card. Returns None if the box can't be answered.
+    pub fn answer(&mut self, circle: usize, card: VennTarget) -> Option<Placement> {
+        if !self.can_answer(circle) {
+            return None;
+        }
+        self.circles_mut().nth(circle).unwrap().answer.built = Some(card);
+        Some(self.check(circle))
+    }
+
+    /// Answers the box above a circle with a compound rule the same way,
+    /// which is right if it picks out the same guesses as the circle's.
+    pub fn answer_rule(&mut self, circle: usize, rule: RuleExpr) -> Option<Placement> {
+        if !self.can_answer(circle) {
+            return None;
+        }
+        self.circles_mut().nth(circle).unwrap().answer.built_rule = Some(rule);
+        Some(self.check(circle))
+    }
+
+    /// Names the attribute the circles sort the cards by in Set mode, in
+    /// the box above the circle. The round is won once it is right.
+    pub fn answer_kind(&mut self, circle: usize, kind: Kind) -> Option<Placement> {
+        if !self.can_answer(circle) {
+            return None;
+        }
+        self.circles_mut().nth(circle).unwrap().answer.built_kind = Some(kind);
+        Some(self.check(circle))
+    }
+
+    // Checks what was just built in the answer box of the circle, or in
+    // deduction mode all of them once every box has something.
+    fn check(&mut self, circle: usize) -> Placement {
+        if self.options.mode == Mode::Deduction {
+            self.evaluate();
+            return Placement::Submitted {
+                circle,
+                outcome: self.outcome,
+            };
+        }
+        let level = self.current_level();
+        let guesses = self.targets();
+        let c = self.circles().nth(circle).unwrap();
+        let matches = c.answer_matches(level, &guesses) == Some(true);
+        let answer = &c.answer;
+        debug!(
+            "answer circle={} card={:?} rule={:?} kind={:?} matches={}",
+            circle, answer.built, answer.built_rule, answer.built_kind, matches
+        );
+        let before = self.unknown();
+        self.circles_mut().nth(circle).unwrap().answer.matches = Some(matches);
+        self.score.record(matches, &level.score);
+        // What the answer told isn't worth points again when a placement
+        // tells it.
+        if let Some(before) = before {
+            self.learned(before);
+        }
+        if self.options.mode == Mode::Set && matches {
+            self.outcome = Some(true);
+        }
+        if !matches {
+            // With nothing left to spend, a wrong answer loses the round.
+            if self.placements_left() == Some(0) {
+                self.out_of_placements = true;
+                self.reveal();
+            }
+            self.spend();
+        }
+        Placement::Answer { circle, matches }
+    }
+
+    // Uses up one of the placements of a co-op round.
+    fn spend(&mut self) {
+        if self.options.budget.is_some() {
+            self.spent += 1;
+        }
+    }
+
+    /// How many placements are left in co-op.
+    pub fn placements_left(&self) -> Option<u32> {
+        let budget = self.options.budget?;
+        Some(budget.saturating_sub(self.spent))
+    }
+
+    /// Whether the co-op round was lost for running out of placements.
+    pub fn is_out_of_placements(&self) -> bool {
+        self.out_of_placements
+    }
+
+    /// How many more guesses may turn red this round, with lives.
+    pub fn lives_left(&self) -> Option<u32> {
+        let lives = self.options.lives?;
+        Some(lives.saturating_sub(self.missed))
+    }
+
+    /// Whether the round was lost for running out of lives.
+    pub fn is_out_of_lives(&self) -> bool {
+        self.out_of_lives
+    }
+
+    // The cards of all the guesses.
+    fn targets(&self) -> Vec<VennTarget> {
+        self.shapes.iter().map(|shape| shape.target).collect()
+    }
+
+    /// Puts the guess moved last back where it was picked up, without a
+    /// result. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if self.drag_index.is_some() || self.outcome.is_some() || self.forfeited {
+            return false;
+        }
+        match self.history.undo() {
+            Some(last) => {
+                self.move_guess(last.guess, last.from);
+                self.shapes[last.guess].matches = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drops the guess moved by the last undone move where it was dropped
+    /// before. Returns None if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Placement> {
+        if self.drag_index.is_some() || self.outcome.is_some() || self.forfeited {
+            return None;
+        }
+        let next = self.history.redo()?;
+        // Nothing was picked up, so the history doesn't see a new move.
+        self.drag_index = Some(next.guess);
+        self.grab = Some(Grab {
+            from: next.to,
+            offset: Vector::zeros(),
+            moved: true,
+        });
+        self.drop(&next.to)
+    }
+
+    /// Moves the guess straight to the point and drops it there, for a move
+    /// made on another machine. Returns None if the board is frozen or the
+    /// guess is there already.
+    pub fn place(&mut self, index: usize, point: &Point) -> Option<Placement> {
+        if self.drag_index.is_some()
+            || self.outcome.is_some()
+            || self.forfeited
+            || self.shapes.get(index)?.center == *point
+        {
+            return None;
+        }
+        self.history.start(index, self.shapes[index].center);
+        self.raise(index);
+        self.drag_index = Some(index);
+        self.grab = Some(Grab {
+            from: *point,
+            offset: Vector::zeros(),
+            moved: true,
+        });
+        self.drop(point)
+    }
+
+    /// The part of the diagram the point is in.
+    pub fn region(&self, point: &Point) -> Region {
+        let sets: Vec<Boundary> = self.circles().map(VennSet::boundary).collect();
+        region::classify(point, &sets)
+    }
+
+    // Whether the guess is right in the region according to the level's
+    // semantics. Returns None outside of the circles unless the outside is
+    // part of the game.
+    fn region_matches(&self, region: Region, target: &VennTarget) -> Option<bool> {
+        if region == Region::Outside && !self.options.outside {
+            return None;
+        }
+        let level = self.current_level();
+        let matches = [
+            Some(self.left.matches(target, level)),
+            Some(self.right.matches(target, level)),
+            self.bottom.as_ref().map(|c| c.matches(target, level)),
+        ];
+        Some(self.semantics(region).evaluate(region, matches))
+    }
+
+    // How the rules decide the region.
+    fn semantics(&self, region: Region) -> Semantics {
+        // Outside, a guess must not match any circle whatever the semantics.
+        match region {
+            Region::Outside => Semantics::Exclusive,
+            _ => self.current_level().regions,
+        }
+    }
+
+    // Once every circle has a deduction, checks them all against the hidden
+    // cards and decides the round.
+    fn evaluate(&mut self) {
+        let level = self.current_level();
+        if !self.circles().all(VennSet::is_answered) {
+            return;
+        }
+        let guesses = self.targets();
+        let results: Vec<bool> = self
+            .circles()
+            .map(|c| c.answer_matches(level, &guesses) == Some(true))
+            .collect();
+        debug!("deduce results={:?}", results);
+        for (circle, &correct) in self.circles_mut().zip(&results) {
+            circle.answer.matches = Some(correct);
+        }
+        for &correct in &results {
+            self.score.record(correct, &level.score);
+        }
+        self.outcome = Some(results.iter().all(|&correct| correct));
+    }
+
+    // How many bits of the circles' rules and cards are still unknown, with
+    // every candidate the solver has left taken as equally likely. None
+    // unless scoring by information.
+    fn unknown(&self) -> Option<f32> {
+        if self.options.scoring != Scoring::Information {
+            return None;
+        }
+        let solver = self.solver();
+        let circles = 0..self.circles().count();
+        Some(
+            circles
+                .map(|c| (solver.candidates(c).len().max(1) as f32).log2())
+                .sum(),
+        )
+    }
+
+    // How many bits fewer are unknown than ever before this round, given
+    // how many were before the latest move. Moving a guess away and back
+    // tells nothing new.
+    fn learned(&mut self, before: f32) -> f32 {
+        let after = self.unknown().unwrap_or(before);
+        let least = self.least_unknown.map_or(before, |least| least.min(before));
+        self.least_unknown = Some(least.min(after));
+        (least - after).max(0.0)
+    }
+
+    /// What the guesses that turned green or red this round say about the
+    /// hidden cards, wherever they are now.
+    pub fn solver(&self) -> Solver<'static> {
+        let mut observations: Vec<Observation> = self
+            .seen
+            .iter()
+            .map(|&(target, region, matches)| Observation::Region {
+                region,
+                target,
+                matches,
+            })
+            .collect();
+        // Deductions say nothing until the round is over.
+        if self.options.mode == Mode::Classic {
+            observations.extend(self.circles().enumerate().filter_map(|(circle, c)| {
+                let matches = c.answer.matches?;
+                Some(match &c.answer.built_rule {
+                    Some(rule) => Observation::Rule {
+                        circle,
+                        rule: rule.clone(),
+                        matches,
+                    },
+                    None => Observation::Answer {
+                        circle,
+                        target: c.answer.built?,
+                        matches,
+                    },
+                })
+            }));
+        }
+        let compound: Vec<bool> = self.circles().map(|c| c.compound.is_some()).collect();
+        Solver::new(
+            self.current_level(),
+            &self.in_play,
+            &compound,
+            &observations,
+        )
+    }
+
+    /// Suggests where to put which guess next, if anything is left to do.
+    pub fn hint(&self) -> Option<Hint> {
+        if self.outcome.is_some() || self.forfeited {
+            return None;
+        }
+        let solver = self.solver();
+        let regions = generator::regions(self.circles().count(), self.options.outside);
+        // Green guesses are left alone unless they might belong in more
+        // circles, and then only go into more circles, so what turning
+        // green told stays on the board. The others go somewhere they
+        // aren't, and haven't turned red before, as that would tell nothing.
+        let guesses: Vec<(usize, VennTarget, Vec<Region>)> = self
+            .shapes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, shape)| {
+                let here = self.region(&shape.center);
+                if shape.matches != Some(true) {
+                    let placed = shape.center != shape.home;
+                    let untried = regions.iter().copied().filter(|&region| {
+                        (!placed || region != here)
+                            && !self.seen.contains(&(shape.target, region, false))
+                    });
+                    return Some((i, shape.target, untried.collect()));
+                }
+                if solver.belonging(&shape.target, here) >= 1.0 {
+                    return None;
+                }
+                let inside = here.membership();
+                let more = regions.iter().copied().filter(|&region| {
+                    let membership = region.membership();
+                    region != here
+                        && (0..3).all(|c| !inside[c] || membership[c])
+                        && !self.seen.contains(&(shape.target, region, false))
+                });
+                Some((i, shape.target, more.collect()))
+            })
+            .collect();
+        solver.hint(&guesses).or_else(|| {
+            // All that is left is taking red guesses that belong nowhere
+            // they haven't been tried back out of the diagram.
+            guesses
+                .iter()
+                .find(|(i, target, untried)| {
+                    self.shapes[*i].matches == Some(false)
+                        && untried.iter().all(|&r| solver.chance(target, r) <= 0.0)
+                })
+                .map(|&(guess, _, _)| Hint {
+                    guess,
+                    region: Region::Outside,
+                })
+        })
+    }
+
+    /// Moves the guess where it belongs for a player who is stuck: into the
+    /// part of the diagram that is inside every circle it fits and outside
+    /// the others, or out of the diagram if it belongs nowhere. It earns no
+    /// points, costs as many as a wrong placement and ends the streak.
+    /// Returns None if the guess is already right or can't be moved.
+    pub fn place_for(&mut self, index: usize) -> Option<Placement> {
+        if self.is_right(self.shapes.get(index)?) {
+            return None;
+        }
+        let point = match self.belongs(&self.shapes[index].target) {
+            Region::Outside if !self.options.outside => self.shapes[index].home,
+            region => self.region_center(region)?,
+        };
+        let (scored, points) = (self.shapes[index].scored, self.score.points);
+        self.shapes[index].scored = true;
+        let placement = self.place(index, &point);
+        match placement {
+            // Whatever it told when scoring by information is given away.
+            Some(_) => {
+                let rules = &self.current_level().score;
+                self.score.points = points - rules.incorrect;
+                self.score.streak = 0;
+            }
+            None => self.shapes[index].scored = scored,
+        }
+        placement
+    }
+
+    /// The regions where the guess would turn green.
+    pub fn right_regions(&self, index: usize) -> Vec<Region> {
+        let target = &self.shapes[index].target;
+        generator::regions(self.circles().count(), self.options.outside)
+            .into_iter()
+            .filter(|&region| self.region_matches(region, target) == Some(true))
+            .collect()
+    }
+
+    /// How many guesses in each region of the diagram turned green there.
+    pub fn region_counts(&self) -> Vec<(Region, usize)> {
+        let circles = self.circles().count();
+        generator::regions(circles, self.options.outside)
+            .into_iter()
+            .map(|region| {
+                let count = self
+                    .shapes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, shape)| {
+                        self.drag_index != Some(i)
+                            && shape.matches == Some(true)
+                            && self.region(&shape.center) == region
+                    })
+                    .count();
+                (region, count)
+            })
+            .collect()
+    }
+
+    /// A point well inside the region, away from the answer boxes, or None
+    /// if the diagram has no such region.
+    pub fn region_center(&self, region: Region) -> Option<Point> {
+        self.region_points(region).into_iter().next()
+    }
+
+    // Where a guess dropped at the point settles: the closest free spot of a
+    // grid laid out from the middle of the part of the diagram it was dropped
+    // in, so guesses line up without covering each other. Spots where the
+    // guess doesn't hang over an edge come first. Off the circles, or if
+    // there is no room, it stays where it was dropped.
+    fn snap(&self, index: usize, point: Point) -> Point {
+        let region = self.region(&point);
+        let middle = match region {
+            Region::Outside => return point,
+            _ => self.region_center(region).unwrap_or(point),
+        };
+        let radius = self.shapes[index].radius;
+        let step = radius * 2.0 + SNAP_GAP;
+        let inside = |spot: &Point| {
+            self.region(spot) == region && !self.circles().any(|c| c.answer.contains(spot))
+        };
+        let overhangs = |spot: &Point| {
+            !(0..8).all(|i| {
+                let angle = i as f32 * std::f32::consts::FRAC_PI_4;
+                inside(&(spot + Vector::new(angle.cos(), angle.sin()) * radius))
+            })
+        };
+        let free = |spot: &Point| {
+            self.shapes.iter().enumerate().all(|(i, other)| {
+                i == index || nalgebra::distance(spot, &other.center) >= radius + other.radius
+            })
+        };
+        let reach = (WIDTH.max(HEIGHT) / step) as i32;
+        (-reach..=reach)
+            .flat_map(|x| (-reach..=reach).map(move |y| (x, y)))
+            .map(|(x, y)| Point::new(middle.x + x as f32 * step, middle.y + y as f32 * step))
+            .filter(|spot| inside(spot) && free(spot))
+            .map(|spot| ((overhangs(&spot), nalgebra::distance(&spot, &point)), spot))
+            .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+            .map_or(point, |(_, spot)| spot)
+    }
+
+    /// Nudges guesses in the diagram that overlap a little further apart,
+    /// keeping each in its part of the diagram, as far as they go in the
+    /// seconds. Called every tick, a pile of guesses spreads out until each
+    /// can be seen.
+    pub fn spread(&mut self, seconds: f32) {
+        let nudge = 1.0 - (1.0 - NUDGE).powf(seconds * 60.0);
+        let placed: Vec<usize> = (0..self.shapes.len())
+            .filter(|&i| {
+                let shape = &self.shapes[i];
+                self.drag_index != Some(i)
+                    && shape.matches.is_some()
+                    && self.region(&shape.center) != Region::Outside
+            })
+            .collect();
+        let mut pushes = vec![Vector::zeros(); self.shapes.len()];
+        for (n, &a) in placed.iter().enumerate() {
+            for &b in &placed[n + 1..] {
+                let (a_shape, b_shape) = (&self.shapes[a], &self.shapes[b]);
+                let apart = b_shape.center - a_shape.center;
+                let distance = apart.norm();
+                let overlap = a_shape.radius + b_shape.radius - distance;
+                // Close enough to leave alone, or they would never stop.
+                if overlap < 0.5 {
+                    continue;
+                }
+                // Guesses right on top of each other go sideways.
+                let direction = if distance > 0.0 {
+                    apart / distance
+                } else {
+                    Vector::new(1.0, 0.0)
+                };
+                let push = direction * overlap * nudge / 2.0;
+                pushes[a] -= push;
+                pushes[b] += push;
+            }
+        }
+        for i in placed {
+            let center = self.shapes[i].center;
+            let to = center + pushes[i];
+            if to != center
+                && self.region(&to) == self.region(&center)
+                && !self.circles().any(|c| c.answer.contains(&to))
+            {
+                self.move_guess(i, to);
+            }
+        }
+    }
+
+    // Points of the region away from the answer boxes, closest to its middle
+    // first.
+    fn region_points(&self, region: Region) -> Vec<Point> {
+        let mut points = Vec::new();
+        for x in (0..WIDTH as usize).step_by(10) {
+            for y in (0..HEIGHT as usize).step_by(10) {
+                let point = Point::new(x as f32, y as f32);
+                if self.region(&point) == region
+                    && !self.circles().any(|c| c.answer.contains(&point))
+                {
+                    points.push(point);
+                }
+            }
+        }
+        let count = points.len() as f32;
+        let (x, y) = points
+            .iter()
+            .fold((0.0, 0.0), |(x, y), p| (x + p.x / count, y + p.y / count));
+        // The middle of a crescent is not in it, so go by distance to it.
+        let middle = Point::new(x, y);
+        points.sort_by(|a, b| {
+            nalgebra::distance(a, &middle)
+                .partial_cmp(&nalgebra::distance(b, &middle))
+                .unwrap()
+        });
+        points
+    }
+
+    /// Ends the round without solving it, and shows the answer. The level
+    /// has to be played again.
+    pub fn give_up(&mut self) {
+        if self.is_finished() || self.options.mode == Mode::Sandbox {
+            return;
+        }
+        self.score.forfeits += 1;
+        debug!("give up level={}", self.level);
+        self.reveal();
+    }
+
+    /// Lets time pass. In time attack, the round ends like it was given up
+    /// on once time runs out.
+    pub fn advance(&mut self, seconds: f32) {
+        if self.is_finished() {
+            return;
+        }
+        self.elapsed += seconds;
+        if self.remaining() == Some(0.0) {
+            self.timed_out = true;
+            self.reveal();
+        }
+    }
+
+    /// Seconds spent on the round so far.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// The share of the guesses dropped in the round that turned green, in
+    /// percent, or None if none were.
+    pub fn accuracy(&self) -> Option<u32> {
+        if self.placed == 0 {
+            return None;
+        }
+        let right = (self.placed - self.missed) as f32;
+        Some((right / self.placed as f32 * 100.0).round() as u32)
+    }
+
+    /// Whether the rules of the circles show while the round is played: at
+    /// levels that show the hidden cards anyway, and in the sandbox.
+    pub fn shows_rules(&self) -> bool {
+        self.current_level().visible || self.options.mode == Mode::Sandbox
+    }
+
+    /// Every card of the level, which can be added to the sandbox.
+    pub fn palette(&self) -> Vec<VennTarget> {
+        match &self.tokens {
+            Some(tokens) => tokens.targets(),
+            None => targets(self.current_level(), &self.in_play),
+        }
+    }
+
+    /// Adds a guess of the card at the end of the rail, and returns its
+    /// index. The rail makes room for it, which can't be undone.
+    pub fn add(&mut self, target: VennTarget) -> usize {
+        let rail = Rail::new(self.shapes.len() + 1, HEIGHT, self.fit.scale);
+        let index = self.shapes.len();
+        self.shapes
+            .push(VennGuess::new(rail.slot(index), rail.radius(), target));
+        self.name_guesses();
+        self.rescale(self.fit.scale);
+        index
+    }
+
+    /// How far from the left edge the rail reaches.
+    pub fn rail_right(&self) -> f32 {
+        Rail::new(self.shapes.len(), HEIGHT, self.fit.scale).right()
+    }
+
+    /// Takes the guess away, and moves the ones after it up the rail.
+    pub fn remove(&mut self, index: usize) {
+        if index >= self.shapes.len() || self.drag_index.is_some() {
+            return;
+        }
+        self.shapes.remove(index);
+        self.rescale(self.fit.scale);
+    }
+
+    /// How many guesses were dropped in the diagram this round.
+    pub fn placements(&self) -> u32 {
+        self.placed
+    }
+
+    /// How many placements following the solver's hints takes to solve the
+    /// round from where it stands, to measure the player against. The hints
+    /// only look one placement ahead, so this is not the fewest the round
+    /// can be solved in, and a player can beat it. None where the round
+    /// isn't won by sorting guesses, or if the hints get stuck before it is
+    /// solved, as they only go by what the placements showed.
+    pub fn par(&self) -> Option<u32> {
+        if self.options.mode != Mode::Classic || self.options.budget.is_some() {
+            return None;
+        }
+        let mut model = self.clone();
+        model.options.lives = None;
+        model.options.time_limit = None;
+        let run = autoplay::play(&mut model);
+        run.solved.then_some(run.placements)
+    }
+
+    /// Seconds left in time attack.
+    pub fn remaining(&self) -> Option<f32> {
+        let limit = self.options.time_limit? as f32;
+        Some((limit - self.elapsed).max(0.0))
+    }
+
+    /// Whether the round ended because time ran out.
+    pub fn is_timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    // Ends the round unsolved: moves every guess left to where it belongs so
+    // the player can see the answer.
+    fn reveal(&mut self) {
+        self.drag_index = None;
+        self.grab = None;
+        self.forfeited = true;
+        self.score.streak = 0;
+        let regions = generator::regions(self.circles().count(), self.options.outside);
+        // Spread the guesses out over each region, keeping the ones already
+        // placed right where they are.
+        let mut taken: Vec<Point> = self
+            .shapes
+            .iter()
+            .filter(|s| s.matches == Some(true))
+            .map(|s| s.center)
+            .collect();
+        for i in 0..self.shapes.len() {
+            if self.shapes[i].matches == Some(true) {
+                continue;
+            }
+            let target = self.shapes[i].target;
+            let home = regions
+                .iter()
+                .find(|&&r| self.region_matches(r, &target) == Some(true));
+            let shape = match home {
+                Some(&region) => {
+                    let points = self.region_points(region);
+                    let point = points
+                        .iter()
+                        .find(|p| taken.iter().all(|t| nalgebra::distance(*p, t) > 25.0))
+                        .or_else(|| points.first())
+                        .map(|&point| self.snap(i, point));
+                    let shape = &mut self.shapes[i];
+                    if let Some(point) = point {
+                        taken.push(point);
+                        shape.center = point;
+                    }
+                    shape.matches = Some(true);
+                    shape
+                }
+                None => {
+                    let shape = &mut self.shapes[i];
+                    shape.center = shape.home;
+                    shape.matches = None;
+                    shape
+                }
+            };
+            shape.dragged = false;
+        }
+        // Nearly every guess moved.
+        self.grid = OnceCell::new();
+    }
+
+    /// Whether the round ended unsolved, because the player gave up or ran
+    /// out of time, placements or lives. The hidden cards are shown then.
+    pub fn is_forfeited(&self) -> bool {
+        self.forfeited
+    }
+
+    /// Whether the round is over, either because every guess is classified,
+    /// because the deductions have been submitted or, in co-op, because
+    /// every answer box is right.
+    pub fn is_finished(&self) -> bool {
+        if self.forfeited {
+            return true;
+        }
+        match self.options.mode {
+            Mode::Classic if self.options.budget.is_some() => self.is_deduced(),
+            Mode::Classic => self.is_solved(),
+            Mode::Deduction | Mode::Set => self.outcome.is_some(),
+            Mode::Sandbox => false,
+        }
+    }
+
+    /// Whether the round was won rather than lost or given up on.
+    pub fn is_won(&self) -> bool {
+        if self.forfeited {
+            return false;
+        }
+        match self.options.mode {
+            Mode::Classic if self.options.budget.is_some() => self.is_deduced(),
+            Mode::Classic => self.is_solved(),
+            Mode::Deduction | Mode::Set => self.outcome == Some(true),
+            Mode::Sandbox => false,
+        }
+    }
+
+    // Whether every answer box is right, which is what a co-op round is
+    // won by.
+    fn is_deduced(&self) -> bool {
+        self.circles().all(|c| c.answer.matches == Some(true))
+    }
+
+    /// Every guess that belongs in a circle has to be green in the part of
+    /// the diagram that is inside every circle it fits and outside the
+    /// others, even where the level turns it green elsewhere. A guess that
+    /// fits two circles only counts in their overlap. The ones that belong
+    /// nowhere have to be left out, or dropped outside and green when the
+    /// outside is part of the game.
+    pub fn is_solved(&self) -> bool {
+        self.drag_index.is_none() && self.shapes.iter().all(|shape| self.is_right(shape))
+    }
+
+    /// Whether the guess with the index is where it belongs, as a solved
+    /// round needs it.
+    pub fn is_in_place(&self, index: usize) -> bool {
+        self.is_right(&self.shapes[index])
+    }
+
+    /// The part of the diagram the card belongs in: inside every circle it
+    /// fits and outside the others.
+    pub fn belongs(&self, target: &VennTarget) -> Region {
+        let level = self.current_level();
+        let mut fits = self.circles().map(|c| c.matches(target, level));
+        let (left, right) = (fits.next().unwrap_or(false), fits.next().unwrap_or(false));
+        Region::from_membership(left, right, fits.next().unwrap_or(false))
+    }
+
+    // Whether the guess is where it belongs: green there, or left alone if
+    // it belongs nowhere.
+    fn is_right(&self, shape: &VennGuess) -> bool {
+        let region = self.belongs(&shape.target);
+        if self.options.outside || region != Region::Outside {
+            shape.matches == Some(true) && self.region(&shape.center) == region
+        } else {
+            shape.matches.is_none()
+        }
+    }
+
+    /// Where every guess ended up and whether that is right, for showing the
+    /// results of a round.
+    pub fn results(&self) -> Vec<GuessResult> {
+        self.shapes
+            .iter()
+            .map(|shape| {
+                let place = if shape.center == shape.home {
+                    Place::Rail
+                } else {
+                    Place::Region(self.region(&shape.center))
+                };
+                GuessResult {
+                    target: shape.target,
+                    place,
+                    right: self.is_right(shape),
+                }
+            })
+            .collect()
+    }
+
+    /// The level to play after this one.
+    pub fn next_level(&self) -> usize {
+        // A wrong deduction, giving up or running out of time or lives means
+        // another puzzle at the same level.
+        match self.outcome {
+            _ if self.forfeited || self.options.difficulty.is_some() => self.level,
+            _ if matches!(self.options.mode, Mode::Set | Mode::Sandbox) => self.level,
+            Some(false) => self.level,
+            _ => (self.level + 1).min(LEVELS.len() - 1),
+        }
+    }
+}
+
+// Every guess the level plays with: each shape in every color, size,
+// pattern, count, orientation and fill the level brings in. Shapes that don't
+// turn only stand upright.
+fn targets(level: &Level, shapes: &[VennShape]) -> Vec<VennTarget> {
+    let mut targets = Vec::new();
+    for &shape in shapes {
+        for &color in VennColor::variants().iter().take(level.colors) {
+            // Levels that ignore size use medium guesses only.
+            let sizes = match level.sizes {
+                1 => vec![VennSize::Medium],
+                sizes => VennSize::variants().iter().copied().take(sizes).collect(),
+            };
+            for size in sizes {
+                for &pattern in VennPattern::variants().iter().take(level.patterns) {
+                    for &count in VennCount::variants().iter().take(level.counts) {
+                        let orientations = if shape.turns() { level.orientations } else { 1 };
+                        for &orientation in VennOrientation::variants().iter().take(orientations) {
+                            for &fill in VennFill::variants().iter().take(level.fills) {
+                                targets.push(VennTarget {
+                                    color,
+                                    shape,
+                                    size,
+                                    pattern,
+                                    count,
+                                    orientation,
+                                    fill,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    targets
+}
+
+// Saves from before the shapes could be chosen played with the classic
+// ones.
+fn classic_shapes() -> Vec<VennShape> {
+    CLASSIC_SHAPES.to_vec()
+}
+
+// How far the diagram, given as each set at the usual size and which way
+// its box hangs, shrinks and moves to fit the window with boxes scale times
+// as big and the rail reaching as far as rail instead of usual. A diagram
+// that was out of the window or over the rail to begin with is left as far
+// out.
+fn fit(circles: &[(Boundary, f32)], scale: f32, rail: f32, usual: f32) -> Fit {
+    let bounds = |fit: &Fit| {
+        let mut low = Point::new(f32::INFINITY, f32::INFINITY);
+        let mut high = Point::new(f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for &(set, direction) in circles {
+            let center = fit.apply(set.center);
+            let (across, down) = set.extent();
+            let (across, down) = (across * fit.zoom, down * fit.zoom);
+            let answer = center.y + direction * answer_offset(down, fit.scale);
+            let half = Vector::new(ANSWER_WIDTH / 2.0, ANSWER_HEIGHT / 2.0) * fit.scale;
+            low.x = low.x.min(center.x - across).min(center.x - half.x);
+            low.y = low.y.min(center.y - down).min(answer - half.y);
+            high.x = high.x.max(center.x + across).max(center.x + half.x);
+            high.y = high.y.max(center.y + down).max(answer + half.y);
+        }
+        (low, high)
+    };
+    let (low, high) = bounds(&Fit::default());
+    let left = low.x.min(10.0).max(rail - (usual - low.x).max(0.0));
+    let right = high.x.max(WIDTH - 10.0);
+    let top = low.y.min(5.0);
+    let bottom = high.y.max(HEIGHT - 5.0);
+    let mut fit = Fit {
+        scale,
+        ..Fit::default()
+    };
+    loop {
+        let (low, high) = bounds(&fit);
+        let fits = high.x - low.x <= right - left && high.y - low.y <= bottom - top;
+        if fits || fit.zoom <= MIN_ZOOM {
+            fit.shift = (
+                into(low.x, high.x, left, right),
+                into(low.y, high.y, top, bottom),
+            );
+            return fit;
+        }
+        fit.zoom -= 0.01;
+    }
+}
+
+// How far to move a span from low to high so it is within from and to, or
+// starts at from if it is too long.
+fn into(low: f32, high: f32, from: f32, to: f32) -> f32 {
+    if low < from {
+        from - low
+    } else if high > to {
+        to - high
+    } else {
+        0.0
+    }
+}
+
+// Guesses for the targets, lined up on the rail.
+fn line_up(targets: &[VennTarget]) -> Vec<VennGuess> {
+    let rail = Rail::new(targets.len(), HEIGHT, 1.0);
+    targets
+        .iter()
+        .enumerate()
+        .map(|(i, &target)| VennGuess::new(rail.slot(i), rail.radius(), target))
+        .collect()
+}
+
+// The answer boxes sit just outside of their circle, which reaches as far
+// up and down from its center, with boxes scale times as big as usual.
+fn answer_offset(reach: f32, scale: f32) -> f32 {
+    reach + (ANSWER_HEIGHT / 2.0 + 15.0) * scale
+}
+
+fn answer(center: Point, target: VennTarget) -> VennAnswer {
+    VennAnswer {
+        center,
+        width: ANSWER_WIDTH,
+        height: ANSWER_HEIGHT,
+        target,
+        built: None,
+        built_rule: None,
+        built_kind: None,
+        matches: None,
+        written: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // A puzzle generated at the level with the options, which are given
+    // as plain values so proptest can shrink and print them.
+    fn model(
+        level: usize,
+        seed: u64,
+        three: bool,
+        outside: bool,
+        set: bool,
+        difficulty: usize,
+    ) -> VennModel {
+        let options = Options {
+            three,
+            mode: if set { Mode::Set } else { Mode::Classic },
+            outside,
+            seed,
+            difficulty: [
+                None,
+                Some(Preset::Easy),
+                Some(Preset::Medium),
+                Some(Preset::Hard),
+            ][difficulty],
+            ..Options::default()
+        };
+        VennModel::new(level, &options, &mut StdRng::seed_from_u64(seed))
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn answers_stay_clear_of_circles(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+            outside: bool,
+            set: bool,
+            difficulty in 0..4usize,
+        ) {
+            let model = model(level, seed, three, outside, set, difficulty);
+            for circle in model.circles() {
+                for answer in model.circles().map(|c| &c.answer) {
+                    // The point of the box closest to the circle's center.
+                    let x = circle.center.x.clamp(
+                        answer.center.x - answer.width / 2.0,
+                        answer.center.x + answer.width / 2.0,
+                    );
+                    let y = circle.center.y.clamp(
+                        answer.center.y - answer.height / 2.0,
+                        answer.center.y + answer.height / 2.0,
+                    );
+                    prop_assert!(!circle.contains(&Point::new(x, y)));
+                }
+            }
+        }
+
+        #[test]
+        fn lens_is_in_both_circles(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+            difficulty in 0..4usize,
+        ) {
+            let model = model(level, seed, three, false, false, difficulty);
+            let point = model.region_center(Region::Both);
+            prop_assert!(point.is_some());
+            let point = point.unwrap();
+            prop_assert_eq!(model.region(&point), Region::Both);
+            prop_assert!(model.left.contains(&point) && model.right.contains(&point));
+        }
+
+        #[test]
+        fn every_region_has_guesses_that_belong(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+            outside: bool,
+            difficulty in 0..4usize,
+        ) {
+            let model = model(level, seed, three, outside, false, difficulty);
+            // Not dropped to an easier level to find a puzzle.
+            prop_assert_eq!(model.level(), level);
+            let per_region = model.current_level().difficulty.per_region;
+            for region in generator::regions(model.circles().count(), outside) {
+                let belong = model
+                    .guesses()
+                    .iter()
+                    .filter(|guess| model.belongs(&guess.target) == region)
+                    .count();
+                prop_assert!(belong >= per_region, "{:?} has {} guesses", region, belong);
+            }
+        }
+
+        #[test]
+        fn every_rule_has_a_region(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+            outside: bool,
+            set: bool,
+            difficulty in 0..4usize,
+        ) {
+            let model = model(level, seed, three, outside, set, difficulty);
+            let level = model.current_level();
+            let guesses = model.guesses();
+            for circle in model.circles() {
+                let fitting: Vec<usize> = (0..guesses.len())
+                    .filter(|&i| circle.matches(&guesses[i].target, level))
+                    .collect();
+                prop_assert!(!fitting.is_empty());
+                prop_assert!(fitting.iter().any(|&i| !model.right_regions(i).is_empty()));
+            }
+        }
+
+        #[test]
+        fn piled_guesses_spread_out(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+        ) {
+            let mut model = model(level, seed, three, false, false, 0);
+            let point = model.region_center(Region::LeftOnly).unwrap();
+            for shape in &mut model.shapes[..2] {
+                shape.center = point;
+                shape.matches = Some(true);
+            }
+            for _ in 0..200 {
+                model.spread(1.0 / 60.0);
+            }
+            let (a, b) = (&model.shapes[0], &model.shapes[1]);
+            prop_assert!(nalgebra::distance(&a.center, &b.center) > a.radius + b.radius - 1.0);
+            prop_assert_eq!(model.region(&a.center), Region::LeftOnly);
+            prop_assert_eq!(model.region(&b.center), Region::LeftOnly);
+        }
+
+        #[test]
+        fn placing_for_the_player_turns_green_at_a_price(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+        ) {
+            let mut model = model(level, seed, three, false, false, 1);
+            let guess = (0..model.guesses().len()).find(|&i| !model.right_regions(i).is_empty());
+            prop_assume!(guess.is_some());
+            let guess = guess.unwrap();
+            let points = model.score().points;
+            prop_assert!(model.place_for(guess).is_some());
+            prop_assert_eq!(model.guesses()[guess].matches, Some(true));
+            prop_assert_eq!(model.score().points, points - model.current_level().score.incorrect);
+            prop_assert_eq!(model.score().streak, 0);
+        }
+
+        #[test]
+        fn information_only_pays_for_what_is_new(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+            guess in 0..6usize,
+        ) {
+            let mut model = model(level, seed, three, false, false, 0);
+            model.options.scoring = Scoring::Information;
+            let guess = guess % model.guesses().len();
+            let point = model.region_center(Region::Both).unwrap();
+            let points = model.score().points;
+            prop_assert!(model.place(guess, &point).is_some());
+            // Nothing can be unlearned, so red costs nothing either.
+            let told = model.score().points;
+            prop_assert!(told >= points);
+            let home = model.guesses()[guess].home;
+            model.place(guess, &home);
+            model.place(guess, &point);
+            prop_assert_eq!(model.score().points, told);
+        }
+
+        #[test]
+        fn the_sandbox_keeps_its_rail_in_order(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+            cards in proptest::collection::vec(0..64usize, 2..30),
+            taken in 0..30usize,
+        ) {
+            let mut model = model(level, seed, three, false, false, 0);
+            model.options.mode = Mode::Sandbox;
+            model.shapes.clear();
+            let palette = model.palette();
+            for &card in &cards {
+                model.add(palette[card % palette.len()]);
+            }
+            let point = model.region_center(Region::Both).unwrap();
+            model.place(0, &point);
+            prop_assert_eq!(model.score().points, 0);
+            let taken = taken % cards.len();
+            model.remove(taken);
+            prop_assert_eq!(model.guesses().len(), cards.len() - 1);
+            let rail = Rail::new(cards.len() - 1, HEIGHT, model.ui_scale());
+            for (i, guess) in model.guesses().iter().enumerate() {
+                let card = cards[if i < taken { i } else { i + 1 }];
+                prop_assert!(guess.target == palette[card % palette.len()]);
+                prop_assert_eq!(guess.home, rail.slot(i));
+                prop_assert!(guess.center == guess.home || i == 0 && taken != 0);
+            }
+            prop_assert!(!model.is_finished());
+        }
+
+        #[test]
+        fn the_guess_moved_last_is_picked_first(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+        ) {
+            let mut model = model(level, seed, three, false, false, 0);
+            let point = model.region_center(Region::Both).unwrap();
+            // Without a stacking order the later guess would be on top.
+            model.place(0, &point);
+            model.place(1, &point);
+            model.place(0, &Point::new(point.x + 1.0, point.y));
+            prop_assert_eq!(model.guess_at(&point), Some(0));
+            prop_assert_eq!(model.stacking().last().copied(), Some(0));
+            // Picking it up and letting go where it was keeps it on top.
+            prop_assert_eq!(model.pick(&point), Some(0));
+            model.drop(&point);
+            prop_assert_eq!(model.guess_at(&point), Some(0));
+        }
+
+        #[test]
+        fn a_bigger_board_fits_and_keeps_regions(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+            set: bool,
+            steps in 1..=4usize,
+            moves in prop::collection::vec((0usize..20, any::<[bool; 3]>()), 0..8),
+        ) {
+            let mut model = model(level, seed, three, false, set, 0);
+            // The middle of the outside can be right on the edge of a circle.
+            let inside_some = moves.iter().filter(|(_, m)| m.contains(&true));
+            for &(guess, [left, right, bottom]) in inside_some {
+                let guess = guess % model.guesses().len();
+                let region = Region::from_membership(left, right, bottom);
+                if let Some(point) = model.region_center(region) {
+                    model.place(guess, &point);
+                }
+            }
+            let regions: Vec<Region> = model.guesses().iter().map(|g| model.region(&g.center)).collect();
+            let circles: Vec<(Point, f32)> = model.circles().map(|c| (c.center, c.radius)).collect();
+            let inside = |x: f32, y: f32| (-0.5..=WIDTH + 0.5).contains(&x) && (-0.5..=HEIGHT + 0.5).contains(&y);
+            model.rescale(1.0 + steps as f32 * 0.25);
+            for circle in model.circles() {
+                let answer = &circle.answer;
+                prop_assert!(inside(circle.center.x - circle.radius, circle.center.y - circle.radius));
+                prop_assert!(inside(circle.center.x + circle.radius, circle.center.y + circle.radius));
+                prop_assert!(inside(answer.center.x - answer.width / 2.0, answer.center.y - answer.height / 2.0));
+                prop_assert!(inside(answer.center.x + answer.width / 2.0, answer.center.y + answer.height / 2.0));
+            }
+            for (guess, &region) in model.guesses().iter().zip(&regions) {
+                // Guesses on the rail stick out of the left edge a little,
+                // as they always have.
+                prop_assert!(inside(guess.home.x, guess.home.y - guess.radius));
+                prop_assert!(inside(guess.home.x + guess.radius, guess.home.y + guess.radius));
+                prop_assert_eq!(model.region(&guess.center), region);
+            }
+            // Going back to the usual size puts the diagram where it was.
+            model.rescale(1.0);
+            for (circle, &(center, radius)) in model.circles().zip(&circles) {
+                prop_assert!(nalgebra::distance(&circle.center, &center) < 0.01);
+                prop_assert!((circle.radius - radius).abs() < 0.01);
+            }
+        }
+    }
+
+    // Playing whole rounds out is slow, so these try fewer of them.
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn what_the_solver_is_sure_of_is_true(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+            set: bool,
+            moves in prop::collection::vec((0usize..20, any::<[bool; 3]>()), 0..12),
+        ) {
+            let mut model = model(level, seed, three, false, set, 0);
+            for &(guess, [left, right, bottom]) in &moves {
+                let guess = guess % model.guesses().len();
+                let region = Region::from_membership(left, right, bottom);
+                if let Some(point) = model.region_center(region) {
+                    model.place(guess, &point);
+                }
+            }
+            let solver = model.solver();
+            let values = model.current_level().values(model.in_play());
+            for (c, circle) in model.circles().enumerate() {
+                for &value in values.iter().flatten() {
+                    let has = match &circle.compound {
+                        Some(expr) => expr.mentions(value),
+                        None => value.holds(&circle.answer.target),
+                    };
+                    prop_assert!(solver.has(c, value).is_none_or(|sure| sure == has));
+                }
+            }
+        }
+
+        #[test]
+        fn a_round_is_solved_with_every_guess_where_it_belongs(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+        ) {
+            let mut model = model(level, seed, three, false, false, 0);
+            for guess in 0..model.guesses().len() {
+                model.place_for(guess);
+            }
+            prop_assert!(model.is_solved());
+            // Green in only one of the circles it fits isn't enough.
+            let both = (0..model.guesses().len())
+                .find(|&i| model.belongs(&model.guesses()[i].target) == Region::Both);
+            if let Some(guess) = both {
+                let point = model.region_center(Region::LeftOnly).unwrap();
+                model.place(guess, &point);
+                prop_assert!(!model.is_solved());
+            }
+        }
+    }
+
+    // Following the hints works out what is known before every placement,
+    // slower still.
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn following_the_hints_solves_the_round(
+            level in 0..LEVEL_COUNT,
+            seed: u64,
+            three: bool,
+            outside: bool,
+        ) {
+            let mut model = model(level, seed, three, outside, false, 0);
+            // A guess turns red in each region once at most, so this is
+            // plenty.
+            for _ in 0..model.guesses().len() * 16 {
+                if model.is_solved() {
+                    break;
+                }
+                let hint = model.hint();
+                prop_assert!(hint.is_some(), "no hint before the round is solved");
+                let hint = hint.unwrap();
+                let point = model.region_center(hint.region).unwrap();
+                prop_assert!(model.place(hint.guess, &point).is_some());
+            }
+            prop_assert!(model.is_solved());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Playing across two machines on a local network. One hosts and the other
+//! joins, then every move is sent over TCP as a line of JSON so both boards
+//! stay the same.
+
+use crate::{
+    graphics::Point,
+    model::VennModel,
+    rule::RuleExpr,
+    shapes::{Kind, VennTarget},
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+/// What one machine tells the other.
+#[derive(Serialize, Deserialize)]
+pub enum Message {
+    /// A round to play, sent by whoever started it.
+    Puzzle(Box<VennModel>),
+    /// A guess let go of at the point.
+    Move {
+        guess: usize,
+        #[serde(with = "crate::save::point")]
+        to: Point,
+    },
+    /// A card built in the answer box of the circle.
+    Card {
+        circle: usize,
+        card: VennTarget,
+    },
+    /// A compound rule built in the answer box of the circle.
+    Rule {
+        circle: usize,
+        rule: RuleExpr,
+    },
+    /// The attribute named in the answer box of the circle in Set mode.
+    Kind {
+        circle: usize,
+        kind: Kind,
+    },
+    GiveUp,
+}
+
+/// The connection to the other machine.
+pub struct Link {
+    stream: TcpStream,
+    // Filled by a thread reading from the stream, so the game never waits.
+    incoming: Receiver<Message>,
+    hosting: bool,
+}
+
+impl Link {
+    /// Waits for the other player to join on the port.
+    pub fn host(port: u16) -> io::Result<Link> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let (stream, _) = listener.accept()?;
+        Link::new(stream, true)
+    }
+
+    /// Joins the game hosted at the address, like "192.168.1.2:7878".
+    pub fn join(address: &str) -> io::Result<Link> {
+        Link::new(TcpStream::connect(address)?, false)
+    }
+
+    fn new(stream: TcpStream, hosting: bool) -> io::Result<Link> {
+        stream.set_nodelay(true)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+                match serde_json::from_str(&line) {
+                    Ok(message) => {
+                        if sender.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    Err(error) => warn!("could not read a message: {}", error),
+                }
+            }
+        });
+        Ok(Link {
+            stream,
+            incoming,
+            hosting,
+        })
+    }
+
+    /// Whether this machine is the host, which starts the rounds.
+    pub fn is_host(&self) -> bool {
+        self.hosting
+    }
+
+    /// Which of the two players this machine is. The host goes first.
+    pub fn player(&self) -> usize {
+        if self.hosting {
+            0
+        } else {
+            1
+        }
+    }
+
+    pub fn send(&mut self, message: &Message) -> io::Result<()> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        self.stream.write_all(line.as_bytes())
+    }
+
+    /// The messages that came in since the last call. Fails once the other
+    /// machine is gone.
+    pub fn receive(&mut self) -> io::Result<Vec<Message>> {
+        let mut messages = Vec::new();
+        loop {
+            match self.incoming.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Empty) => return Ok(messages),
+                Err(TryRecvError::Disconnected) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "the other player left",
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Waits for the next message, like the first puzzle when joining.
+    pub fn wait(&mut self) -> io::Result<Message> {
+        self.incoming
+            .recv()
+            .map_err(|_| io::Error::new(io::ErrorKind::ConnectionAborted, "the other player left"))
+    }
+}
@@ -0,0 +1,105 @@
+//! Notes the player keeps on the hidden cards, like the detective notes in
+//! Clue: for every circle, each value the level cares about can be marked
+//! as ruled out or as certain. The notes last for the round. The assistant
+//! fills in what the solver has worked out from the guesses that turned
+//! green or red and the answers checked so far.
+
+use crate::{model::VennModel, shapes::Value};
+
+/// What the player noted about a value for a circle.
+#[derive(PartialEq, Copy, Clone, Default)]
+pub enum Mark {
+    #[default]
+    Unknown,
+    /// The circle's card can't have the value.
+    Eliminated,
+    /// The circle's card has the value.
+    Confirmed,
+}
+
+impl Mark {
+    /// The mark clicking this one turns it into.
+    pub fn next(self) -> Mark {
+        match self {
+            Mark::Unknown => Mark::Eliminated,
+            Mark::Eliminated => Mark::Confirmed,
+            Mark::Confirmed => Mark::Unknown,
+        }
+    }
+}
+
+/// The marks of the round, by circle and value. Values left unknown aren't
+/// kept.
+#[derive(Default)]
+pub struct Notebook {
+    marks: Vec<(usize, Value, Mark)>,
+    // Whether the assistant marks what is certain. It stays on from round
+    // to round.
+    assistant: bool,
+}
+
+impl Notebook {
+    pub fn mark(&self, circle: usize, value: Value) -> Mark {
+        self.marks
+            .iter()
+            .find(|&&(c, v, _)| c == circle && v == value)
+            .map_or(Mark::Unknown, |&(_, _, mark)| mark)
+    }
+
+    pub fn set(&mut self, circle: usize, value: Value, mark: Mark) {
+        self.marks.retain(|&(c, v, _)| c != circle || v != value);
+        if mark != Mark::Unknown {
+            self.marks.push((circle, value, mark));
+        }
+    }
+
+    /// Moves the value on to the next mark for the circle.
+    pub fn cycle(&mut self, circle: usize, value: Value) {
+        self.set(circle, value, self.mark(circle, value).next());
+    }
+
+    pub fn clear(&mut self) {
+        self.marks.clear();
+    }
+
+    pub fn is_assisting(&self) -> bool {
+        self.assistant
+    }
+
+    /// Turns the assistant on or off, marking what is certain at once when
+    /// it comes on. Marks it made stay when it goes off.
+    pub fn toggle_assistant(&mut self, model: &VennModel) {
+        self.assistant = !self.assistant;
+        self.assist(model);
+    }
+
+    /// Crosses out every value a circle's card can't have any more and
+    /// confirms every value it must have, if the assistant is on. Marks the
+    /// player made that disagree are replaced.
+    pub fn assist(&mut self, model: &VennModel) {
+        if !self.assistant {
+            return;
+        }
+        let solver = model.solver();
+        for circle in 0..model.circles().count() {
+            for &(_, value) in &rows(model) {
+                match solver.has(circle, value) {
+                    Some(true) => self.set(circle, value, Mark::Confirmed),
+                    Some(false) => self.set(circle, value, Mark::Eliminated),
+                    None => {}
+                }
+            }
+        }
+    }
+}
+
+/// The values the level cares about, in the order the notebook lists them,
+/// each with the number of the attribute it is a value of.
+pub fn rows(model: &VennModel) -> Vec<(usize, Value)> {
+    let values = model.current_level().values(model.in_play());
+    values
+        .into_iter()
+        .enumerate()
+        .flat_map(|(group, values)| values.into_iter().map(move |value| (group, value)))
+        .collect()
+}
@@ -0,0 +1,218 @@
+//! Packs of hand written puzzles to play through as a campaign. A pack is
+//! a directory of puzzle files with a `pack.json` manifest that gives its
+//! name, the order of the puzzles and what unlocks each of them.
+
+use crate::{profile, puzzle};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// What has to be solved before a puzzle of a pack can be played.
+#[derive(Deserialize, Default)]
+pub enum Unlock {
+    /// The puzzle before it in the pack. The first one is always open.
+    #[default]
+    Previous,
+    /// Nothing.
+    Open,
+    /// Every one of these puzzles of the pack, by file name.
+    Solved(Vec<String>),
+    /// This many of the pack's puzzles, whichever they are.
+    Count(usize),
+}
+
+/// A puzzle of a pack as listed in its manifest.
+#[derive(Deserialize)]
+pub struct Entry {
+    /// The puzzle file, relative to the pack's directory.
+    pub file: String,
+    #[serde(default)]
+    pub unlock: Unlock,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    name: String,
+    puzzles: Vec<Entry>,
+}
+
+/// A pack read from its directory.
+pub struct Pack {
+    pub name: String,
+    /// The directory name, which progress is kept under.
+    pub id: String,
+    dir: PathBuf,
+    pub entries: Vec<Entry>,
+}
+
+impl Pack {
+    /// Reads the pack's manifest and checks every puzzle in it.
+    pub fn read(dir: &Path) -> io::Result<Pack> {
+        let contents = fs::read_to_string(dir.join("pack.json"))?;
+        let manifest: Manifest = serde_json::from_str(&contents)?;
+        let invalid = |error: String| io::Error::new(io::ErrorKind::InvalidData, error);
+        if manifest.puzzles.is_empty() {
+            return Err(invalid("a pack needs at least one puzzle".to_string()));
+        }
+        for entry in &manifest.puzzles {
+            puzzle::read(&dir.join(&entry.file))
+                .map_err(|error| invalid(format!("{}: {}", entry.file, error)))?;
+            let unknown = match &entry.unlock {
+                Unlock::Solved(files) => files
+                    .iter()
+                    .find(|file| !manifest.puzzles.iter().any(|e| &e.file == *file)),
+                _ => None,
+            };
+            if let Some(file) = unknown {
+                return Err(invalid(format!(
+                    "{} is unlocked by {}, which is not in the pack",
+                    entry.file, file
+                )));
+            }
+        }
+        let id = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| invalid(format!("{} is not a pack directory", dir.display())))?
+            .to_string();
+        Ok(Pack {
+            name: manifest.name,
+            id,
+            dir: dir.to_path_buf(),
+            entries: manifest.puzzles,
+        })
+    }
+
+    /// Reads the puzzle of the pack.
+    pub fn puzzle(&self, index: usize) -> io::Result<puzzle::Puzzle> {
+        puzzle::read(&self.dir.join(&self.entries[index].file))
+    }
+
+    /// Whether what has been solved opens up the puzzle.
+    pub fn is_unlocked(&self, index: usize, progress: &Progress) -> bool {
+        match &self.entries[index].unlock {
+            Unlock::Previous => index == 0 || self.is_solved(index - 1, progress),
+            Unlock::Open => true,
+            Unlock::Solved(files) => files.iter().all(|file| progress.is_solved(&self.id, file)),
+            Unlock::Count(count) => self.solved(progress) >= *count,
+        }
+    }
+
+    pub fn is_solved(&self, index: usize, progress: &Progress) -> bool {
+        progress.is_solved(&self.id, &self.entries[index].file)
+    }
+
+    /// How many of the pack's puzzles have been solved.
+    pub fn solved(&self, progress: &Progress) -> usize {
+        (0..self.entries.len())
+            .filter(|&i| self.is_solved(i, progress))
+            .count()
+    }
+
+    /// The first puzzle that is open and not solved yet, or None once the
+    /// pack is done or the rest is still locked.
+    pub fn next(&self, progress: &Progress) -> Option<usize> {
+        (0..self.entries.len())
+            .find(|&i| !self.is_solved(i, progress) && self.is_unlocked(i, progress))
+    }
+}
+
+/// Where the packs are kept, if there is a data directory.
+pub fn dir() -> Option<PathBuf> {
+    profile::root().map(|root| root.join("packs"))
+}
+
+/// The packs there are, in order of their directory names. Packs that
+/// can't be read are left out.
+pub fn list() -> Vec<Pack> {
+    let entries = match dir().map(fs::read_dir) {
+        Some(Ok(entries)) => entries,
+        _ => return vec![],
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs.iter()
+        .filter_map(|dir| {
+            Pack::read(dir)
+                .map_err(|error| warn!("could not read pack {}: {}", dir.display(), error))
+                .ok()
+        })
+        .collect()
+}
+
+/// The puzzles solved in each pack, by pack and file name.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Progress {
+    solved: BTreeMap<String, Vec<String>>,
+}
+
+impl Progress {
+    pub fn is_solved(&self, pack: &str, file: &str) -> bool {
+        self.solved
+            .get(pack)
+            .is_some_and(|files| files.iter().any(|f| f == file))
+    }
+
+    /// Marks the puzzle of the pack as solved.
+    pub fn solve(&mut self, pack: &str, file: &str) {
+        if !self.is_solved(pack, file) {
+            self.solved
+                .entry(pack.to_string())
+                .or_default()
+                .push(file.to_string());
+        }
+    }
+}
+
+/// Where the progress is kept when no profile is played with.
+pub fn progress_path() -> Option<PathBuf> {
+    profile::root().map(|root| root.join("progress.json"))
+}
+
+/// Reads the progress, which starts out empty if there is none yet.
+pub fn read_progress(path: &Path) -> io::Result<Progress> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(serde_json::from_str(&contents)?),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Progress::default()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Writes the progress, making the directory for it if needed.
+pub fn write_progress(path: &Path, progress: &Progress) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(progress)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example pack opens up one puzzle at a time, and the last one
+    // once any two are solved.
+    #[test]
+    fn the_starter_pack_unlocks_in_order() {
+        let pack = Pack::read(Path::new("puzzles/starter")).unwrap();
+        let mut progress = Progress::default();
+        assert_eq!(pack.next(&progress), Some(0));
+        assert!(!pack.is_unlocked(1, &progress));
+        progress.solve(&pack.id, "shapes.json");
+        assert_eq!(pack.next(&progress), Some(1));
+        assert!(!pack.is_unlocked(2, &progress));
+        progress.solve(&pack.id, "colors.json");
+        assert_eq!(pack.next(&progress), Some(2));
+        progress.solve(&pack.id, "overlap.json");
+        assert_eq!(pack.next(&progress), None);
+        assert_eq!(pack.solved(&progress), 3);
+    }
+}
@@ -0,0 +1,80 @@
+//! Colors to draw the game with, and patterns that tell the colors apart
+//! without relying on hue.
+
+use crate::{
+    colors::{BLUE, PURPLE, YELLOW},
+    graphics::Color,
+    shapes::VennColor,
+    theme::Theme,
+};
+use serde::{Deserialize, Serialize};
+
+/// A set of colors for the cards and for right and wrong placements.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Palette {
+    Standard,
+    /// The Okabe-Ito colors, which stay distinct with the common forms of
+    /// color blindness. Right and wrong are blue and orange instead of green
+    /// and red.
+    Colorblind,
+}
+
+impl Palette {
+    pub fn color(self, color: VennColor) -> Color {
+        match (self, color) {
+            (Palette::Standard, VennColor::Yellow) => YELLOW,
+            (Palette::Standard, VennColor::Blue) => BLUE,
+            (Palette::Standard, VennColor::Purple) => PURPLE,
+            (Palette::Colorblind, VennColor::Yellow) => Color::from_rgb(0xF0, 0xE4, 0x42),
+            (Palette::Colorblind, VennColor::Blue) => Color::from_rgb(0x00, 0x72, 0xB2),
+            (Palette::Colorblind, VennColor::Purple) => Color::from_rgb(0xCC, 0x79, 0xA7),
+        }
+    }
+}
+
+/// How the game is drawn. Unlike the options, this has no bearing on play.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Style {
+    pub palette: Palette,
+    /// Overlay each card color with its own pattern: stripes for yellow,
+    /// dots for blue and cross-hatching for purple.
+    pub patterns: bool,
+    pub theme: Theme,
+    /// How many times as thick as usual lines are and as big as usual the
+    /// shapes on cards and the text over the board.
+    pub scale: f32,
+}
+
+impl Style {
+    /// The background of a guess placed right.
+    pub fn right(&self) -> Color {
+        match self.palette {
+            Palette::Standard => self.theme.right,
+            Palette::Colorblind => Color::from_rgb(0x56, 0xB4, 0xE9),
+        }
+    }
+
+    /// The background of a guess placed wrong.
+    pub fn wrong(&self) -> Color {
+        match self.palette {
+            Palette::Standard => self.theme.wrong,
+            Palette::Colorblind => Color::from_rgb(0xE6, 0x9F, 0x00),
+        }
+    }
+
+    /// A line width scaled up with everything else.
+    pub fn width(&self, width: u16) -> u16 {
+        (width as f32 * self.scale).round() as u16
+    }
+}
+
+impl Default for Style {
+    fn default() -> Style {
+        Style {
+            palette: Palette::Standard,
+            patterns: false,
+            theme: Theme::LIGHT,
+            scale: 1.0,
+        }
+    }
+}
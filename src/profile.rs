@@ -0,0 +1,93 @@
+//! Named players sharing a machine, each with their own statistics,
+//! settings and saved game.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A player and the directory their things are kept in.
+pub struct Profile {
+    pub name: String,
+    dir: PathBuf,
+}
+
+impl Profile {
+    /// The profile with the name, which doesn't have to exist yet. Names
+    /// are kept to letters, digits, spaces, dashes and underscores so they
+    /// make safe directory names.
+    pub fn new(name: &str) -> io::Result<Profile> {
+        let valid = name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_');
+        if name.trim().is_empty() || !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} is not a valid profile name", name),
+            ));
+        }
+        let root = root()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "there is no data directory"))?;
+        Ok(Profile {
+            name: name.to_string(),
+            dir: root.join("profiles").join(name),
+        })
+    }
+
+    /// Makes the profile's directory, so it shows up in the list.
+    pub fn create(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)
+    }
+
+    pub fn config_path(&self) -> PathBuf {
+        self.dir.join("config.toml")
+    }
+
+    pub fn stats_path(&self) -> PathBuf {
+        self.dir.join("stats.json")
+    }
+
+    /// Where the puzzles solved in each pack are kept.
+    pub fn progress_path(&self) -> PathBuf {
+        self.dir.join("progress.json")
+    }
+
+    /// Where the game is saved, so the player picks up where they left off.
+    pub fn save_path(&self) -> PathBuf {
+        self.dir.join("save.json")
+    }
+}
+
+/// The names of the profiles that have been played with, in order.
+pub fn list() -> Vec<String> {
+    let entries = match root().map(|root| fs::read_dir(root.join("profiles"))) {
+        Some(Ok(entries)) => entries,
+        _ => return vec![],
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names
+}
+
+/// The directory venn keeps its files in, if there is a data directory.
+pub fn root() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("venn"))
+}
+
+// The platform's place for application data, like ~/.local/share.
+fn data_dir() -> Option<PathBuf> {
+    let var = |name| env::var_os(name).filter(|value| !value.is_empty());
+    if cfg!(windows) {
+        var("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| Path::new(&home).join("Library/Application Support"))
+    } else {
+        var("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| Path::new(&home).join(".local/share")))
+    }
+}
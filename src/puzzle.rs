@@ -0,0 +1,271 @@
+//! Hand written puzzles, for when a specific exercise is wanted instead of
+//! a generated one.
+
+use crate::{
+    model::LEVELS,
+    region::Outline,
+    rule::{Rule, RuleExpr},
+    shapes::{
+        Attribute, Value, VennCount, VennFill, VennOrientation, VennPattern, VennShape, VennTarget,
+    },
+    tokens::{self, Tokens},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A puzzle as described in a puzzle file.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Puzzle {
+    /// Index into the level progression. Decides which attributes count,
+    /// the region semantics and the scoring.
+    pub level: usize,
+    /// Two circles, or three to play with a bottom circle.
+    pub circles: Vec<PuzzleCircle>,
+    /// The shapes in play, which the guesses come in every color and size
+    /// of. Defaults to the level's shapes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shapes: Option<Vec<VennShape>>,
+    /// The guesses to play with, instead of every combination. Defaults to
+    /// the level's usual set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guesses: Option<Vec<VennTarget>>,
+    /// A CSV file of cards of the puzzle's own, next to the puzzle file,
+    /// which the guesses default to instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<PathBuf>,
+    /// Cards of the puzzle's own shown as pictures, instead of a tokens
+    /// file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pictures: Option<Pictures>,
+    /// The cards of the tokens file or the pictures, once read.
+    #[serde(skip)]
+    pub table: Option<Tokens>,
+}
+
+/// Cards shown as pictures, with their attributes.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Pictures {
+    /// What the cards are, like animal.
+    pub name: String,
+    /// The attributes the cards differ in, like habitat.
+    pub attributes: Vec<String>,
+    pub cards: Vec<Picture>,
+}
+
+/// A card shown as a picture.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Picture {
+    pub name: String,
+    /// A PNG file next to the puzzle file.
+    pub image: PathBuf,
+    /// The card's value of each attribute, in order.
+    pub values: Vec<String>,
+}
+
+impl Pictures {
+    /// The cards, with their pictures in the directory of the puzzle file.
+    fn tokens(&self, dir: &Path) -> Result<Tokens, String> {
+        let rows = self
+            .cards
+            .iter()
+            .map(|card| (card.name.clone(), card.values.clone()));
+        let mut table = tokens::build(&self.name, &self.attributes, rows.collect())?;
+        for (token, card) in table.tokens.iter_mut().zip(&self.cards) {
+            let image = dir.join(&card.image);
+            // Checked now, so a picture that won't show is found before
+            // playing.
+            let file = fs::File::open(&image).map_err(|e| format!("{}: {}", image.display(), e))?;
+            png::Decoder::new(file)
+                .read_info()
+                .map_err(|e| format!("{} is not a PNG: {}", image.display(), e))?;
+            token.image = Some(image);
+        }
+        Ok(table)
+    }
+}
+
+/// One circle of a puzzle file.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PuzzleCircle {
+    pub rule: Rule,
+    /// The hidden card in the circle's answer box.
+    #[serde(default)]
+    pub card: VennTarget,
+    /// The token of the puzzle's own cards to use as the hidden card, by
+    /// name, instead of the card.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// A compound rule to use instead of the rule and card, like
+    /// `{"And": [{"Is": {"Color": "Blue"}}, {"Is": {"Shape": "Triangle"}}]}`
+    /// or `{"Not": {"Is": {"Color": "Blue"}}}` for everything except blue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compound: Option<RuleExpr>,
+    /// A compound rule in the words of the puzzle's own cards instead, the
+    /// value a guess has to have in each column, like `{"habitat":
+    /// "water"}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has: Option<BTreeMap<String, String>>,
+    /// Overrides where the circle is drawn. Its answer box moves with it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub center: Option<(f32, f32)>,
+    /// Half the width of the circle, or of the set if it has an outline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f32>,
+    /// Makes the set an ellipse, like `{"Ellipse": {"ratio": 0.6, "angle":
+    /// 30}}`, or a rounded rectangle, like `{"Rounded": {"ratio": 0.5,
+    /// "corner": 0.4}}`, instead of a circle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outline: Option<Outline>,
+    /// Written in the circle while the round is played, like `"Has 3
+    /// sides"`, to put the rule in words. The rule and card still decide
+    /// which guesses fit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Reads and checks a puzzle file.
+pub fn read(path: &Path) -> io::Result<Puzzle> {
+    let contents = fs::read_to_string(path)?;
+    let mut puzzle: Puzzle = serde_json::from_str(&contents)?;
+    let invalid = |error: String| io::Error::new(io::ErrorKind::InvalidData, error);
+    if puzzle.level >= LEVELS.len() {
+        return Err(invalid(format!(
+            "level {} does not exist, there are {} levels",
+            puzzle.level,
+            LEVELS.len()
+        )));
+    }
+    if puzzle.circles.len() != 2 && puzzle.circles.len() != 3 {
+        return Err(invalid(format!(
+            "a puzzle needs 2 or 3 circles, not {}",
+            puzzle.circles.len()
+        )));
+    }
+    for circle in &puzzle.circles {
+        if let Some(radius) = circle.radius.filter(|r| !(r.is_finite() && *r > 0.0)) {
+            return Err(invalid(format!(
+                "a circle needs a finite radius above 0, not {}",
+                radius
+            )));
+        }
+        if let Some((x, y)) = circle
+            .center
+            .filter(|(x, y)| !(x.is_finite() && y.is_finite()))
+        {
+            return Err(invalid(format!(
+                "a circle needs a finite center, not ({}, {})",
+                x, y
+            )));
+        }
+        if let Some(outline) = circle.outline {
+            outline.check().map_err(invalid)?;
+        }
+    }
+    if puzzle.shapes.as_ref().is_some_and(|s| s.is_empty()) {
+        return Err(invalid("a puzzle needs at least one shape".to_string()));
+    }
+    if puzzle.guesses.as_ref().is_some_and(|g| g.is_empty()) {
+        return Err(invalid("a puzzle needs at least one guess".to_string()));
+    }
+    let cards = puzzle.circles.iter().map(|circle| &circle.card);
+    for target in cards.chain(puzzle.guesses.iter().flatten()) {
+        if !VennCount::variants().contains(&target.count) {
+            return Err(invalid(format!(
+                "a card shows 1 to 3 shapes, not {}",
+                target.count.0
+            )));
+        }
+        if target.orientation == VennOrientation::Rotated && !target.shape.turns() {
+            return Err(invalid(format!(
+                "only squares and triangles can be rotated, not a {:?}",
+                target.shape
+            )));
+        }
+        if target.fill == VennFill::Outlined && target.pattern != VennPattern::Solid {
+            return Err(invalid(
+                "an outlined card has no pattern, leave it solid".to_string(),
+            ));
+        }
+    }
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let table = match (&puzzle.tokens, &puzzle.pictures) {
+        (Some(_), Some(_)) => {
+            return Err(invalid(
+                "a puzzle has tokens or pictures, not both".to_string(),
+            ))
+        }
+        (Some(file), None) => {
+            let file = dir.join(file);
+            let table = tokens::read(&file)
+                .map_err(|error| invalid(format!("{}: {}", file.display(), error)))?;
+            Some(table)
+        }
+        (None, Some(pictures)) => Some(pictures.tokens(dir).map_err(invalid)?),
+        (None, None) => None,
+    };
+    if let Some(table) = table {
+        if puzzle.shapes.is_some() {
+            return Err(invalid(
+                "a puzzle with tokens plays with those, not shapes".to_string(),
+            ));
+        }
+        // The level has to compare every column, and nothing else.
+        let level = &LEVELS[puzzle.level];
+        let values = level.values(&table.shapes());
+        let kinds = values
+            .iter()
+            .filter_map(|values| Some(values.first()?.kind()));
+        if !kinds.eq(table.columns.iter().map(|column| column.kind)) {
+            return Err(invalid(format!(
+                "level {} doesn't compare the {} attributes of the tokens",
+                puzzle.level,
+                table.columns.len()
+            )));
+        }
+        for (column, values) in table.columns.iter().zip(&values) {
+            if column.values.len() > values.len() {
+                return Err(invalid(format!(
+                    "level {} has room for {} values of {}, the tokens have {}",
+                    puzzle.level,
+                    values.len(),
+                    column.name,
+                    column.values.len()
+                )));
+            }
+        }
+        for circle in &mut puzzle.circles {
+            if let Some(name) = &circle.token {
+                circle.card = match table.find(name) {
+                    Some(token) => token.target,
+                    None => return Err(invalid(format!("there is no token called {}", name))),
+                };
+            }
+            if let Some(has) = &circle.has {
+                let values: Result<Vec<Value>, String> = has
+                    .iter()
+                    .map(|(column, word)| table.value(column, word))
+                    .collect();
+                circle.compound = RuleExpr::join(&values.map_err(invalid)?, false);
+            }
+        }
+        puzzle.table = Some(table);
+    } else if puzzle
+        .circles
+        .iter()
+        .any(|c| c.token.is_some() || c.has.is_some())
+    {
+        return Err(invalid(
+            "a circle goes by tokens but the puzzle has no tokens file".to_string(),
+        ));
+    }
+    Ok(puzzle)
+}
+
+/// Writes the puzzle file, as the editor saves it.
+pub fn write(path: &Path, puzzle: &Puzzle) -> io::Result<()> {
+    fs::write(path, serde_json::to_string_pretty(puzzle)?)
+}
@@ -0,0 +1,62 @@
+//! The strip along the left edge where guesses wait to be placed.
+
+use crate::graphics::Point;
+
+// Room between guesses when there is enough of it.
+const SPACING: f32 = 40.0;
+// More columns than this would run into the diagram at the usual size, so
+// guesses get smaller instead.
+const MAX_COLUMNS: usize = 2;
+
+/// Lays out home slots for a number of guesses, filling a column down the
+/// left edge before starting another.
+pub struct Rail {
+    spacing: f32,
+    rows: usize,
+    columns: usize,
+    scale: f32,
+}
+
+impl Rail {
+    /// Fits count guesses into a window this high, with guesses scale
+    /// times as big as usual. Bigger guesses may take more columns, as the
+    /// diagram makes room for them.
+    pub fn new(count: usize, height: f32, scale: f32) -> Rail {
+        let mut spacing = SPACING * scale;
+        let room = height - spacing;
+        let mut rows = ((room / spacing) as usize).max(1);
+        let max_columns = ((MAX_COLUMNS as f32 * scale).round() as usize).max(MAX_COLUMNS);
+        if count > rows * max_columns {
+            rows = count.div_ceil(max_columns);
+            spacing = room / rows as f32;
+        }
+        let columns = count.div_ceil(rows).max(1);
+        Rail {
+            spacing,
+            rows,
+            columns,
+            scale,
+        }
+    }
+
+    /// Where the guess with the index lives.
+    pub fn slot(&self, index: usize) -> Point {
+        let column = index / self.rows;
+        let row = index % self.rows;
+        Point::new(
+            20.0 * self.scale + column as f32 * self.spacing,
+            (row + 1) as f32 * self.spacing,
+        )
+    }
+
+    /// How big guesses are drawn, so neighbours overlap as little as they
+    /// do with the usual spacing.
+    pub fn radius(&self) -> f32 {
+        self.spacing * 0.75
+    }
+
+    /// How far from the left edge the guesses in the last column reach.
+    pub fn right(&self) -> f32 {
+        self.slot((self.columns - 1) * self.rows).x + self.radius()
+    }
+}
@@ -0,0 +1,105 @@
+//! Recording a round as an animated GIF to share how it was solved. The
+//! board is drawn into a small picture a few times a second and each new
+//! picture is written to the file as it comes.
+
+use crate::screenshot::{self, Raster};
+use gif::SetParameter;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::PathBuf,
+};
+
+// The size of the recording next to the window.
+const SCALE: f32 = 0.5;
+// Seconds between pictures, which makes ten a second.
+const INTERVAL: f32 = 0.1;
+// Hundredths of a second each picture shows for.
+const DELAY: u16 = 10;
+// How hard to work on the colors of each picture, from 1 (best) to 30
+// (fastest).
+const SPEED: i32 = 30;
+
+/// A round being recorded.
+pub struct Recorder {
+    path: PathBuf,
+    encoder: gif::Encoder<BufWriter<File>>,
+    scale: f32,
+    size: (usize, usize),
+    // Seconds since the last picture.
+    since: f32,
+    // The last picture, written once a different one comes or the round
+    // ends, so a board that stays still makes one long frame.
+    pending: Option<(Vec<u8>, u16)>,
+}
+
+impl Recorder {
+    /// Starts recording to a file named after the time in the recordings
+    /// directory, for a window scaled by the factor.
+    pub fn new(scale: f32) -> io::Result<Recorder> {
+        let path =
+            screenshot::dir("recordings").join(format!("venn-{}.gif", screenshot::timestamp()));
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let size = Raster::new(scale * SCALE).size();
+        let (width, height) = size;
+        let file = BufWriter::new(File::create(&path)?);
+        let mut encoder = gif::Encoder::new(file, width as u16, height as u16, &[])?;
+        encoder.set(gif::Repeat::Infinite)?;
+        Ok(Recorder {
+            path,
+            encoder,
+            scale: scale * SCALE,
+            size,
+            since: 0.0,
+            pending: None,
+        })
+    }
+
+    /// Lets the seconds of play pass, drawing a picture with the function
+    /// every tenth of a second.
+    pub fn tick(&mut self, seconds: f32, draw: impl FnOnce(&mut Raster)) -> io::Result<()> {
+        self.since += seconds;
+        if self.since < INTERVAL {
+            return Ok(());
+        }
+        self.since -= INTERVAL;
+        let mut raster = Raster::new(self.scale);
+        draw(&mut raster);
+        let pixels = raster.into_pixels();
+        match &mut self.pending {
+            Some((last, delay)) if *last == pixels => *delay += DELAY,
+            _ => {
+                self.flush()?;
+                self.pending = Some((pixels, DELAY));
+            }
+        }
+        Ok(())
+    }
+
+    // Writes the picture waiting to be written.
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some((mut pixels, delay)) = self.pending.take() {
+            let (width, height) = self.size;
+            let mut frame =
+                gif::Frame::from_rgba_speed(width as u16, height as u16, &mut pixels, SPEED);
+            frame.delay = delay;
+            self.encoder.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+
+    /// Ends the recording, returning the file it went to.
+    pub fn finish(mut self) -> io::Result<PathBuf> {
+        self.flush()?;
+        Ok(self.path)
+    }
+
+    /// Stops recording and deletes what was recorded.
+    pub fn discard(self) -> io::Result<()> {
+        let path = self.path.clone();
+        drop(self);
+        fs::remove_file(path)
+    }
+}
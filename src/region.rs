@@ -0,0 +1,483 @@
+//! The parts of the diagram and how points are sorted into them. The
+//! geometry is kept to plain functions of the sets and boxes so it can be
+//! checked on its own.
+
+use crate::graphics::Point;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+/// The part of the diagram a point falls in.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Region {
+    LeftOnly,
+    RightOnly,
+    /// The overlap of the left and right circles, outside of the bottom one.
+    Both,
+    // The remaining regions only exist in three set games.
+    BottomOnly,
+    LeftBottom,
+    RightBottom,
+    /// The middle of a three set diagram, inside every circle.
+    All,
+    Outside,
+}
+
+impl Region {
+    /// The region for a point inside the given circles.
+    pub fn from_membership(left: bool, right: bool, bottom: bool) -> Region {
+        match (left, right, bottom) {
+            (true, false, false) => Region::LeftOnly,
+            (false, true, false) => Region::RightOnly,
+            (true, true, false) => Region::Both,
+            (false, false, true) => Region::BottomOnly,
+            (true, false, true) => Region::LeftBottom,
+            (false, true, true) => Region::RightBottom,
+            (true, true, true) => Region::All,
+            (false, false, false) => Region::Outside,
+        }
+    }
+
+    /// Whether the region is inside the left, right and bottom circles.
+    pub fn membership(self) -> [bool; 3] {
+        match self {
+            Region::LeftOnly => [true, false, false],
+            Region::RightOnly => [false, true, false],
+            Region::Both => [true, true, false],
+            Region::BottomOnly => [false, false, true],
+            Region::LeftBottom => [true, false, true],
+            Region::RightBottom => [false, true, true],
+            Region::All => [true, true, true],
+            Region::Outside => [false, false, false],
+        }
+    }
+
+    /// The region in set notation, with A for the left circle, B for the
+    /// right one and C for the bottom one of a diagram with that many
+    /// circles. Sets next to each other are intersected and a prime is the
+    /// complement, so AB′ is in the left circle but not the right one.
+    pub fn notation(self, circles: usize) -> String {
+        self.membership()
+            .iter()
+            .zip(&['A', 'B', 'C'])
+            .take(circles)
+            .flat_map(
+                |(&inside, &set)| {
+                    if inside {
+                        vec![set]
+                    } else {
+                        vec![set, '′']
+                    }
+                },
+            )
+            .collect()
+    }
+}
+
+/// The shape of the edge of a set. Whatever the shape, the set is twice its
+/// radius wide before it is turned.
+#[derive(PartialEq, Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub enum Outline {
+    #[default]
+    Circle,
+    /// An ellipse ratio times as tall as it is wide, turned clockwise by
+    /// angle degrees.
+    Ellipse { ratio: f32, angle: f32 },
+    /// A rectangle ratio times as tall as it is wide, its corners rounded
+    /// off as far as corner times half its shorter side, so 0 leaves them
+    /// square and 1 rounds the short sides into half circles.
+    Rounded { ratio: f32, corner: f32 },
+}
+
+impl Outline {
+    /// Why the outline can't be drawn, if it can't.
+    pub fn check(self) -> Result<(), String> {
+        match self {
+            Outline::Circle => Ok(()),
+            // NaN is neither above 0 nor in the range of the corner.
+            Outline::Ellipse { ratio, angle }
+                if ratio.is_finite() && ratio > 0.0 && angle.is_finite() =>
+            {
+                Ok(())
+            }
+            Outline::Rounded { ratio, corner }
+                if ratio.is_finite() && ratio > 0.0 && (0.0..=1.0).contains(&corner) =>
+            {
+                Ok(())
+            }
+            Outline::Ellipse { ratio, angle } => Err(format!(
+                "an ellipse needs a finite ratio above 0 and a finite angle, not {} and {}",
+                ratio, angle
+            )),
+            Outline::Rounded { ratio, corner } => Err(format!(
+                "a rounded rectangle needs a finite ratio above 0 and a corner from 0 to 1, \
+                 not {} and {}",
+                ratio, corner
+            )),
+        }
+    }
+}
+
+/// Where a set is and the shape of its edge.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Boundary {
+    pub center: Point,
+    /// Half the width, before it is turned.
+    pub radius: f32,
+    pub outline: Outline,
+}
+
+impl Boundary {
+    pub fn circle(center: Point, radius: f32) -> Boundary {
+        Boundary {
+            center,
+            radius,
+            outline: Outline::Circle,
+        }
+    }
+
+    /// Whether the point is inside the set. A point on the edge is outside.
+    pub fn contains(&self, point: &Point) -> bool {
+        let (center, radius) = (&self.center, self.radius);
+        match self.outline {
+            Outline::Circle => in_circle(point, center, radius),
+            Outline::Ellipse { ratio, angle } => {
+                in_ellipse(point, center, (radius, radius * ratio), angle)
+            }
+            Outline::Rounded { ratio, corner } => {
+                let corner = corner * radius.min(radius * ratio);
+                in_rounded(point, center, radius * 2.0, radius * ratio * 2.0, corner)
+            }
+        }
+    }
+
+    /// How far the set reaches from its center sideways and up and down.
+    pub fn extent(&self) -> (f32, f32) {
+        match self.outline {
+            Outline::Circle => (self.radius, self.radius),
+            Outline::Ellipse { ratio, angle } => {
+                let (a, b) = (self.radius, self.radius * ratio);
+                let (sin, cos) = angle.to_radians().sin_cos();
+                (
+                    (a * a * cos * cos + b * b * sin * sin).sqrt(),
+                    (a * a * sin * sin + b * b * cos * cos).sqrt(),
+                )
+            }
+            Outline::Rounded { ratio, .. } => (self.radius, self.radius * ratio),
+        }
+    }
+
+    /// The same shape the distance further out all round.
+    pub fn grown(&self, by: f32) -> Boundary {
+        let radius = self.radius + by;
+        let outline = match self.outline {
+            Outline::Circle => Outline::Circle,
+            Outline::Ellipse { ratio, angle } => Outline::Ellipse {
+                ratio: (self.radius * ratio + by) / radius,
+                angle,
+            },
+            Outline::Rounded { ratio, corner } => {
+                let short = self.radius.min(self.radius * ratio);
+                Outline::Rounded {
+                    ratio: (self.radius * ratio + by) / radius,
+                    corner: (corner * short + by) / (short + by),
+                }
+            }
+        };
+        Boundary {
+            radius,
+            outline,
+            ..*self
+        }
+    }
+
+    /// Points along the edge a few pixels apart, clockwise on screen from
+    /// the right. The first isn't repeated at the end.
+    pub fn edge(&self) -> Vec<Point> {
+        let (center, radius) = (self.center, self.radius);
+        match self.outline {
+            Outline::Circle | Outline::Ellipse { .. } => {
+                let (ratio, angle) = match self.outline {
+                    Outline::Ellipse { ratio, angle } => (ratio, angle.to_radians()),
+                    _ => (1.0, 0.0),
+                };
+                let (a, b) = (radius, radius * ratio);
+                let (sin, cos) = angle.sin_cos();
+                let steps = ((TAU * a.max(b) / 4.0) as usize).max(16);
+                (0..steps)
+                    .map(|i| {
+                        let t = i as f32 / steps as f32 * TAU;
+                        let (u, v) = (a * t.cos(), b * t.sin());
+                        Point::new(center.x + u * cos - v * sin, center.y + u * sin + v * cos)
+                    })
+                    .collect()
+            }
+            Outline::Rounded { ratio, corner } => {
+                let (width, height) = (radius, radius * ratio);
+                let round = corner * width.min(height);
+                let steps = ((FRAC_PI_2 * round / 4.0) as usize).max(1);
+                // A quarter circle at each corner, starting at the bottom
+                // right one.
+                [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)]
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(quarter, &(x, y))| {
+                        let middle = Point::new(
+                            center.x + x * (width - round),
+                            center.y + y * (height - round),
+                        );
+                        (0..=steps).map(move |i| {
+                            let t = (quarter as f32 + i as f32 / steps as f32) * FRAC_PI_2;
+                            Point::new(middle.x + round * t.cos(), middle.y + round * t.sin())
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Whether the point is inside the circle with the center and radius. A
+/// point on the edge is outside.
+pub fn in_circle(point: &Point, center: &Point, radius: f32) -> bool {
+    nalgebra::distance(point, center) < radius
+}
+
+/// Whether the point is inside the box with the center and size, like an
+/// answer box. A point on the edge is outside.
+pub fn in_box(point: &Point, center: &Point, width: f32, height: f32) -> bool {
+    (point.x - center.x).abs() < width / 2.0 && (point.y - center.y).abs() < height / 2.0
+}
+
+/// Whether the point is inside the ellipse with the center and the radii
+/// across and up and down, turned clockwise by the angle in degrees. A
+/// point on the edge is outside.
+pub fn in_ellipse(point: &Point, center: &Point, radii: (f32, f32), angle: f32) -> bool {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let (dx, dy) = (point.x - center.x, point.y - center.y);
+    // The point as seen from the ellipse turned back straight.
+    let (u, v) = (dx * cos + dy * sin, dy * cos - dx * sin);
+    (u / radii.0).powi(2) + (v / radii.1).powi(2) < 1.0
+}
+
+/// Whether the point is inside the box with the center and size, with its
+/// corners rounded off by circles of the radius. A point on the edge is
+/// outside.
+pub fn in_rounded(point: &Point, center: &Point, width: f32, height: f32, corner: f32) -> bool {
+    if !in_box(point, center, width, height) {
+        return false;
+    }
+    // How far past where the corner starts to round off the point is, on
+    // each side.
+    let x = (point.x - center.x).abs() - (width / 2.0 - corner);
+    let y = (point.y - center.y).abs() - (height / 2.0 - corner);
+    x <= 0.0 || y <= 0.0 || x * x + y * y < corner * corner
+}
+
+/// The region a point is in, given the left, right and, in a three set
+/// diagram, bottom set.
+pub fn classify(point: &Point, sets: &[Boundary]) -> Region {
+    let inside = |i: usize| sets.get(i).is_some_and(|set| set.contains(point));
+    Region::from_membership(inside(0), inside(1), inside(2))
+}
+
+/// What a guess has to satisfy to be right in a region.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Semantics {
+    /// The rule of every circle the region is in has to hold. The rules of
+    /// the other circles are ignored, so a guess fitting both circles is
+    /// also right in either one alone.
+    Inclusive,
+    /// The rules of the circles the region is in have to hold and the rules
+    /// of the others must not. Every guess has exactly one right region, so
+    /// one fitting both circles belongs in the intersection.
+    Exclusive,
+}
+
+impl Semantics {
+    /// Whether a guess with the given circle results is right in the region.
+    /// Both arrays are indexed left, right, bottom.
+    pub fn evaluate(self, region: Region, matches: [Option<bool>; 3]) -> bool {
+        region
+            .membership()
+            .iter()
+            .zip(matches.iter())
+            .all(|(&inside, &matches)| match (self, matches) {
+                // There is no such circle.
+                (_, None) => true,
+                (Semantics::Inclusive, Some(matches)) => !inside || matches,
+                (Semantics::Exclusive, Some(matches)) => inside == matches,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn point() -> impl Strategy<Value = Point> {
+        (-1000.0f32..1000.0, -1000.0f32..1000.0).prop_map(|(x, y)| Point::new(x, y))
+    }
+
+    fn circle() -> impl Strategy<Value = (Point, f32)> {
+        (point(), 1.0f32..500.0)
+    }
+
+    fn outline() -> impl Strategy<Value = Outline> {
+        prop_oneof![
+            Just(Outline::Circle),
+            (0.2f32..3.0, -180.0f32..180.0)
+                .prop_map(|(ratio, angle)| Outline::Ellipse { ratio, angle }),
+            (0.2f32..3.0, 0.0f32..=1.0)
+                .prop_map(|(ratio, corner)| Outline::Rounded { ratio, corner }),
+        ]
+    }
+
+    fn boundary() -> impl Strategy<Value = Boundary> {
+        (circle(), outline()).prop_map(|((center, radius), outline)| Boundary {
+            center,
+            radius,
+            outline,
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn membership_round_trips(left: bool, right: bool, bottom: bool) {
+            let region = Region::from_membership(left, right, bottom);
+            prop_assert_eq!(region.membership(), [left, right, bottom]);
+        }
+
+        #[test]
+        fn classify_agrees_with_each_set(
+            point in point(),
+            sets in proptest::collection::vec(boundary(), 2..=3),
+        ) {
+            let membership = classify(&point, &sets).membership();
+            for (i, set) in sets.iter().enumerate() {
+                prop_assert_eq!(membership[i], set.contains(&point));
+            }
+            // Without a bottom set nothing is in it.
+            if sets.len() == 2 {
+                prop_assert!(!membership[2]);
+            }
+        }
+
+        // Just inside the points along the edge is in the set, and just
+        // outside isn't, within how far it reaches.
+        #[test]
+        fn edges_go_round_the_set(set in boundary()) {
+            let (across, down) = set.extent();
+            for at in set.edge() {
+                let out = at - set.center;
+                prop_assert!(set.contains(&(set.center + out * 0.99)));
+                prop_assert!(!set.contains(&(set.center + out * 1.01)));
+                prop_assert!(out.x.abs() <= across * 1.001 && out.y.abs() <= down * 1.001);
+            }
+        }
+
+        #[test]
+        fn grown_sets_hold_the_set(
+            set in boundary(),
+            (x, y) in (-1.0f32..1.0, -1.0f32..1.0),
+            by in 0.0f32..50.0,
+        ) {
+            let (across, down) = set.extent();
+            let point = Point::new(set.center.x + x * across, set.center.y + y * down);
+            prop_assume!(set.contains(&point));
+            prop_assert!(set.grown(by).contains(&point));
+        }
+
+        #[test]
+        fn round_ellipses_are_circles(
+            (center, radius) in circle(),
+            angle in -180.0f32..180.0,
+            point in point(),
+        ) {
+            // Right on the edge rounding could go either way.
+            prop_assume!((nalgebra::distance(&point, &center) - radius).abs() > 0.01 * radius);
+            prop_assert_eq!(
+                in_ellipse(&point, &center, (radius, radius), angle),
+                in_circle(&point, &center, radius)
+            );
+        }
+
+        #[test]
+        fn square_cornered_boxes_are_boxes(
+            center in point(),
+            width in 1.0f32..500.0,
+            height in 1.0f32..500.0,
+            point in point(),
+        ) {
+            prop_assert_eq!(
+                in_rounded(&point, &center, width, height, 0.0),
+                in_box(&point, &center, width, height)
+            );
+        }
+
+        // Points on the line between overlapping circles, where both cover
+        // it, are in the lens.
+        #[test]
+        fn lens_is_in_both_circles(
+            left in circle(),
+            right_radius in 1.0f32..500.0,
+            overlap in 0.05f32..0.95,
+            along in 0.05f32..0.95,
+            angle in 0.0f32..std::f32::consts::TAU,
+        ) {
+            let (center, radius) = left;
+            let gap = radius + right_radius;
+            // Far enough apart that neither circle holds the other.
+            let distance = (gap - overlap * right_radius.min(radius))
+                .max((radius - right_radius).abs() + 0.5);
+            prop_assume!(distance < gap - 0.5);
+            let (sin, cos) = angle.sin_cos();
+            let right = Point::new(center.x + distance * cos, center.y + distance * sin);
+            // The lens spans from the right circle's edge to the left one's.
+            let near = distance - right_radius;
+            let at = near + along * (radius - near);
+            let point = Point::new(center.x + at * cos, center.y + at * sin);
+            prop_assert_eq!(
+                classify(
+                    &point,
+                    &[Boundary::circle(center, radius), Boundary::circle(right, right_radius)]
+                ),
+                Region::Both
+            );
+            prop_assert!(in_circle(&point, &center, radius));
+            prop_assert!(in_circle(&point, &right, right_radius));
+        }
+
+        #[test]
+        fn box_holds_its_center_but_not_what_is_past_its_corners(
+            center in point(),
+            width in 1.0f32..500.0,
+            height in 1.0f32..500.0,
+        ) {
+            prop_assert!(in_box(&center, &center, width, height));
+            let past = Point::new(center.x + width / 2.0 + 1.0, center.y + height / 2.0 + 1.0);
+            prop_assert!(!in_box(&past, &center, width, height));
+        }
+    }
+
+    #[test]
+    fn outlines_need_a_finite_ratio_above_zero() {
+        for ratio in [0.0, -1.0, f32::INFINITY, f32::NAN] {
+            assert!(Outline::Ellipse { ratio, angle: 0.0 }.check().is_err());
+            let corner = 0.5;
+            assert!(Outline::Rounded { ratio, corner }.check().is_err());
+        }
+        assert!(Outline::Ellipse {
+            ratio: 0.5,
+            angle: f32::NAN
+        }
+        .check()
+        .is_err());
+        assert!(Outline::Rounded {
+            ratio: 0.5,
+            corner: 0.5
+        }
+        .check()
+        .is_ok());
+    }
+}
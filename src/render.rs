@@ -0,0 +1,1089 @@
+//! Drawing for the model types. Everything that knows about meshes lives
+//! here so the model can be used without a window. It draws on a
+//! [`Canvas`], so the same code draws the window, exported SVG files and
+//! screenshots.
+//!
+//! [`Canvas`]: trait.Canvas.html
+
+use crate::{
+    circle::{VennAnswer, VennSet},
+    colors::PLAYERS,
+    cursor::Cursor,
+    graphics::{Color, Point, Rectangle, Shape},
+    guess::VennGuess,
+    model::{VennModel, HEIGHT, WIDTH},
+    palette::Style,
+    region::{Boundary, Outline, Region},
+    seat::HotSeat,
+    shapes::{
+        VennColor, VennCount, VennFill, VennOrientation, VennPattern, VennShape, VennSize,
+        VennTarget,
+    },
+    solver::Hint,
+    trail::{Trails, FADE},
+    tutorial::{Step, Tutorial},
+};
+#[cfg(feature = "coffee")]
+use coffee::graphics::Mesh;
+use std::f32::consts::PI;
+
+/// Something shapes can be filled and stroked on, like a mesh drawn in the
+/// window, an SVG document or a screenshot.
+pub trait Canvas {
+    fn fill(&mut self, shape: Shape, color: Color);
+    fn stroke(&mut self, shape: Shape, color: Color, width: u16);
+}
+
+#[cfg(feature = "coffee")]
+impl Canvas for Mesh {
+    fn fill(&mut self, shape: Shape, color: Color) {
+        Mesh::fill(self, shape, color);
+    }
+
+    fn stroke(&mut self, shape: Shape, color: Color, width: u16) {
+        Mesh::stroke(self, shape, color, width);
+    }
+}
+
+// Draws on another canvas the factor times as big around a point, lines
+// as much thicker.
+struct Scaled<'a, C> {
+    canvas: &'a mut C,
+    origin: Point,
+    factor: f32,
+}
+
+impl<C: Canvas> Scaled<'_, C> {
+    fn point(&self, point: Point) -> Point {
+        self.origin + (point - self.origin) * self.factor
+    }
+
+    fn shape(&self, shape: Shape) -> Shape {
+        match shape {
+            Shape::Rectangle(rectangle) => {
+                let corner = self.point(Point::new(rectangle.x, rectangle.y));
+                Shape::Rectangle(Rectangle {
+                    x: corner.x,
+                    y: corner.y,
+                    width: rectangle.width * self.factor,
+                    height: rectangle.height * self.factor,
+                })
+            }
+            Shape::Circle { center, radius } => Shape::Circle {
+                center: self.point(center),
+                radius: radius * self.factor,
+            },
+            Shape::Ellipse {
+                center,
+                horizontal_radius,
+                vertical_radius,
+                rotation,
+            } => Shape::Ellipse {
+                center: self.point(center),
+                horizontal_radius: horizontal_radius * self.factor,
+                vertical_radius: vertical_radius * self.factor,
+                rotation,
+            },
+            Shape::Polyline { points } => Shape::Polyline {
+                points: points.into_iter().map(|p| self.point(p)).collect(),
+            },
+        }
+    }
+}
+
+impl<C: Canvas> Canvas for Scaled<'_, C> {
+    fn fill(&mut self, shape: Shape, color: Color) {
+        let shape = self.shape(shape);
+        self.canvas.fill(shape, color);
+    }
+
+    fn stroke(&mut self, shape: Shape, color: Color, width: u16) {
+        let shape = self.shape(shape);
+        let width = (width as f32 * self.factor).round() as u16;
+        self.canvas.stroke(shape, color, width);
+    }
+}
+
+impl VennSize {
+    // Half the width of the shape drawn on a guess.
+    fn radius(self) -> f32 {
+        match self {
+            VennSize::Small => 7.0,
+            VennSize::Medium => 11.0,
+            VennSize::Large => 15.0,
+        }
+    }
+}
+
+impl VennAnswer {
+    // revealed shows the hidden card in the box instead of the one built.
+    // The box is tinted green or red once the built card is checked.
+    pub fn draw(&self, mesh: &mut impl Canvas, style: &Style, hover: bool, revealed: bool) {
+        let tint = match (self.matches, hover) {
+            (Some(true), _) => Some(style.right()),
+            (Some(false), _) => Some(style.wrong()),
+            (None, true) => Some(style.theme.highlight),
+            (None, false) => None,
+        };
+        if let Some(mut color) = tint {
+            color.a = if hover { 0.3 } else { 0.2 };
+            mesh.fill(
+                Shape::Rectangle(Rectangle {
+                    x: self.center.x - self.width / 2.0,
+                    y: self.center.y - self.height / 2.0,
+                    width: self.width,
+                    height: self.height,
+                }),
+                color,
+            );
+        }
+        mesh.stroke(
+            Shape::Rectangle(Rectangle {
+                x: self.center.x - self.width / 2.0,
+                y: self.center.y - self.height / 2.0,
+                width: self.width,
+                height: self.height,
+            }),
+            style.theme.stroke,
+            style.width(2),
+        );
+        if self.written {
+            return;
+        }
+        if revealed {
+            self.target.draw(mesh, style, self.center);
+        } else if let Some(built) = &self.built {
+            built.draw(mesh, style, self.center);
+        }
+    }
+
+    // A line from corner to corner across the box, like a no entry sign.
+    fn draw_slash(&self, mesh: &mut impl Canvas, style: &Style) {
+        let (dx, dy) = (self.width / 2.0, self.height / 2.0);
+        mesh.stroke(
+            Shape::Polyline {
+                points: vec![
+                    Point::new(self.center.x - dx, self.center.y + dy),
+                    Point::new(self.center.x + dx, self.center.y - dy),
+                ],
+            },
+            style.theme.alert,
+            style.width(3),
+        );
+    }
+}
+
+impl VennSet {
+    // selected highlights the circle, hover the answer box above it. Without
+    // fill only the edge is drawn, for when the regions are filled apart.
+    pub fn draw(
+        &self,
+        mesh: &mut impl Canvas,
+        style: &Style,
+        selected: bool,
+        hover: bool,
+        revealed: bool,
+        fill: bool,
+    ) {
+        // A compound rule has no card to show, the text of the box says it.
+        // One of everything except a value is struck through as well.
+        let negated = match (&self.compound, &self.answer.built_rule) {
+            (Some(rule), _) if revealed => rule.is_negated(),
+            (Some(_), Some(built)) => built.is_negated(),
+            _ => false,
+        };
+        let revealed = revealed && self.compound.is_none();
+        self.answer.draw(mesh, style, hover, revealed);
+        if negated {
+            self.answer.draw_slash(mesh, style);
+        }
+        let edge = self.boundary().shape();
+        if fill {
+            mesh.fill(
+                edge.clone(),
+                shade(style.palette.color(self.color), selected),
+            );
+        }
+        mesh.stroke(edge, style.theme.stroke, style.width(1));
+    }
+}
+
+impl Boundary {
+    /// The edge of the set, to fill or stroke.
+    pub fn shape(&self) -> Shape {
+        match self.outline {
+            Outline::Circle => Shape::Circle {
+                center: self.center,
+                radius: self.radius,
+            },
+            Outline::Ellipse { ratio, angle } => Shape::Ellipse {
+                center: self.center,
+                horizontal_radius: self.radius,
+                vertical_radius: self.radius * ratio,
+                rotation: angle.to_radians(),
+            },
+            Outline::Rounded { .. } => closed(self.edge()),
+        }
+    }
+}
+
+// The fill of a region, stronger when the point is in it.
+fn shade(mut color: Color, selected: bool) -> Color {
+    color.a = if selected { 0.3 } else { 0.1 };
+    color
+}
+
+// Points along the edge of the circle from one point on it to another, going
+// the way angles increase, which is clockwise on screen, or the other way.
+// Both ends are included.
+fn arc(circle: &VennSet, from: Point, to: Point, increasing: bool) -> Vec<Point> {
+    let angle = |p: Point| (p.y - circle.center.y).atan2(p.x - circle.center.x);
+    let start = angle(from);
+    let mut sweep = angle(to) - start;
+    if increasing && sweep < 0.0 {
+        sweep += 2.0 * PI;
+    } else if !increasing && sweep > 0.0 {
+        sweep -= 2.0 * PI;
+    }
+    // A point every four pixels or so keeps the edge smooth.
+    let steps = ((sweep.abs() * circle.radius / 4.0) as usize).max(8);
+    (0..=steps)
+        .map(|i| {
+            let a = start + sweep * i as f32 / steps as f32;
+            Point::new(
+                circle.center.x + circle.radius * a.cos(),
+                circle.center.y + circle.radius * a.sin(),
+            )
+        })
+        .collect()
+}
+
+// A closed path for a filled polygon.
+fn closed(mut points: Vec<Point>) -> Shape {
+    points.push(points[0]);
+    Shape::Polyline { points }
+}
+
+impl VennModel {
+    /// Draws the circles and their answer boxes. With two overlapping
+    /// circles the part only in the left one, the part only in the right one
+    /// and the lens where they overlap are filled as regions of their own,
+    /// and only the region the point is in is highlighted, as is a box a
+    /// card can be built in.
+    pub fn draw_diagram(
+        &self,
+        mesh: &mut impl Canvas,
+        style: &Style,
+        point: Point,
+        revealed: bool,
+    ) {
+        let circles: Vec<&VennSet> = self.circles().collect();
+        let crossings = match circles[..] {
+            [left, right] => left.crossings(right).map(|c| (left, right, c)),
+            _ => None,
+        };
+        let (left, right, (first, second)) = match crossings {
+            Some(crossings) => crossings,
+            None => {
+                for (i, circle) in circles.into_iter().enumerate() {
+                    let selected = circle.contains(&point);
+                    let hover = self.answer_at(&point) == Some(i);
+                    circle.draw(mesh, style, selected, hover, revealed, true);
+                }
+                return;
+            }
+        };
+        // The first crossing is the upper one. Going clockwise, the lens is
+        // the edge of the left circle down to the lower crossing and the
+        // edge of the right one back up.
+        let mut lens = arc(left, first, second, true);
+        lens.extend(arc(right, second, first, true));
+        let mut only_left = arc(left, second, first, true);
+        only_left.extend(arc(right, first, second, false));
+        let mut only_right = arc(right, first, second, true);
+        only_right.extend(arc(left, second, first, false));
+        let region = self.region(&point);
+        let (a, b) = (
+            style.palette.color(left.color),
+            style.palette.color(right.color),
+        );
+        let mixed = Color {
+            r: (a.r + b.r) / 2.0,
+            g: (a.g + b.g) / 2.0,
+            b: (a.b + b.b) / 2.0,
+            a: 1.0,
+        };
+        mesh.fill(closed(only_left), shade(a, region == Region::LeftOnly));
+        mesh.fill(closed(only_right), shade(b, region == Region::RightOnly));
+        mesh.fill(closed(lens), shade(mixed, region == Region::Both));
+        for (i, &circle) in [left, right].iter().enumerate() {
+            let hover = self.answer_at(&point) == Some(i);
+            circle.draw(mesh, style, false, hover, revealed, false);
+        }
+    }
+}
+
+impl VennGuess {
+    pub fn draw(&self, mesh: &mut impl Canvas, style: &Style) {
+        let mut color = match self.matches {
+            None => style.theme.neutral,
+            Some(true) => style.right(),
+            Some(false) => style.wrong(),
+        };
+        color.a = 1.0;
+        if self.dragged {
+            color.a -= 0.3;
+        }
+        mesh.fill(
+            Shape::Circle {
+                center: self.center,
+                radius: self.radius,
+            },
+            color,
+        );
+        mesh.stroke(
+            Shape::Circle {
+                center: self.center,
+                radius: self.radius,
+            },
+            style.theme.stroke,
+            style.width(1),
+        );
+        // The ui writes the name of a token on it.
+        if self.name.is_none() {
+            self.target.draw(mesh, style, self.center);
+        }
+    }
+
+    // Rings the guess the keyboard acts on.
+    pub fn draw_focus(&self, mesh: &mut impl Canvas, style: &Style) {
+        mesh.stroke(
+            Shape::Circle {
+                center: self.center,
+                radius: self.radius + 3.0,
+            },
+            style.theme.stroke,
+            style.width(2),
+        );
+    }
+}
+
+impl VennShape {
+    // The corners of the shape around a center at the origin, or None for a
+    // circle.
+    fn outline(self, r: f32) -> Option<Vec<(f32, f32)>> {
+        Some(match self {
+            VennShape::Circle => return None,
+            VennShape::Square => vec![(-r, -r), (r, -r), (r, r), (-r, r)],
+            VennShape::Triangle => vec![(0.0, -r), (r, r), (-r, r)],
+            // Narrower than it is tall, so it doesn't look like a turned
+            // square.
+            VennShape::Diamond => vec![(0.0, -r), (r * 0.7, 0.0), (0.0, r), (-r * 0.7, 0.0)],
+            VennShape::Pentagon => polygon(5, r, r),
+            VennShape::Hexagon => polygon(6, r, r),
+            VennShape::Star => polygon(10, r, r * 0.45),
+        })
+    }
+}
+
+// Turns the corners of an outline around the origin by the angle,
+// clockwise on screen.
+fn turn(outline: Vec<(f32, f32)>, angle: f32) -> Vec<(f32, f32)> {
+    let (sin, cos) = angle.sin_cos();
+    outline
+        .into_iter()
+        .map(|(x, y)| (x * cos - y * sin, x * sin + y * cos))
+        .collect()
+}
+
+// The corners of a regular polygon with the first one at the top. Every
+// other corner is at the inner radius, which makes a star if it is smaller.
+fn polygon(corners: usize, outer: f32, inner: f32) -> Vec<(f32, f32)> {
+    (0..corners)
+        .map(|i| {
+            let angle =
+                -std::f32::consts::FRAC_PI_2 + i as f32 * std::f32::consts::TAU / corners as f32;
+            let radius = if i % 2 == 0 { outer } else { inner };
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect()
+}
+
+// Where the edges of an outline cross the horizontal line at y, paired up
+// into the stretches that are inside it.
+fn crossings(outline: &[(f32, f32)], y: f32) -> Vec<(f32, f32)> {
+    let mut xs: Vec<f32> = outline
+        .iter()
+        .zip(outline.iter().cycle().skip(1))
+        .filter(|((_, ay), (_, by))| (*ay <= y) != (*by <= y))
+        .map(|((ax, ay), (bx, by))| ax + (y - ay) * (bx - ax) / (by - ay))
+        .collect();
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    xs.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+impl VennTarget {
+    // Draws the shapes of the card centered on the point, side by side or
+    // in a triangle if there is more than one, as big as the style says.
+    pub fn draw(&self, mesh: &mut impl Canvas, style: &Style, center: Point) {
+        if style.scale != 1.0 {
+            let mut scaled = Scaled {
+                canvas: mesh,
+                origin: center,
+                factor: style.scale,
+            };
+            self.draw_pips(&mut scaled, style, center);
+        } else {
+            self.draw_pips(mesh, style, center);
+        }
+    }
+
+    fn draw_pips(&self, mesh: &mut impl Canvas, style: &Style, center: Point) {
+        let d = self.size.radius() * 0.6;
+        let offsets: &[(f32, f32)] = match self.count {
+            VennCount(1) => &[(0.0, 0.0)],
+            VennCount(2) => &[(-d, 0.0), (d, 0.0)],
+            _ => &[(0.0, -d), (-d, d * 0.8), (d, d * 0.8)],
+        };
+        for &(dx, dy) in offsets {
+            self.draw_pip(mesh, style, Point::new(center.x + dx, center.y + dy));
+        }
+    }
+
+    // The edge of one copy of the shape around the center, r across.
+    fn edge(&self, center: Point, r: f32) -> Shape {
+        match self.outline(r) {
+            None => Shape::Circle { center, radius: r },
+            Some(outline) => {
+                let mut points: Vec<Point> = outline
+                    .iter()
+                    .map(|&(x, y)| Point::new(center.x + x, center.y + y))
+                    .collect();
+                points.push(points[0]);
+                Shape::Polyline { points }
+            }
+        }
+    }
+
+    // The corners of one copy of the shape, turned if the card is.
+    fn outline(&self, r: f32) -> Option<Vec<(f32, f32)>> {
+        let outline = self.shape.outline(r)?;
+        Some(match self.orientation {
+            VennOrientation::Upright => outline,
+            VennOrientation::Rotated => turn(outline, PI / 4.0),
+        })
+    }
+
+    // How big each copy of the shape is drawn, smaller when there are more
+    // of them to fit.
+    fn pip_radius(&self) -> f32 {
+        match self.count {
+            VennCount(1) => self.size.radius(),
+            _ => self.size.radius() * 0.55,
+        }
+    }
+
+    // Draws one copy of the shape centered on the point.
+    fn draw_pip(&self, mesh: &mut impl Canvas, style: &Style, center: Point) {
+        let r = self.pip_radius();
+        let shape = self.edge(center, r);
+        let color = style.palette.color(self.color);
+        if self.fill == VennFill::Outlined {
+            // Only strokes, so it can't be taken for a hollow pattern,
+            // which is a single thick line around the black edge.
+            mesh.stroke(shape, color, 2);
+            mesh.stroke(self.edge(center, r * 0.55), color, 2);
+            if style.patterns {
+                self.draw_pattern(mesh, center);
+            }
+            return;
+        }
+        if self.pattern == VennPattern::Solid {
+            mesh.fill(shape.clone(), color);
+        } else {
+            self.draw_fill(mesh, center, color);
+            mesh.stroke(shape.clone(), color, 3);
+        }
+        if style.patterns {
+            self.draw_pattern(mesh, center);
+        }
+        mesh.stroke(shape, style.theme.stroke, 1);
+    }
+
+    // Fills in the shape with strokes of its color: upright stripes, dots
+    // or nothing at all for a hollow shape.
+    fn draw_fill(&self, mesh: &mut impl Canvas, center: Point, color: Color) {
+        let r = self.pip_radius();
+        let spacing = 3.0;
+        let steps = (r / spacing) as i32;
+        let offsets = (-steps..=steps).map(|i| i as f32 * spacing);
+        match self.pattern {
+            VennPattern::Solid | VennPattern::Hollow => {}
+            VennPattern::Striped => {
+                for dx in offsets.step_by(2) {
+                    for (top, bottom) in self.column(dx) {
+                        mesh.stroke(
+                            Shape::Polyline {
+                                points: vec![
+                                    Point::new(center.x + dx, center.y + top),
+                                    Point::new(center.x + dx, center.y + bottom),
+                                ],
+                            },
+                            color,
+                            2,
+                        );
+                    }
+                }
+            }
+            VennPattern::Dotted => {
+                for dy in offsets.clone().step_by(2) {
+                    for dx in offsets.clone().step_by(2) {
+                        if self
+                            .span(dy)
+                            .iter()
+                            .any(|&(left, right)| left < dx && dx < right)
+                        {
+                            mesh.fill(
+                                Shape::Circle {
+                                    center: Point::new(center.x + dx, center.y + dy),
+                                    radius: 2.0,
+                                },
+                                color,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Stripes for yellow, dots for blue and cross-hatching for purple,
+    // clipped to the shape.
+    fn draw_pattern<C: Canvas>(&self, mesh: &mut C, center: Point) {
+        let r = self.pip_radius();
+        let spacing = 4.0;
+        let steps = (r / spacing) as i32;
+        let offsets = (-steps..=steps).map(|i| i as f32 * spacing);
+        let line = |mesh: &mut C, from: Point, to: Point| {
+            mesh.stroke(
+                Shape::Polyline {
+                    points: vec![from, to],
+                },
+                Color::BLACK,
+                1,
+            );
+        };
+        match self.color {
+            VennColor::Yellow => {
+                for dy in offsets {
+                    for (left, right) in self.span(dy) {
+                        line(
+                            mesh,
+                            Point::new(center.x + left, center.y + dy),
+                            Point::new(center.x + right, center.y + dy),
+                        );
+                    }
+                }
+            }
+            VennColor::Blue => {
+                for dy in offsets.clone() {
+                    for dx in offsets.clone() {
+                        if self
+                            .span(dy)
+                            .iter()
+                            .any(|&(left, right)| left < dx && dx < right)
+                        {
+                            mesh.fill(
+                                Shape::Circle {
+                                    center: Point::new(center.x + dx, center.y + dy),
+                                    radius: 1.0,
+                                },
+                                Color::BLACK,
+                            );
+                        }
+                    }
+                }
+            }
+            VennColor::Purple => {
+                for d in offsets {
+                    for (left, right) in self.span(d) {
+                        line(
+                            mesh,
+                            Point::new(center.x + left, center.y + d),
+                            Point::new(center.x + right, center.y + d),
+                        );
+                    }
+                    for (top, bottom) in self.column(d) {
+                        line(
+                            mesh,
+                            Point::new(center.x + d, center.y + top),
+                            Point::new(center.x + d, center.y + bottom),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // Where a horizontal line dy below the center is inside the shape,
+    // relative to the center. A star can take more than one stretch.
+    fn span(&self, dy: f32) -> Vec<(f32, f32)> {
+        let r = self.pip_radius();
+        match self.outline(r) {
+            None if dy.abs() < r => {
+                let half = (r * r - dy * dy).sqrt();
+                vec![(-half, half)]
+            }
+            None => vec![],
+            Some(outline) => crossings(&outline, dy),
+        }
+    }
+
+    // Like span, for a vertical line dx right of the center.
+    fn column(&self, dx: f32) -> Vec<(f32, f32)> {
+        let r = self.pip_radius();
+        match self.outline(r) {
+            None if dx.abs() < r => {
+                let half = (r * r - dx * dx).sqrt();
+                vec![(-half, half)]
+            }
+            None => vec![],
+            Some(outline) => {
+                let turned: Vec<(f32, f32)> = outline.iter().map(|&(x, y)| (y, x)).collect();
+                crossings(&turned, dx)
+            }
+        }
+    }
+}
+
+impl VennModel {
+    // Tints the parts of the diagram where the guess would turn green, in
+    // small squares since regions are bounded by arcs.
+    pub fn draw_right_regions(&self, mesh: &mut impl Canvas, style: &Style, index: usize) {
+        let regions = self.right_regions(index);
+        if regions.is_empty() {
+            return;
+        }
+        let mut color = style.right();
+        color.a = 0.15;
+        let cell = 6.0;
+        for x in 0..(WIDTH / cell) as usize {
+            for y in 0..(HEIGHT / cell) as usize {
+                let (x, y) = (x as f32 * cell, y as f32 * cell);
+                let middle = Point::new(x + cell / 2.0, y + cell / 2.0);
+                if regions.contains(&self.region(&middle))
+                    && !self.circles().any(|c| c.answer.contains(&middle))
+                {
+                    mesh.fill(
+                        Shape::Rectangle(Rectangle {
+                            x,
+                            y,
+                            width: cell,
+                            height: cell,
+                        }),
+                        color,
+                    );
+                }
+            }
+        }
+    }
+}
+
+// A heart around the center, as wide as twice the size, filled while it is
+// a life left.
+fn heart(mesh: &mut impl Canvas, style: &Style, center: Point, size: f32, full: bool) {
+    let points = (0..32)
+        .map(|i| {
+            let t = i as f32 / 32.0 * 2.0 * PI;
+            let x = t.sin().powi(3);
+            let y =
+                (13.0 * t.cos() - 5.0 * (2.0 * t).cos() - 2.0 * (3.0 * t).cos() - (4.0 * t).cos())
+                    / 16.0;
+            Point::new(center.x + x * size, center.y - y * size)
+        })
+        .collect();
+    let shape = closed(points);
+    if full {
+        mesh.fill(
+            shape.clone(),
+            Color {
+                a: 0.1,
+                ..style.theme.alert
+            },
+        );
+    }
+    mesh.stroke(shape, style.theme.stroke, style.width(1));
+}
+
+impl VennModel {
+    // A heart for each life of the round in a row ending at the point, the
+    // ones lost at the end and only outlined.
+    pub fn draw_lives(&self, mesh: &mut impl Canvas, style: &Style, end: Point) {
+        let (lives, left) = match (self.options().lives, self.lives_left()) {
+            (Some(lives), Some(left)) => (lives, left),
+            _ => return,
+        };
+        for i in 0..lives {
+            let size = 8.0 * style.scale;
+            let center = Point::new(end.x - size - (lives - 1 - i) as f32 * size * 2.5, end.y);
+            heart(mesh, style, center, size, i < left);
+        }
+    }
+}
+
+impl Hint {
+    // Rings the suggested guess and the spot to drop it on.
+    pub fn draw(&self, mesh: &mut impl Canvas, style: &Style, model: &VennModel) {
+        let guess = &model.guesses()[self.guess];
+        mesh.stroke(
+            Shape::Circle {
+                center: guess.center,
+                radius: guess.radius + 4.0,
+            },
+            style.theme.hint,
+            style.width(3),
+        );
+        if let Some(center) = model.region_center(self.region) {
+            mesh.stroke(
+                Shape::Circle {
+                    center,
+                    radius: guess.radius,
+                },
+                style.theme.hint,
+                style.width(3),
+            );
+            mesh.stroke(
+                Shape::Polyline {
+                    points: vec![guess.center, center],
+                },
+                style.theme.hint,
+                style.width(1),
+            );
+        }
+    }
+}
+
+impl HotSeat {
+    // Rings the point the player whose turn it is works at in their color.
+    pub fn draw(&self, mesh: &mut impl Canvas, point: Point) {
+        mesh.stroke(
+            Shape::Circle {
+                center: point,
+                radius: 18.0,
+            },
+            PLAYERS[self.turn()],
+            3,
+        );
+    }
+}
+
+impl Cursor {
+    // A small hand below and to the right of the pointer, open over a guess
+    // and closed around one being dragged, filled green where it can be
+    // dropped.
+    pub fn draw(self, mesh: &mut impl Canvas, style: &Style, point: Point) {
+        let palm = Point::new(point.x + 18.0, point.y + 22.0);
+        let fill = if self == Cursor::Dropping {
+            style.theme.hint
+        } else {
+            style.theme.panel
+        };
+        // Open fingers are longer and further apart.
+        let (length, spread) = match self {
+            Cursor::Open => (10.0, 0.45),
+            _ => (5.0, 0.3),
+        };
+        for i in 0..4 {
+            let angle = -PI / 2.0 + (i as f32 - 1.5) * spread;
+            let reach = 6.0 + length / 2.0;
+            let finger = Shape::Ellipse {
+                center: Point::new(palm.x + reach * angle.cos(), palm.y + reach * angle.sin()),
+                horizontal_radius: length / 2.0,
+                vertical_radius: 2.0,
+                rotation: angle,
+            };
+            mesh.fill(finger.clone(), fill);
+            mesh.stroke(finger, style.theme.stroke, 1);
+        }
+        let palm = Shape::Circle {
+            center: palm,
+            radius: 7.0,
+        };
+        mesh.fill(palm.clone(), fill);
+        mesh.stroke(palm, style.theme.stroke, 1);
+    }
+}
+
+impl Trails {
+    // Each stretch of a trail is as faint as its older end. The trails are
+    // faded further by the seconds since they were last updated and run on
+    // to where the guesses are now, so they keep up with a guess dragged
+    // between ticks.
+    pub fn draw(&self, mesh: &mut impl Canvas, style: &Style, guesses: &[VennGuess], since: f32) {
+        for (path, guess) in self.paths.iter().zip(guesses) {
+            let mut path = path.clone();
+            if let Some(&(point, life)) = path.last() {
+                if point != guess.center {
+                    path.push((guess.center, life));
+                }
+            }
+            for pair in path.windows(2) {
+                let ((from, life), (to, _)) = (pair[0], pair[1]);
+                let life = life - since / FADE;
+                if life <= 0.0 {
+                    continue;
+                }
+                mesh.stroke(
+                    Shape::Polyline {
+                        points: vec![from, to],
+                    },
+                    Color {
+                        a: life * 0.6,
+                        ..style.theme.trail
+                    },
+                    style.width(2),
+                );
+            }
+        }
+    }
+}
+
+impl Tutorial {
+    // Rings what the current step is about.
+    pub fn draw<C: Canvas>(&self, mesh: &mut C, style: &Style, model: &VennModel) {
+        let highlight = style.theme.attention;
+        let ring = |mesh: &mut C, center: Point, radius: f32| {
+            mesh.stroke(Shape::Circle { center, radius }, highlight, style.width(4));
+        };
+        match self.step() {
+            Step::PickUp => {
+                let guess = &model.guesses()[0];
+                ring(mesh, guess.center, guess.radius + 6.0);
+            }
+            Step::Drop { .. } => {
+                for circle in model.circles() {
+                    let edge = circle.boundary().grown(4.0).shape();
+                    mesh.stroke(edge, highlight, style.width(4));
+                }
+            }
+            Step::Feedback { guess } => {
+                let guess = &model.guesses()[guess];
+                ring(mesh, guess.center, guess.radius + 6.0);
+            }
+            Step::Answer => {
+                for circle in model.circles() {
+                    let answer = &circle.answer;
+                    mesh.stroke(
+                        Shape::Rectangle(Rectangle {
+                            x: answer.center.x - answer.width / 2.0 - 4.0,
+                            y: answer.center.y - answer.height / 2.0 - 4.0,
+                            width: answer.width + 8.0,
+                            height: answer.height + 8.0,
+                        }),
+                        highlight,
+                        style.width(4),
+                    );
+                }
+            }
+            Step::Done => {}
+        }
+    }
+}
+
+// Draws known boards into pixels and compares them with the pictures in
+// tests/golden. Run with VENN_BLESS=1 to write the pictures again after
+// changing how something looks on purpose.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::Options, palette::Palette, puzzle, screenshot::Raster, theme::Theme};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{
+        env,
+        fs::File,
+        path::{Path, PathBuf},
+    };
+
+    // How far apart a channel of two pixels can be and still count as the
+    // same, and the share of pixels that may differ.
+    const CHANNEL: u8 = 16;
+    const PIXELS: f32 = 0.005;
+
+    fn model(level: usize, seed: u64, three: bool) -> VennModel {
+        let options = Options {
+            three,
+            seed,
+            ..Options::default()
+        };
+        VennModel::new(level, &options, &mut StdRng::seed_from_u64(seed))
+    }
+
+    // The board of one of the example puzzles.
+    fn example(name: &str) -> VennModel {
+        let path = format!("puzzles/{}.json", name);
+        let puzzle = puzzle::read(Path::new(&path)).unwrap();
+        VennModel::from_puzzle(&puzzle, model(0, 1, false).options())
+    }
+
+    // Puts each of the first guesses where it belongs, or in the left
+    // circle where it turns red if it belongs nowhere, spread out around
+    // the middle of the region so they don't hide each other.
+    fn play(model: &mut VennModel, placements: usize) {
+        for guess in 0..placements {
+            let region = model
+                .right_regions(guess)
+                .first()
+                .copied()
+                .unwrap_or(Region::LeftOnly);
+            let center = model.region_center(region).unwrap();
+            let angle = guess as f32 * 2.4;
+            let spread = Point::new(center.x + 35.0 * angle.cos(), center.y + 35.0 * angle.sin());
+            let point = if model.region(&spread) == region {
+                spread
+            } else {
+                center
+            };
+            model.place(guess, &point);
+        }
+    }
+
+    fn draw(model: &VennModel, style: &Style, revealed: bool) -> Raster {
+        let mut raster = Raster::new(1.0);
+        let board = Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: WIDTH,
+            height: HEIGHT,
+        };
+        raster.fill(Shape::Rectangle(board), style.theme.background);
+        model.draw_diagram(&mut raster, style, Point::new(-WIDTH, -HEIGHT), revealed);
+        for i in model.stacking() {
+            model.guesses()[i].draw(&mut raster, style);
+        }
+        raster
+    }
+
+    fn read(path: &PathBuf) -> (usize, usize, Vec<u8>) {
+        let decoder = png::Decoder::new(File::open(path).unwrap());
+        let (info, mut reader) = decoder.read_info().unwrap();
+        let mut pixels = vec![0; info.buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        (info.width as usize, info.height as usize, pixels)
+    }
+
+    fn check(name: &str, raster: Raster) {
+        let golden = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(format!("{}.png", name));
+        if env::var_os("VENN_BLESS").is_some() {
+            raster.write(&golden).unwrap();
+            return;
+        }
+        let (width, height, expected) = read(&golden);
+        assert_eq!((width, height), raster.size(), "{} changed size", name);
+        // Kept next to the build for a look when it differs.
+        let actual = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("target/golden")
+            .join(format!("{}.png", name));
+        raster.write(&actual).unwrap();
+        let pixels = raster.into_pixels();
+        let differing = pixels
+            .chunks(4)
+            .zip(expected.chunks(4))
+            .filter(|(a, b)| a.iter().zip(*b).any(|(a, b)| a.abs_diff(*b) > CHANNEL))
+            .count();
+        let share = differing as f32 / (width * height) as f32;
+        assert!(
+            share <= PIXELS,
+            "{}: {:.2}% of the pixels differ from {}, see {}",
+            name,
+            share * 100.0,
+            golden.display(),
+            actual.display()
+        );
+    }
+
+    #[test]
+    fn new_puzzle() {
+        let style = Style::default();
+        check("new_puzzle", draw(&model(0, 1, false), &style, false));
+    }
+
+    #[test]
+    fn three_circles_in_play() {
+        let mut model = model(4, 2, true);
+        play(&mut model, 6);
+        let style = Style::default();
+        check("three_circles_in_play", draw(&model, &style, false));
+    }
+
+    #[test]
+    fn revealed_with_patterns() {
+        let mut model = model(7, 3, true);
+        play(&mut model, 8);
+        let style = Style {
+            palette: Palette::Colorblind,
+            patterns: true,
+            ..Style::default()
+        };
+        check("revealed_with_patterns", draw(&model, &style, true));
+    }
+
+    #[test]
+    fn rotated_cards() {
+        let mut model = model(8, 3, false);
+        play(&mut model, 6);
+        let style = Style::default();
+        check("rotated_cards", draw(&model, &style, false));
+    }
+
+    #[test]
+    fn outlined_cards() {
+        let mut model = model(9, 5, false);
+        play(&mut model, 6);
+        let style = Style::default();
+        check("outlined_cards", draw(&model, &style, false));
+    }
+
+    #[test]
+    fn ellipses() {
+        let mut model = example("ellipses");
+        play(&mut model, 6);
+        let style = Style::default();
+        check("ellipses", draw(&model, &style, false));
+    }
+
+    // One rounded rectangle inside another, with the part only in the
+    // inner one left out.
+    #[test]
+    fn nested_sets() {
+        let mut model = example("euler");
+        play(&mut model, 4);
+        let style = Style::default();
+        check("nested_sets", draw(&model, &style, false));
+    }
+
+    #[test]
+    fn high_contrast() {
+        let mut model = model(3, 5, false);
+        play(&mut model, 5);
+        let style = Style {
+            theme: Theme::HIGH_CONTRAST,
+            ..Style::default()
+        };
+        check("high_contrast", draw(&model, &style, false));
+    }
+
+    #[test]
+    fn hint_and_focus() {
+        let mut model = model(2, 4, false);
+        play(&mut model, 2);
+        let style = Style::default();
+        let mut raster = draw(&model, &style, false);
+        if let Some(hint) = model.hint() {
+            hint.draw(&mut raster, &style, &model);
+        }
+        model.guesses()[0].draw_focus(&mut raster, &style);
+        check("hint_and_focus", raster);
+    }
+}
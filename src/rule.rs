@@ -0,0 +1,118 @@
+use crate::{
+    circle::compare,
+    model::Level,
+    shapes::{Value, VennTarget},
+};
+use serde::{Deserialize, Serialize};
+
+/// How a circle combines the attributes of its hidden card into a rule.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Rule {
+    /// Every attribute in play has to match.
+    All,
+    /// At least one attribute has to match.
+    Any,
+    /// None of the attributes may match.
+    Not,
+    /// Exactly this many attributes have to match.
+    Exactly(usize),
+}
+
+impl Rule {
+    /// Applies the rule to whether each attribute in play matched.
+    pub fn evaluate(self, attributes: &[bool]) -> bool {
+        let count = attributes.iter().filter(|&&matches| matches).count();
+        match self {
+            Rule::All => count == attributes.len(),
+            Rule::Any => count > 0,
+            Rule::Not => count == 0,
+            Rule::Exactly(n) => count == n,
+        }
+    }
+}
+
+/// A compound rule made of values, like "blue and triangle", "blue or
+/// small" or "everything except blue", which doesn't go through a hidden
+/// card.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum RuleExpr {
+    /// The guess has the value.
+    Is(Value),
+    And(Box<RuleExpr>, Box<RuleExpr>),
+    Or(Box<RuleExpr>, Box<RuleExpr>),
+    /// The guess doesn't fit the rule.
+    Not(Box<RuleExpr>),
+}
+
+impl RuleExpr {
+    pub fn evaluate(&self, target: &VennTarget) -> bool {
+        match self {
+            RuleExpr::Is(value) => value.holds(target),
+            RuleExpr::And(a, b) => a.evaluate(target) && b.evaluate(target),
+            RuleExpr::Or(a, b) => a.evaluate(target) || b.evaluate(target),
+            RuleExpr::Not(a) => !a.evaluate(target),
+        }
+    }
+
+    /// Whether the value is one of those the rule is made of.
+    pub fn mentions(&self, value: Value) -> bool {
+        match self {
+            RuleExpr::Is(v) => *v == value,
+            RuleExpr::And(a, b) | RuleExpr::Or(a, b) => a.mentions(value) || b.mentions(value),
+            RuleExpr::Not(a) => a.mentions(value),
+        }
+    }
+
+    /// Whether the rule picks out everything except what it names.
+    pub fn is_negated(&self) -> bool {
+        matches!(self, RuleExpr::Not(_))
+    }
+
+    /// The values joined by and, or by or. None without any values.
+    pub fn join(values: &[Value], or: bool) -> Option<RuleExpr> {
+        let mut values = values.iter().map(|&value| RuleExpr::Is(value));
+        let first = values.next()?;
+        Some(values.fold(first, |joined, next| {
+            if or {
+                RuleExpr::Or(Box::new(joined), Box::new(next))
+            } else {
+                RuleExpr::And(Box::new(joined), Box::new(next))
+            }
+        }))
+    }
+
+    /// Whether both pick out the same guesses.
+    pub fn same_as(&self, other: &RuleExpr, guesses: &[VennTarget]) -> bool {
+        guesses
+            .iter()
+            .all(|guess| self.evaluate(guess) == other.evaluate(guess))
+    }
+}
+
+/// What decides which guesses fit a circle: a rule applied to its hidden
+/// card, or a compound rule.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Secret {
+    Card(Rule, VennTarget),
+    Compound(RuleExpr),
+}
+
+impl Secret {
+    /// Whether the guess fits, comparing only the attributes the level
+    /// cares about to a hidden card.
+    pub fn matches(&self, target: &VennTarget, level: &Level) -> bool {
+        match self {
+            Secret::Card(rule, card) => rule.evaluate(&compare(card, target, level)),
+            Secret::Compound(expr) => expr.evaluate(target),
+        }
+    }
+
+    /// Whether the hidden card has the value, or the compound rule is
+    /// made of it.
+    pub fn has(&self, value: Value) -> bool {
+        match self {
+            Secret::Card(_, card) => value.holds(card),
+            Secret::Compound(expr) => expr.mentions(value),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Saving a game to disk and resuming it later.
+
+use crate::model::VennModel;
+use std::{fs, io, path::Path};
+
+/// Reads a game written by [`write`].
+///
+/// [`write`]: fn.write.html
+pub fn read(path: &Path) -> io::Result<VennModel> {
+    let contents = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Writes the whole state of a game as JSON, making the directory for it
+/// if needed.
+pub fn write(path: &Path, model: &VennModel) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(model)?)
+}
+
+// Points come from coffee or nalgebra, neither of which makes them
+// serializable here, so they are stored as (x, y) pairs.
+pub(crate) mod point {
+    use crate::graphics::Point;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(point: &Point, serializer: S) -> Result<S::Ok, S::Error> {
+        (point.x, point.y).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Point, D::Error> {
+        let (x, y) = <(f32, f32)>::deserialize(deserializer)?;
+        Ok(Point::new(x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::Options, region::Region};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn a_saved_game_resumes_where_it_was() {
+        let options = Options {
+            three: true,
+            seed: 7,
+            ..Options::default()
+        };
+        let mut model = VennModel::new(4, &options, &mut StdRng::seed_from_u64(7));
+        let both = model.region_center(Region::Both).unwrap();
+        model.place(0, &both);
+        let path = std::env::temp_dir().join(format!("venn-save-{}.json", std::process::id()));
+        write(&path, &model).unwrap();
+        let resumed = read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            serde_json::to_value(&resumed).unwrap(),
+            serde_json::to_value(&model).unwrap()
+        );
+        assert_eq!(resumed.level(), 4);
+        assert_eq!(resumed.score().points, model.score().points);
+        assert_eq!(resumed.region(&resumed.guesses()[0].center), Region::Both);
+    }
+
+    #[test]
+    fn a_file_that_is_no_game_does_not_read() {
+        let path =
+            std::env::temp_dir().join(format!("venn-not-a-save-{}.json", std::process::id()));
+        fs::write(&path, "{ \"level\": 2 }").unwrap();
+        assert!(read(&path).is_err());
+        fs::remove_file(&path).unwrap();
+        assert!(read(&path).is_err());
+    }
+}
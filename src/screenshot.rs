@@ -0,0 +1,277 @@
+//! Screenshots of the board as PNG files. The engine can't read the window
+//! back, so the board is drawn again into pixels of its own, without the
+//! text.
+
+use crate::{
+    graphics::{Color, Shape},
+    model::{HEIGHT, WIDTH},
+    profile,
+    render::Canvas,
+};
+use png::HasParameters;
+use std::{
+    f32::consts::PI,
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// How many corners circles and ellipses get when stroked.
+const SEGMENTS: usize = 64;
+
+/// The board drawn into pixels, at the size of the window.
+pub struct Raster {
+    width: usize,
+    height: usize,
+    scale: f32,
+    // RGBA, row by row from the top.
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    /// A white picture of the window scaled by the factor.
+    pub fn new(scale: f32) -> Raster {
+        let (width, height) = ((WIDTH * scale) as usize, (HEIGHT * scale) as usize);
+        Raster {
+            width,
+            height,
+            scale,
+            pixels: vec![255; width * height * 4],
+        }
+    }
+
+    // Blends the color over every pixel within the bounds whose middle is
+    // inside, with the bounds and the test in board coordinates.
+    fn paint(&mut self, bounds: [f32; 4], color: Color, inside: impl Fn(f32, f32) -> bool) {
+        let [left, top, right, bottom] = bounds;
+        let column = |x: f32| ((x * self.scale).max(0.0) as usize).min(self.width);
+        let row = |y: f32| ((y * self.scale).max(0.0) as usize).min(self.height);
+        let (x0, x1) = (column(left), column(right.ceil() + 1.0));
+        let (y0, y1) = (row(top), row(bottom.ceil() + 1.0));
+        let [r, g, b, _] = color.to_rgba();
+        let a = color.a.clamp(0.0, 1.0);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let (px, py) = ((x as f32 + 0.5) / self.scale, (y as f32 + 0.5) / self.scale);
+                if !inside(px, py) {
+                    continue;
+                }
+                let i = (y * self.width + x) * 4;
+                for (channel, value) in self.pixels[i..i + 3].iter_mut().zip(&[r, g, b]) {
+                    *channel = (*channel as f32 * (1.0 - a) + *value as f32 * a).round() as u8;
+                }
+            }
+        }
+    }
+
+    /// The width and height in pixels.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// The pixels as RGBA, row by row from the top.
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Writes the picture to the file as PNG, making the directory for it
+    /// if needed.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width as u32, self.height as u32);
+        encoder.set(png::ColorType::RGBA).set(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+}
+
+impl Canvas for Raster {
+    fn fill(&mut self, shape: Shape, color: Color) {
+        match shape {
+            Shape::Rectangle(r) => {
+                let bounds = [r.x, r.y, r.x + r.width, r.y + r.height];
+                self.paint(bounds, color, |x, y| {
+                    x >= r.x && x <= r.x + r.width && y >= r.y && y <= r.y + r.height
+                });
+            }
+            Shape::Circle { center, radius } => {
+                let (cx, cy) = (center.x, center.y);
+                let bounds = [cx - radius, cy - radius, cx + radius, cy + radius];
+                self.paint(bounds, color, |x, y| {
+                    (x - cx).powi(2) + (y - cy).powi(2) <= radius * radius
+                });
+            }
+            Shape::Ellipse {
+                center,
+                horizontal_radius: rx,
+                vertical_radius: ry,
+                rotation,
+            } => {
+                let (cx, cy) = (center.x, center.y);
+                let reach = rx.max(ry);
+                let bounds = [cx - reach, cy - reach, cx + reach, cy + reach];
+                let (sin, cos) = rotation.sin_cos();
+                self.paint(bounds, color, |x, y| {
+                    let (dx, dy) = (x - cx, y - cy);
+                    let (u, v) = (dx * cos + dy * sin, dy * cos - dx * sin);
+                    (u / rx).powi(2) + (v / ry).powi(2) <= 1.0
+                });
+            }
+            Shape::Polyline { points } => {
+                let points: Vec<(f32, f32)> = points.iter().map(|p| (p.x, p.y)).collect();
+                self.paint(bounds(&points, 0.0), color, |x, y| contains(&points, x, y));
+            }
+        }
+    }
+
+    fn stroke(&mut self, shape: Shape, color: Color, width: u16) {
+        let points = outline(shape);
+        // Thin lines still cover the pixels they pass through.
+        let half = (width as f32 / 2.0).max(0.5 / self.scale);
+        self.paint(bounds(&points, half), color, |x, y| {
+            points
+                .windows(2)
+                .any(|pair| distance(pair[0], pair[1], x, y) <= half)
+        });
+    }
+}
+
+// The points a stroke goes through, closed for the shapes with an inside.
+fn outline(shape: Shape) -> Vec<(f32, f32)> {
+    let around = |point: &dyn Fn(f32) -> (f32, f32)| {
+        (0..=SEGMENTS)
+            .map(|i| point(i as f32 / SEGMENTS as f32 * 2.0 * PI))
+            .collect()
+    };
+    match shape {
+        Shape::Rectangle(r) => vec![
+            (r.x, r.y),
+            (r.x + r.width, r.y),
+            (r.x + r.width, r.y + r.height),
+            (r.x, r.y + r.height),
+            (r.x, r.y),
+        ],
+        Shape::Circle { center, radius } => around(&|angle| {
+            (
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        }),
+        Shape::Ellipse {
+            center,
+            horizontal_radius: rx,
+            vertical_radius: ry,
+            rotation,
+        } => {
+            let (sin, cos) = rotation.sin_cos();
+            around(&|angle| {
+                let (u, v) = (rx * angle.cos(), ry * angle.sin());
+                (center.x + u * cos - v * sin, center.y + u * sin + v * cos)
+            })
+        }
+        Shape::Polyline { points } => points.iter().map(|p| (p.x, p.y)).collect(),
+    }
+}
+
+// The box around the points, grown by the margin on every side.
+fn bounds(points: &[(f32, f32)], margin: f32) -> [f32; 4] {
+    points.iter().fold(
+        [
+            f32::INFINITY,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::NEG_INFINITY,
+        ],
+        |[left, top, right, bottom], &(x, y)| {
+            [
+                left.min(x - margin),
+                top.min(y - margin),
+                right.max(x + margin),
+                bottom.max(y + margin),
+            ]
+        },
+    )
+}
+
+// Whether the point is inside the polygon, counting how many of its sides a
+// ray to the right crosses like the window does.
+fn contains(points: &[(f32, f32)], x: f32, y: f32) -> bool {
+    let mut inside = false;
+    let mut previous = match points.last() {
+        Some(&last) => last,
+        None => return false,
+    };
+    for &(x1, y1) in points {
+        let (x0, y0) = previous;
+        if (y0 > y) != (y1 > y) && x < x0 + (y - y0) / (y1 - y0) * (x1 - x0) {
+            inside = !inside;
+        }
+        previous = (x1, y1);
+    }
+    inside
+}
+
+// How far the point is from the line segment.
+fn distance((x0, y0): (f32, f32), (x1, y1): (f32, f32), x: f32, y: f32) -> f32 {
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (((x - x0) * dx + (y - y0) * dy) / length).clamp(0.0, 1.0)
+    };
+    ((x - x0 - t * dx).powi(2) + (y - y0 - t * dy).powi(2)).sqrt()
+}
+
+/// Where a screenshot taken now goes: named after the time in the
+/// screenshots directory.
+pub fn path() -> PathBuf {
+    dir("screenshots").join(format!("venn-{}.png", timestamp()))
+}
+
+/// The directory of that name next to the other files, or in the working
+/// directory if there is no data directory.
+pub fn dir(name: &str) -> PathBuf {
+    profile::root()
+        .map(|root| root.join(name))
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+/// The time in UTC, like 2019-11-30-142501.
+pub fn timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil(seconds / (24 * 60 * 60));
+    let time = seconds % (24 * 60 * 60);
+    format!(
+        "{}-{:02}-{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+// The date so many days after 1970-01-01, by Howard Hinnant's algorithm.
+fn civil(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Months counted from March, so the leap day comes last.
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
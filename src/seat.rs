@@ -0,0 +1,70 @@
+//! Two players taking turns at the same board, each with their own score.
+
+use crate::model::{Level, Score};
+
+/// How one of the players is doing.
+#[derive(Default, Copy, Clone)]
+pub struct Player {
+    /// Carried from one round to the next.
+    pub score: Score,
+    /// Guesses the player put where they belong this round.
+    pub right: u32,
+}
+
+/// Whose turn it is and how both players are doing. A turn is one guess
+/// dropped somewhere it turns green or red.
+pub struct HotSeat {
+    pub players: [Player; 2],
+    turn: usize,
+    // Who went first this round.
+    opener: usize,
+}
+
+impl HotSeat {
+    pub fn new() -> HotSeat {
+        HotSeat {
+            players: [Player::default(); 2],
+            turn: 0,
+            opener: 0,
+        }
+    }
+
+    /// The index of the player whose turn it is.
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    /// Scores a placement for the player whose turn it is and hands the
+    /// turn over. A guess that was right before counts only for whoever
+    /// got it right first.
+    pub fn place(&mut self, matches: bool, first: bool, level: &Level) {
+        let player = &mut self.players[self.turn];
+        if !matches {
+            player.score.record(false, &level.score);
+        } else if first {
+            player.score.record(true, &level.score);
+            player.right += 1;
+        }
+        self.turn = 1 - self.turn;
+    }
+
+    /// Counts the right guesses from nothing again, keeping the scores.
+    /// Whoever went second last round goes first.
+    pub fn new_round(&mut self) {
+        for player in &mut self.players {
+            player.right = 0;
+        }
+        self.opener = 1 - self.opener;
+        self.turn = self.opener;
+    }
+
+    /// The player with more right guesses this round, or None on a tie.
+    pub fn winner(&self) -> Option<usize> {
+        let [one, two] = self.players;
+        match one.right.cmp(&two.right) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+
+/// One of the ways guesses and hidden cards differ, like color.
+pub trait Attribute: Copy + 'static {
+    /// Every value the attribute can take, in a fixed order.
+    fn variants() -> &'static [Self];
+}
+
+/// The attributes of a guess or of the hidden card above a circle.
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct VennTarget {
+    pub color: VennColor,
+    pub shape: VennShape,
+    pub size: VennSize,
+    /// Cards from before patterns came in are solid.
+    #[serde(default)]
+    pub pattern: VennPattern,
+    /// Cards from before counts came in show a single shape.
+    #[serde(default)]
+    pub count: VennCount,
+    /// Cards from before orientation came in stand upright.
+    #[serde(default)]
+    pub orientation: VennOrientation,
+    /// Cards from before fills came in are filled.
+    #[serde(default)]
+    pub fill: VennFill,
+}
+
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum VennColor {
+    #[default]
+    Yellow,
+    Blue,
+    Purple,
+}
+
+impl Attribute for VennColor {
+    fn variants() -> &'static [VennColor] {
+        &[VennColor::Yellow, VennColor::Blue, VennColor::Purple]
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum VennSize {
+    #[default]
+    Small,
+    Medium,
+    Large,
+}
+
+impl Attribute for VennSize {
+    fn variants() -> &'static [VennSize] {
+        &[VennSize::Small, VennSize::Medium, VennSize::Large]
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum VennShape {
+    #[default]
+    Circle,
+    Triangle,
+    Square,
+    Diamond,
+    Pentagon,
+    Hexagon,
+    Star,
+}
+
+impl VennShape {
+    /// Whether the shape can be turned on a card. Squares and triangles
+    /// look different turned, the other shapes too much alike.
+    pub fn turns(self) -> bool {
+        matches!(self, VennShape::Square | VennShape::Triangle)
+    }
+}
+
+impl Attribute for VennShape {
+    fn variants() -> &'static [VennShape] {
+        &[
+            VennShape::Circle,
+            VennShape::Square,
+            VennShape::Triangle,
+            VennShape::Diamond,
+            VennShape::Pentagon,
+            VennShape::Hexagon,
+            VennShape::Star,
+        ]
+    }
+}
+
+/// How the shape on a card is filled in.
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum VennPattern {
+    #[default]
+    Solid,
+    Striped,
+    Hollow,
+    Dotted,
+}
+
+impl Attribute for VennPattern {
+    fn variants() -> &'static [VennPattern] {
+        &[
+            VennPattern::Solid,
+            VennPattern::Striped,
+            VennPattern::Hollow,
+            VennPattern::Dotted,
+        ]
+    }
+}
+
+/// How many copies of the shape a card shows, like the pips on a die.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VennCount(pub u8);
+
+impl Default for VennCount {
+    fn default() -> VennCount {
+        VennCount(1)
+    }
+}
+
+impl Attribute for VennCount {
+    fn variants() -> &'static [VennCount] {
+        &[VennCount(1), VennCount(2), VennCount(3)]
+    }
+}
+
+/// Which way up the shape on a card stands.
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum VennOrientation {
+    #[default]
+    Upright,
+    /// Turned an eighth of the way round, clockwise.
+    Rotated,
+}
+
+impl Attribute for VennOrientation {
+    fn variants() -> &'static [VennOrientation] {
+        &[VennOrientation::Upright, VennOrientation::Rotated]
+    }
+}
+
+/// Whether the shape on a card is filled in or only drawn around, which
+/// leaves its pattern out.
+#[derive(PartialEq, Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub enum VennFill {
+    #[default]
+    Filled,
+    /// Drawn as a ring of two outlines, one inside the other.
+    Outlined,
+}
+
+impl Attribute for VennFill {
+    fn variants() -> &'static [VennFill] {
+        &[VennFill::Filled, VennFill::Outlined]
+    }
+}
+
+/// One of the ways cards differ, without a value, like color.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Kind {
+    Shape,
+    Color,
+    Size,
+    Pattern,
+    Count,
+    Orientation,
+    Fill,
+}
+
+impl Kind {
+    /// The value the card has of this attribute.
+    pub fn of(self, target: &VennTarget) -> Value {
+        match self {
+            Kind::Shape => Value::Shape(target.shape),
+            Kind::Color => Value::Color(target.color),
+            Kind::Size => Value::Size(target.size),
+            Kind::Pattern => Value::Pattern(target.pattern),
+            Kind::Count => Value::Count(target.count),
+            Kind::Orientation => Value::Orientation(target.orientation),
+            Kind::Fill => Value::Fill(target.fill),
+        }
+    }
+
+    /// Every value of the attribute, in the order of its variants.
+    pub fn values(self) -> Vec<Value> {
+        fn all<A: Attribute>(value: fn(A) -> Value) -> Vec<Value> {
+            A::variants().iter().map(|&a| value(a)).collect()
+        }
+        match self {
+            Kind::Shape => all(Value::Shape),
+            Kind::Color => all(Value::Color),
+            Kind::Size => all(Value::Size),
+            Kind::Pattern => all(Value::Pattern),
+            Kind::Count => all(Value::Count),
+            Kind::Orientation => all(Value::Orientation),
+            Kind::Fill => all(Value::Fill),
+        }
+    }
+}
+
+/// One attribute of a card with its value, like blue or triangle.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Value {
+    Shape(VennShape),
+    Color(VennColor),
+    Size(VennSize),
+    Pattern(VennPattern),
+    Count(VennCount),
+    Orientation(VennOrientation),
+    Fill(VennFill),
+}
+
+impl Value {
+    /// Whether the card has this value.
+    pub fn holds(self, target: &VennTarget) -> bool {
+        match self {
+            Value::Shape(shape) => target.shape == shape,
+            Value::Color(color) => target.color == color,
+            Value::Size(size) => target.size == size,
+            Value::Pattern(pattern) => target.pattern == pattern,
+            Value::Count(count) => target.count == count,
+            Value::Orientation(orientation) => target.orientation == orientation,
+            Value::Fill(fill) => target.fill == fill,
+        }
+    }
+
+    /// The attribute this is a value of.
+    pub fn kind(self) -> Kind {
+        match self {
+            Value::Shape(_) => Kind::Shape,
+            Value::Color(_) => Kind::Color,
+            Value::Size(_) => Kind::Size,
+            Value::Pattern(_) => Kind::Pattern,
+            Value::Count(_) => Kind::Count,
+            Value::Orientation(_) => Kind::Orientation,
+            Value::Fill(_) => Kind::Fill,
+        }
+    }
+
+    /// Gives the card this value.
+    pub fn set(self, target: &mut VennTarget) {
+        match self {
+            Value::Shape(shape) => target.shape = shape,
+            Value::Color(color) => target.color = color,
+            Value::Size(size) => target.size = size,
+            Value::Pattern(pattern) => target.pattern = pattern,
+            Value::Count(count) => target.count = count,
+            Value::Orientation(orientation) => target.orientation = orientation,
+            Value::Fill(fill) => target.fill = fill,
+        }
+    }
+}
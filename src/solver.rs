@@ -0,0 +1,429 @@
+//! Works out what the placements so far say about the hidden cards and
+//! which placement would tell the player the most.
+
+use crate::{
+    circle::compare,
+    generator,
+    model::Level,
+    region::{Region, Semantics},
+    rule::{Rule, RuleExpr, Secret},
+    shapes::{
+        Attribute, Value, VennColor, VennCount, VennFill, VennOrientation, VennPattern, VennShape,
+        VennSize, VennTarget,
+    },
+};
+
+/// A suggested placement.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Hint {
+    /// Index of the guess to move.
+    pub guess: usize,
+    pub region: Region,
+}
+
+/// What the player saw when a guess turned green or red.
+pub enum Observation {
+    /// The guess was dropped in a region of the diagram.
+    Region {
+        region: Region,
+        target: VennTarget,
+        matches: bool,
+    },
+    /// The card was built in a circle's answer box.
+    Answer {
+        circle: usize,
+        target: VennTarget,
+        matches: bool,
+    },
+    /// The compound rule was built in a circle's answer box.
+    Rule {
+        circle: usize,
+        rule: RuleExpr,
+        matches: bool,
+    },
+}
+
+// A single fact about one circle that an observation depends on.
+struct Condition {
+    circle: usize,
+    test: Test,
+    expected: bool,
+}
+
+// What a condition checks about a circle.
+enum Test {
+    // Whether the guess fits the circle.
+    Fits(VennTarget),
+    // Whether the circle's hidden card is this one.
+    Card(VennTarget),
+    // Whether the circle's compound rule picks out the same cards as this.
+    Rule(RuleExpr),
+}
+
+impl Condition {
+    fn holds(&self, secret: &Secret, level: &Level, cards: &[VennTarget]) -> bool {
+        let matches = match (&self.test, secret) {
+            (Test::Fits(target), secret) => secret.matches(target, level),
+            (Test::Card(target), Secret::Card(_, card)) => {
+                Rule::All.evaluate(&compare(card, target, level))
+            }
+            (Test::Rule(rule), Secret::Compound(expr)) => rule.same_as(expr, cards),
+            _ => false,
+        };
+        matches == self.expected
+    }
+}
+
+/// The rules and cards each circle could still have.
+pub struct Solver<'a> {
+    level: &'a Level,
+    // Every card there could be, told apart by the attributes the level
+    // cares about.
+    cards: Vec<VennTarget>,
+    candidates: Vec<Vec<Secret>>,
+}
+
+impl<'a> Solver<'a> {
+    /// Narrows down every circle's possible rule and card, in one of the
+    /// shapes in play, by the observations. Circles with a compound rule,
+    /// which shows in their answer box, only have compound candidates.
+    pub fn new(
+        level: &'a Level,
+        shapes: &[VennShape],
+        compound: &[bool],
+        observations: &[Observation],
+    ) -> Solver<'a> {
+        // Cards that only differ in attributes the level ignores are the
+        // same card as far as the player can tell.
+        let mut cards: Vec<VennTarget> = Vec::new();
+        for &shape in shapes {
+            for &color in VennColor::variants() {
+                for &size in VennSize::variants() {
+                    for &pattern in VennPattern::variants() {
+                        for &count in VennCount::variants() {
+                            for &orientation in VennOrientation::variants() {
+                                if orientation != VennOrientation::Upright && !shape.turns() {
+                                    continue;
+                                }
+                                for &fill in VennFill::variants() {
+                                    let card = VennTarget {
+                                        color,
+                                        shape,
+                                        size,
+                                        pattern,
+                                        count,
+                                        orientation,
+                                        fill,
+                                    };
+                                    if !cards
+                                        .iter()
+                                        .any(|c| Rule::All.evaluate(&compare(c, &card, level)))
+                                    {
+                                        cards.push(card);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let with_cards: Vec<Secret> = level
+            .rules
+            .iter()
+            .flat_map(|&rule| cards.iter().map(move |&card| Secret::Card(rule, card)))
+            .collect();
+        let compounds: Vec<Secret> = generator::compounds(level, shapes)
+            .into_iter()
+            .map(Secret::Compound)
+            .collect();
+        let candidates = compound
+            .iter()
+            .map(|&compound| {
+                if compound {
+                    compounds.clone()
+                } else {
+                    with_cards.clone()
+                }
+            })
+            .collect();
+        let mut solver = Solver {
+            level,
+            cards,
+            candidates,
+        };
+        let constraints: Vec<(Vec<Condition>, bool)> =
+            observations.iter().map(|o| solver.conditions(o)).collect();
+        // Every pass can only rule candidates out, so this settles.
+        loop {
+            let before: usize = solver.candidates.iter().map(Vec::len).sum();
+            for (conditions, holds) in &constraints {
+                solver.apply(conditions, *holds);
+            }
+            if solver.candidates.iter().map(Vec::len).sum::<usize>() == before {
+                return solver;
+            }
+        }
+    }
+
+    /// The rules and cards a circle could still have.
+    pub fn candidates(&self, circle: usize) -> &[Secret] {
+        &self.candidates[circle]
+    }
+
+    /// Whether the guess fits the circle's rule as far as can be told: Some
+    /// once every rule and card the circle could still have agrees.
+    pub fn fits(&self, circle: usize, target: &VennTarget) -> Option<bool> {
+        let mut answers = self.candidates[circle]
+            .iter()
+            .map(|secret| secret.matches(target, self.level));
+        let first = answers.next()?;
+        answers.all(|answer| answer == first).then_some(first)
+    }
+
+    /// Whether the circle's hidden card has the value, or its compound rule
+    /// is made of it: Some once every rule and card the circle could still
+    /// have agrees.
+    pub fn has(&self, circle: usize, value: Value) -> Option<bool> {
+        let mut answers = self.candidates[circle]
+            .iter()
+            .map(|secret| secret.has(value));
+        let first = answers.next()?;
+        answers.all(|answer| answer == first).then_some(first)
+    }
+
+    // The facts about each circle that have to hold for the observation to
+    // have turned out green.
+    fn conditions(&self, observation: &Observation) -> (Vec<Condition>, bool) {
+        match observation {
+            Observation::Region {
+                region,
+                target,
+                matches,
+            } => {
+                let semantics = match region {
+                    Region::Outside => Semantics::Exclusive,
+                    _ => self.level.regions,
+                };
+                let conditions = (0..self.candidates.len())
+                    .filter_map(|circle| {
+                        let inside = region.membership()[circle];
+                        match (semantics, inside) {
+                            (Semantics::Inclusive, false) => None,
+                            _ => Some(Condition {
+                                circle,
+                                test: Test::Fits(*target),
+                                expected: inside,
+                            }),
+                        }
+                    })
+                    .collect();
+                (conditions, *matches)
+            }
+            Observation::Answer {
+                circle,
+                target,
+                matches,
+            } => (
+                vec![Condition {
+                    circle: *circle,
+                    test: Test::Card(*target),
+                    expected: true,
+                }],
+                *matches,
+            ),
+            Observation::Rule {
+                circle,
+                rule,
+                matches,
+            } => (
+                vec![Condition {
+                    circle: *circle,
+                    test: Test::Rule(rule.clone()),
+                    expected: true,
+                }],
+                *matches,
+            ),
+        }
+    }
+
+    // Rules out the candidates the outcome of an observation contradicts.
+    fn apply(&mut self, conditions: &[Condition], holds: bool) {
+        let (level, cards) = (self.level, &self.cards);
+        if holds {
+            for condition in conditions {
+                self.candidates[condition.circle].retain(|c| condition.holds(c, level, cards));
+            }
+            return;
+        }
+        // At least one condition failed. That only says something once all
+        // but one of them are known to hold.
+        let mut open = conditions.iter().filter(|condition| {
+            !self.candidates[condition.circle]
+                .iter()
+                .all(|c| condition.holds(c, level, cards))
+        });
+        if let (Some(condition), None) = (open.next(), open.next()) {
+            self.candidates[condition.circle].retain(|c| !condition.holds(c, level, cards));
+        }
+    }
+
+    // The share of a circle's candidates whose rule picks the target.
+    fn probability(&self, circle: usize, target: &VennTarget) -> f32 {
+        let candidates = &self.candidates[circle];
+        if candidates.is_empty() {
+            return 0.0;
+        }
+        let matching = candidates
+            .iter()
+            .filter(|secret| secret.matches(target, self.level))
+            .count();
+        matching as f32 / candidates.len() as f32
+    }
+
+    /// The chance of the guess turning green in the region, treating the
+    /// circles as independent.
+    pub fn chance(&self, target: &VennTarget, region: Region) -> f32 {
+        let semantics = match region {
+            Region::Outside => Semantics::Exclusive,
+            _ => self.level.regions,
+        };
+        self.odds(target, region, semantics)
+    }
+
+    /// The chance of the region being where the guess belongs, inside
+    /// every circle it fits and outside the others, whatever else the
+    /// level turns it green in.
+    pub fn belonging(&self, target: &VennTarget, region: Region) -> f32 {
+        self.odds(target, region, Semantics::Exclusive)
+    }
+
+    fn odds(&self, target: &VennTarget, region: Region, semantics: Semantics) -> f32 {
+        (0..self.candidates.len())
+            .map(|circle| {
+                let p = self.probability(circle, target);
+                match (semantics, region.membership()[circle]) {
+                    (_, true) => p,
+                    (Semantics::Exclusive, false) => 1.0 - p,
+                    (Semantics::Inclusive, false) => 1.0,
+                }
+            })
+            .product()
+    }
+
+    /// The placement whose outcome is least predictable, and so tells the
+    /// most about the hidden cards. Once there is nothing left to learn, a
+    /// placement that is sure to be where the guess belongs. Guesses are
+    /// given with their index in the model and the regions they may go to.
+    pub fn hint(&self, guesses: &[(usize, VennTarget, Vec<Region>)]) -> Option<Hint> {
+        let mut best: Option<(f32, Hint)> = None;
+        let mut certain = None;
+        for (guess, target, regions) in guesses {
+            let (guess, target) = (*guess, *target);
+            for &region in regions {
+                let p = self.chance(&target, region);
+                if self.belonging(&target, region) >= 1.0 && certain.is_none() {
+                    certain = Some(Hint { guess, region });
+                }
+                let information = entropy(p);
+                if information > 0.0 && best.is_none_or(|(best, _)| information > best) {
+                    best = Some((information, Hint { guess, region }));
+                }
+            }
+        }
+        best.map(|(_, hint)| hint).or(certain)
+    }
+}
+
+// How many bits the outcome of a placement that is right with probability
+// p is worth.
+fn entropy(p: f32) -> f32 {
+    if p <= 0.0 || p >= 1.0 {
+        return 0.0;
+    }
+    -(p * p.log2() + (1.0 - p) * (1.0 - p).log2())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LEVELS;
+
+    fn card(shape: VennShape, color: VennColor) -> VennTarget {
+        VennTarget {
+            shape,
+            color,
+            size: VennSize::Medium,
+            ..VennTarget::default()
+        }
+    }
+
+    // The first level, where any shared shape or color makes a guess fit.
+    fn solver(observations: &[Observation]) -> Solver<'static> {
+        let level = &LEVELS[0];
+        Solver::new(level, level.shapes, &[false, false], observations)
+    }
+
+    #[test]
+    fn a_right_answer_gives_the_card_away() {
+        let hidden = card(VennShape::Circle, VennColor::Yellow);
+        let solver = solver(&[Observation::Answer {
+            circle: 0,
+            target: hidden,
+            matches: true,
+        }]);
+        assert_eq!(solver.candidates(0).len(), 1);
+        let square = card(VennShape::Square, VennColor::Yellow);
+        assert_eq!(solver.fits(0, &square), Some(true));
+        let blue = card(VennShape::Square, VennColor::Blue);
+        assert_eq!(solver.fits(0, &blue), Some(false));
+        assert_eq!(solver.has(0, Value::Shape(VennShape::Circle)), Some(true));
+        assert_eq!(solver.has(0, Value::Color(VennColor::Blue)), Some(false));
+        // Nothing is known about the other circle.
+        assert_eq!(solver.fits(1, &square), None);
+    }
+
+    #[test]
+    fn a_red_guess_rules_out_what_it_fits() {
+        let guess = card(VennShape::Triangle, VennColor::Purple);
+        let solver = solver(&[Observation::Region {
+            region: Region::LeftOnly,
+            target: guess,
+            matches: false,
+        }]);
+        assert_eq!(solver.fits(0, &guess), Some(false));
+        assert!(solver.candidates(0).iter().all(|secret| match secret {
+            Secret::Card(_, card) => card.shape != guess.shape && card.color != guess.color,
+            Secret::Compound(_) => false,
+        }));
+        assert_eq!(solver.chance(&guess, Region::Both), 0.0);
+        // Going by the left circle alone, the right one is still open.
+        assert_eq!(solver.fits(1, &guess), None);
+    }
+
+    #[test]
+    fn once_the_cards_are_known_hints_are_sure() {
+        let left = card(VennShape::Circle, VennColor::Yellow);
+        let right = card(VennShape::Square, VennColor::Blue);
+        let solver = solver(&[
+            Observation::Answer {
+                circle: 0,
+                target: left,
+                matches: true,
+            },
+            Observation::Answer {
+                circle: 1,
+                target: right,
+                matches: true,
+            },
+        ]);
+        let guesses: Vec<_> = [left, right, card(VennShape::Circle, VennColor::Blue)]
+            .iter()
+            .enumerate()
+            .map(|(i, &target)| (i, target, vec![Region::LeftOnly, Region::Both]))
+            .collect();
+        let hint = solver.hint(&guesses).unwrap();
+        let (_, target, _) = guesses[hint.guess];
+        assert_eq!(solver.belonging(&target, hint.region), 1.0);
+    }
+}
@@ -0,0 +1,99 @@
+//! Sound effects for what happens on the board.
+//!
+//! The effects are picked and the volume and mute state kept here, so the
+//! game only says what happened. The clips of the effects are made while
+//! the game loads and played on the default output device with rodio.
+//! Built without the `sound` feature, or without a device to play on, the
+//! game is silent.
+
+use crate::assets::Clip;
+#[cfg(feature = "sound")]
+use log::warn;
+#[cfg(feature = "sound")]
+use rodio::{buffer::SamplesBuffer, OutputStream, OutputStreamHandle, Source};
+
+/// Something on the board worth a sound.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Effect {
+    PickUp,
+    /// A guess put down where it doesn't get a result, like the rail.
+    Drop,
+    /// A guess put down where it turned green.
+    Right,
+    /// A guess put down where it turned red.
+    Wrong,
+    RoundOver,
+}
+
+impl Effect {
+    pub const ALL: [Effect; 5] = [
+        Effect::PickUp,
+        Effect::Drop,
+        Effect::Right,
+        Effect::Wrong,
+        Effect::RoundOver,
+    ];
+}
+
+// Without the feature the volume and mute state are kept but never heard.
+#[cfg_attr(not(feature = "sound"), allow(dead_code))]
+pub struct Sound {
+    // From 0 to 100, as in the config file.
+    volume: u8,
+    muted: bool,
+    // The device effects are played on, which stops playing once the
+    // stream is dropped. None if there is no device.
+    #[cfg(feature = "sound")]
+    output: Option<(OutputStream, OutputStreamHandle)>,
+}
+
+impl Sound {
+    /// Opens the default output device, reporting it if there is none.
+    pub fn new(volume: u8) -> Sound {
+        Sound {
+            volume: volume.min(100),
+            muted: false,
+            #[cfg(feature = "sound")]
+            output: OutputStream::try_default()
+                .map_err(|error| warn!("could not open the sound device: {}", error))
+                .ok(),
+        }
+    }
+
+    pub fn set_volume(&mut self, volume: u8) {
+        self.volume = volume.min(100);
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+    }
+
+    // The gain effects are played at, nothing when muted.
+    #[cfg(feature = "sound")]
+    fn gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume as f32 / 100.0
+        }
+    }
+
+    /// Starts playing the clip over whatever is playing already, unless
+    /// muted or turned all the way down.
+    #[cfg(feature = "sound")]
+    pub fn play(&self, clip: &Clip) {
+        let gain = self.gain();
+        if gain == 0.0 {
+            return;
+        }
+        if let Some((_, handle)) = &self.output {
+            let source = SamplesBuffer::new(1, clip.rate, clip.samples.clone()).amplify(gain);
+            if let Err(error) = handle.play_raw(source) {
+                warn!("could not play a sound: {}", error);
+            }
+        }
+    }
+
+    #[cfg(not(feature = "sound"))]
+    pub fn play(&self, _clip: &Clip) {}
+}
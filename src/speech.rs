@@ -0,0 +1,97 @@
+//! Reading out what is on the board, for players who can't make out the
+//! shapes or can't read yet. The speaking is left to the speech program of
+//! the system: `say` on macOS, the speech synthesizer of PowerShell on
+//! Windows and speech-dispatcher's `spd-say` elsewhere. A program named by
+//! `VENN_SPEECH` is run with the text instead, to use another voice or play
+//! recorded clips.
+
+use crate::locale::Language;
+use log::warn;
+use std::{
+    env,
+    process::{Child, Command, Stdio},
+};
+
+/// Speaks one thing at a time.
+pub struct Speech {
+    // What is being said, to cut it off when there is something new.
+    current: Option<Child>,
+    // Set once the speech program can't be run, to stop trying.
+    failed: bool,
+}
+
+impl Speech {
+    pub fn new() -> Speech {
+        Speech {
+            current: None,
+            failed: false,
+        }
+    }
+
+    /// Says the text in the language, instead of whatever was being said.
+    pub fn say(&mut self, text: &str, language: Language) {
+        if self.failed {
+            return;
+        }
+        self.stop();
+        let spawned = command(text, language)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match spawned {
+            Ok(child) => self.current = Some(child),
+            Err(error) => {
+                warn!("could not speak: {}", error);
+                self.failed = true;
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut child) = self.current.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for Speech {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.current.take() {
+            let _ = child.kill();
+        }
+    }
+}
+
+// The command that speaks the text.
+fn command(text: &str, language: Language) -> Command {
+    if let Some(program) = env::var_os("VENN_SPEECH") {
+        let mut command = Command::new(program);
+        command.arg(text).env("VENN_LANGUAGE", language.code());
+        return command;
+    }
+    if cfg!(target_os = "macos") {
+        let mut command = Command::new("say");
+        command.arg(text);
+        command
+    } else if cfg!(target_os = "windows") {
+        // The text goes in the environment, out of reach of the script.
+        let mut command = Command::new("powershell");
+        command
+            .args([
+                "-NoProfile",
+                "-Command",
+                "Add-Type -AssemblyName System.Speech; \
+                 (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak($env:VENN_SAY)",
+            ])
+            .env("VENN_SAY", text);
+        command
+    } else {
+        // speech-dispatcher speaks on after spd-say is gone, so the next
+        // spd-say cancels it before it speaks, without the game waiting on
+        // a program of its own for that.
+        let mut command = Command::new("spd-say");
+        command.args(["--cancel", "--language", language.code(), text]);
+        command
+    }
+}
@@ -0,0 +1,233 @@
+//! Statistics kept across sessions in the user's data directory.
+
+use crate::{
+    adaptive::Adaptive,
+    model::{Place, VennModel},
+    profile,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// How often something was right.
+#[derive(Default, Copy, Clone, Serialize, Deserialize)]
+pub struct Tally {
+    pub right: u32,
+    pub total: u32,
+}
+
+impl Tally {
+    fn add(&mut self, right: bool) {
+        self.total += 1;
+        if right {
+            self.right += 1;
+        }
+    }
+
+    /// The share that was right, or None if there is nothing to go by.
+    pub fn percent(&self) -> Option<u32> {
+        if self.total == 0 {
+            None
+        } else {
+            Some((self.right as f32 / self.total as f32 * 100.0).round() as u32)
+        }
+    }
+}
+
+/// How the placements of solved rounds compare with par, what following the
+/// solver's hints takes.
+#[derive(Default, Copy, Clone, Serialize, Deserialize)]
+pub struct Par {
+    pub rounds: u32,
+    pub placements: u32,
+    pub par: u32,
+    /// Rounds solved in par placements or fewer.
+    pub made: u32,
+}
+
+impl Par {
+    fn add(&mut self, placements: u32, par: u32) {
+        self.rounds += 1;
+        self.placements += placements;
+        self.par += par;
+        if placements <= par {
+            self.made += 1;
+        }
+    }
+
+    /// How many placements over par a round takes on average, under par if
+    /// negative, or None if there is nothing to go by.
+    pub fn average(&self) -> Option<f32> {
+        if self.rounds == 0 {
+            None
+        } else {
+            Some((self.placements as f32 - self.par as f32) / self.rounds as f32)
+        }
+    }
+}
+
+/// What every finished round added up to.
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    /// Rounds that ended, however they ended.
+    pub played: u32,
+    pub solved: u32,
+    pub given_up: u32,
+    /// Seconds spent on the solved rounds.
+    pub solve_time: f32,
+    pub best_streak: u32,
+    /// Whether the guesses dropped in the diagram were green.
+    pub placements: Tally,
+    /// Whether the cards built in an answer box had each attribute of the
+    /// hidden card, for the attributes the level cared about.
+    pub shape: Tally,
+    pub color: Tally,
+    pub size: Tally,
+    pub pattern: Tally,
+    pub count: Tally,
+    pub orientation: Tally,
+    pub fill: Tally,
+    pub par: Par,
+    /// How the latest rounds went, for adaptive difficulty.
+    pub adaptive: Adaptive,
+}
+
+impl Stats {
+    /// Adds a round that just ended, with its par if it has one. Given up
+    /// rounds only count as played, since everything is moved where it
+    /// belongs.
+    pub fn record(&mut self, model: &VennModel, par: Option<u32>) {
+        self.played += 1;
+        self.best_streak = self.best_streak.max(model.score().best_streak);
+        self.adaptive.record(model);
+        if model.is_forfeited() {
+            self.given_up += 1;
+            return;
+        }
+        if model.is_won() {
+            self.solved += 1;
+            self.solve_time += model.elapsed();
+            if let Some(par) = par {
+                self.par.add(model.placements(), par);
+            }
+        }
+        for result in model.results() {
+            if let Place::Region(_) = result.place {
+                self.placements.add(result.right);
+            }
+        }
+        let level = model.current_level();
+        for circle in model.circles() {
+            let (card, built) = match circle.answer.built {
+                Some(built) => (circle.answer.target, built),
+                None => continue,
+            };
+            self.shape.add(card.shape == built.shape);
+            if level.color {
+                self.color.add(card.color == built.color);
+            }
+            if level.size {
+                self.size.add(card.size == built.size);
+            }
+            if level.pattern {
+                self.pattern.add(card.pattern == built.pattern);
+            }
+            if level.count {
+                self.count.add(card.count == built.count);
+            }
+            if level.orientation {
+                self.orientation.add(card.orientation == built.orientation);
+            }
+            if level.fill {
+                self.fill.add(card.fill == built.fill);
+            }
+        }
+    }
+
+    /// Seconds a solved round takes on average.
+    pub fn average_solve_time(&self) -> Option<f32> {
+        if self.solved == 0 {
+            None
+        } else {
+            Some(self.solve_time / self.solved as f32)
+        }
+    }
+}
+
+/// Where the statistics are kept when no profile is played with, if there
+/// is a data directory to keep them in.
+pub fn path() -> Option<PathBuf> {
+    profile::root().map(|root| root.join("stats.json"))
+}
+
+/// Reads the statistics, which start out empty if there are none yet.
+pub fn read(path: &Path) -> io::Result<Stats> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(serde_json::from_str(&contents)?),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Stats::default()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Writes the statistics, making the directory for them if needed.
+pub fn write(path: &Path, stats: &Stats) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(stats)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{autoplay, model::Options};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn model(seed: u64) -> VennModel {
+        let options = Options {
+            seed,
+            ..Options::default()
+        };
+        VennModel::new(2, &options, &mut StdRng::seed_from_u64(seed))
+    }
+
+    #[test]
+    fn rounds_add_up() {
+        let mut stats = Stats::default();
+        let mut solved = model(1);
+        assert!(autoplay::play(&mut solved).solved);
+        // One placement under par.
+        stats.record(&solved, Some(solved.placements() + 1));
+        let mut given_up = model(2);
+        given_up.give_up();
+        stats.record(&given_up, Some(1));
+        assert_eq!((stats.played, stats.solved, stats.given_up), (2, 1, 1));
+        // Every guess ended up where it belongs.
+        assert_eq!(stats.placements.percent(), Some(100));
+        assert_eq!((stats.par.rounds, stats.par.made), (1, 1));
+        assert_eq!(stats.par.average(), Some(-1.0));
+        assert_eq!(stats.average_solve_time(), Some(solved.elapsed()));
+        // No answers were built.
+        assert_eq!(stats.shape.percent(), None);
+    }
+
+    #[test]
+    fn stats_are_kept_between_sessions() {
+        let path = std::env::temp_dir().join(format!("venn-stats-{}.json", std::process::id()));
+        assert_eq!(read(&path).unwrap().played, 0);
+        let mut stats = Stats {
+            played: 3,
+            ..Stats::default()
+        };
+        stats.color.add(true);
+        stats.color.add(false);
+        write(&path, &stats).unwrap();
+        let read = read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.played, 3);
+        assert_eq!(read.color.percent(), Some(50));
+    }
+}
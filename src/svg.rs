@@ -0,0 +1,186 @@
+//! Writing a round as an SVG file, to print or embed in a page. The shapes
+//! come from the same drawing code as the window.
+
+use crate::{
+    graphics::{Color, Point, Rectangle, Shape},
+    model::{VennModel, HEIGHT, WIDTH},
+    palette::Style,
+    render::Canvas,
+    words,
+};
+use std::{fmt::Write as _, fs, io, path::Path};
+
+/// An SVG document being drawn on.
+pub struct Svg {
+    body: String,
+}
+
+impl Svg {
+    pub fn new() -> Svg {
+        Svg {
+            body: String::new(),
+        }
+    }
+
+    /// Writes the text centered on the point, hanging down from it or
+    /// standing on it, with each line of the text under the one before.
+    pub fn text(&mut self, content: &str, position: Point, size: f32, hanging: bool, color: Color) {
+        let lines: Vec<&str> = content.lines().collect();
+        // Text standing on the point grows upwards.
+        let first = if hanging {
+            position.y + size
+        } else {
+            position.y - size * 1.2 * (lines.len() as f32 - 1.0)
+        };
+        for (i, line) in lines.iter().enumerate() {
+            let _ = writeln!(
+                self.body,
+                r#"<text x="{}" y="{}" font-size="{}" font-family="monospace" text-anchor="middle" fill="{}">{}</text>"#,
+                position.x,
+                first + i as f32 * size * 1.2,
+                size,
+                rgb(color),
+                escape(line)
+            );
+        }
+    }
+
+    /// The whole document, the size of the window.
+    pub fn document(&self) -> String {
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+                "\n",
+                r#"<rect width="{w}" height="{h}" fill="white"/>"#,
+                "\n{body}</svg>\n"
+            ),
+            w = WIDTH,
+            h = HEIGHT,
+            body = self.body
+        )
+    }
+
+    fn shape(&mut self, shape: Shape, paint: String) {
+        let _ = match shape {
+            Shape::Rectangle(r) => writeln!(
+                self.body,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                r.x, r.y, r.width, r.height, paint
+            ),
+            Shape::Circle { center, radius } => writeln!(
+                self.body,
+                r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+                center.x, center.y, radius, paint
+            ),
+            Shape::Ellipse {
+                center,
+                horizontal_radius,
+                vertical_radius,
+                rotation,
+            } => writeln!(
+                self.body,
+                r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}" transform="rotate({} {} {})" {}/>"#,
+                center.x,
+                center.y,
+                horizontal_radius,
+                vertical_radius,
+                rotation.to_degrees(),
+                center.x,
+                center.y,
+                paint
+            ),
+            Shape::Polyline { points } => {
+                let points: Vec<String> =
+                    points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+                writeln!(
+                    self.body,
+                    r#"<polyline points="{}" {}/>"#,
+                    points.join(" "),
+                    paint
+                )
+            }
+        };
+    }
+}
+
+impl Canvas for Svg {
+    fn fill(&mut self, shape: Shape, color: Color) {
+        let paint = format!(r#"fill="{}" fill-opacity="{}""#, rgb(color), color.a);
+        self.shape(shape, paint);
+    }
+
+    fn stroke(&mut self, shape: Shape, color: Color, width: u16) {
+        let paint = format!(
+            r#"fill="none" stroke="{}" stroke-opacity="{}" stroke-width="{}""#,
+            rgb(color),
+            color.a,
+            width
+        );
+        self.shape(shape, paint);
+    }
+}
+
+fn rgb(color: Color) -> String {
+    let [r, g, b, _] = color.to_rgba();
+    format!("rgb({},{},{})", r, g, b)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The round as an SVG document: the circles with their hidden cards and
+/// labels, and every guess green or red for where it ended up.
+pub fn export(model: &VennModel, style: &Style) -> String {
+    let mut svg = Svg::new();
+    svg.fill(
+        Shape::Rectangle(Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: WIDTH,
+            height: HEIGHT,
+        }),
+        style.theme.background,
+    );
+    // Away from the diagram, so nothing is highlighted.
+    let away = Point::new(-WIDTH, -HEIGHT);
+    model.draw_diagram(&mut svg, style, away, true);
+    for i in model.stacking() {
+        model.guesses()[i].draw(&mut svg, style);
+    }
+    let level = model.current_level();
+    for circle in model.circles() {
+        let (position, above) = words::label_at(circle);
+        let label = words::caption(circle, level, true, above);
+        svg.text(&label, position, 18.0, above, style.theme.text);
+    }
+    // A puzzle's own cards are written out by name.
+    if let Some(tokens) = model.tokens() {
+        let guesses = model.guesses().iter();
+        let mut names: Vec<(&str, Point)> = guesses
+            .filter_map(|guess| Some((guess.name.as_deref()?, guess.center)))
+            .collect();
+        for circle in model.circles().filter(|c| c.compound.is_none()) {
+            if let Some(name) = tokens.named(&circle.answer.target) {
+                names.push((name, circle.answer.center));
+            }
+        }
+        for (name, center) in names {
+            let size = 12.0;
+            let position = Point::new(center.x, center.y + size / 3.0);
+            svg.text(name, position, size, false, style.theme.text);
+        }
+    }
+    svg.document()
+}
+
+/// Writes the round to the file as SVG, making the directory for it if
+/// needed.
+pub fn write(path: &Path, model: &VennModel, style: &Style) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, export(model, style))
+}
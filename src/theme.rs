@@ -0,0 +1,201 @@
+//! The colors of everything around the cards: the background, lines, text,
+//! highlights and whether a guess is right. The cards keep the colors of
+//! the palette whatever the theme, since they are what is played with.
+
+use crate::colors::{GREEN, RED, YELLOW};
+use crate::graphics::Color;
+use serde::{Deserialize, Serialize};
+
+/// The colors the board and the text over it are drawn in.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Theme {
+    /// Behind the board.
+    pub background: Color,
+    /// Behind menus, buttons and the screens over the board.
+    pub panel: Color,
+    pub text: Color,
+    /// The edges of circles, boxes, guesses and buttons.
+    pub stroke: Color,
+    /// Text that stays in the background, like the instructions.
+    pub faint: Color,
+    /// A guess that hasn't been placed where it gets a result.
+    pub neutral: Color,
+    /// An answer box under the pointer.
+    pub highlight: Color,
+    /// What the tutorial points at.
+    pub attention: Color,
+    /// The suggested move, and where a held guess can be dropped.
+    pub hint: Color,
+    /// A guess or an answer that turned out right, with the standard
+    /// palette.
+    pub right: Color,
+    /// One that turned out wrong.
+    pub wrong: Color,
+    /// Warnings like the clock running out, and lives.
+    pub alert: Color,
+    /// Behind the tooltip and the details of a guess.
+    pub note: Color,
+    /// The chosen entry of a menu or chip of the builder.
+    pub selection: Color,
+    pub trail: Color,
+}
+
+impl Theme {
+    /// Dark lines on white, as the game has always looked.
+    pub const LIGHT: Theme = Theme {
+        background: Color::WHITE,
+        panel: Color::WHITE,
+        text: Color::BLACK,
+        stroke: Color::BLACK,
+        faint: gray(0.3),
+        neutral: gray(0.7),
+        highlight: YELLOW,
+        attention: Color {
+            r: 1.0,
+            g: 0.5,
+            b: 0.0,
+            a: 1.0,
+        },
+        hint: GREEN,
+        right: GREEN,
+        wrong: RED,
+        alert: RED,
+        note: Color {
+            r: 1.0,
+            g: 1.0,
+            b: 0.88,
+            a: 1.0,
+        },
+        selection: Color {
+            r: 0.867,
+            g: 0.933,
+            b: 1.0,
+            a: 1.0,
+        },
+        trail: gray(0.2),
+    };
+
+    /// Light lines on a dark gray, easier on the eyes in a dark room.
+    pub const DARK: Theme = Theme {
+        background: Color {
+            r: 0.12,
+            g: 0.12,
+            b: 0.14,
+            a: 1.0,
+        },
+        panel: Color {
+            r: 0.18,
+            g: 0.18,
+            b: 0.2,
+            a: 1.0,
+        },
+        text: gray(0.92),
+        stroke: gray(0.85),
+        faint: gray(0.6),
+        neutral: gray(0.35),
+        highlight: YELLOW,
+        attention: Color {
+            r: 1.0,
+            g: 0.6,
+            b: 0.2,
+            a: 1.0,
+        },
+        hint: GREEN,
+        right: Color {
+            r: 0.2,
+            g: 0.8,
+            b: 0.3,
+            a: 1.0,
+        },
+        wrong: Color {
+            r: 0.9,
+            g: 0.25,
+            b: 0.25,
+            a: 1.0,
+        },
+        alert: Color {
+            r: 1.0,
+            g: 0.4,
+            b: 0.4,
+            a: 1.0,
+        },
+        note: Color {
+            r: 0.25,
+            g: 0.25,
+            b: 0.2,
+            a: 1.0,
+        },
+        selection: Color {
+            r: 0.2,
+            g: 0.3,
+            b: 0.45,
+            a: 1.0,
+        },
+        trail: gray(0.8),
+    };
+
+    /// White and pure colors on black, for players who need the most
+    /// contrast.
+    pub const HIGH_CONTRAST: Theme = Theme {
+        background: Color::BLACK,
+        panel: Color::BLACK,
+        text: Color::WHITE,
+        stroke: Color::WHITE,
+        faint: Color::WHITE,
+        neutral: gray(0.25),
+        highlight: YELLOW,
+        attention: Color {
+            r: 0.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        },
+        hint: GREEN,
+        right: GREEN,
+        wrong: RED,
+        alert: Color {
+            r: 1.0,
+            g: 0.3,
+            b: 0.3,
+            a: 1.0,
+        },
+        note: Color::BLACK,
+        selection: Color {
+            r: 0.0,
+            g: 0.0,
+            b: 0.6,
+            a: 1.0,
+        },
+        trail: Color::WHITE,
+    };
+}
+
+const fn gray(level: f32) -> Color {
+    Color {
+        r: level,
+        g: level,
+        b: level,
+        a: 1.0,
+    }
+}
+
+/// The themes the settings step through.
+#[derive(PartialEq, Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub enum ThemeName {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl ThemeName {
+    pub const ALL: [ThemeName; 3] = [ThemeName::Light, ThemeName::Dark, ThemeName::HighContrast];
+
+    pub fn theme(self) -> Theme {
+        match self {
+            ThemeName::Light => Theme::LIGHT,
+            ThemeName::Dark => Theme::DARK,
+            ThemeName::HighContrast => Theme::HIGH_CONTRAST,
+        }
+    }
+}
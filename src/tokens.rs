@@ -0,0 +1,359 @@
+//! Cards of a class's own making, read from a CSV file with a column for
+//! each attribute, like animals by class, habitat and diet. Each column
+//! stands in for one of the attributes the game already has, so rules,
+//! hints and scoring work on them unchanged, and the cards are written out
+//! by name instead of drawn.
+//!
+//! The first line names the columns. The first holds the names of the
+//! cards, and its heading says what they are:
+//!
+//! ```text
+//! animal,class,habitat,diet
+//! frog,amphibian,water,meat
+//! cow,mammal,land,plants
+//! ```
+
+use crate::shapes::{Kind, Value, VennShape, VennTarget};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// The attributes the columns stand in for, in the order the levels bring
+// them in, so a level compares the first few columns.
+const KINDS: [Kind; 3] = [Kind::Shape, Kind::Color, Kind::Size];
+
+/// The cards of a CSV file and the attributes they differ in.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Tokens {
+    /// What the cards are, like animal.
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub tokens: Vec<Token>,
+}
+
+/// An attribute of the cards, like habitat, with the values found in its
+/// column in the order they first appear.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    /// The attribute whose values stand in for the column's, in order.
+    pub kind: Kind,
+    pub values: Vec<String>,
+}
+
+/// One card of the file, like frog.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Token {
+    pub name: String,
+    /// The values standing in for the card's, which the rules go by.
+    pub target: VennTarget,
+    /// The PNG file the card is shown as, instead of its name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<PathBuf>,
+}
+
+impl Tokens {
+    /// The card the attributes stand for, if there is one.
+    pub fn named(&self, target: &VennTarget) -> Option<&str> {
+        self.tokens
+            .iter()
+            .find(|token| token.target == *target)
+            .map(|token| token.name.as_str())
+    }
+
+    /// The picture of the card the attributes stand for, if it has one.
+    pub fn image(&self, target: &VennTarget) -> Option<&Path> {
+        let token = self.tokens.iter().find(|token| token.target == *target);
+        token.and_then(|token| token.image.as_deref())
+    }
+
+    /// The card called the name, if there is one.
+    pub fn find(&self, name: &str) -> Option<&Token> {
+        self.tokens.iter().find(|token| token.name == name)
+    }
+
+    /// The value standing in for the word in the column, like water in
+    /// habitat.
+    pub fn value(&self, column: &str, word: &str) -> Result<Value, String> {
+        let found = self.columns.iter().find(|c| c.name == column);
+        let column = found.ok_or_else(|| format!("the tokens have no {} column", column))?;
+        match column.values.iter().position(|value| value == word) {
+            Some(index) => Ok(column.kind.values()[index]),
+            None => Err(format!(
+                "no token has {} in its {} column",
+                word, column.name
+            )),
+        }
+    }
+
+    pub fn targets(&self) -> Vec<VennTarget> {
+        self.tokens.iter().map(|token| token.target).collect()
+    }
+
+    /// The shapes standing in for the values of the first column.
+    pub fn shapes(&self) -> Vec<VennShape> {
+        self.tokens.iter().fold(Vec::new(), |mut shapes, token| {
+            if !shapes.contains(&token.target.shape) {
+                shapes.push(token.target.shape);
+            }
+            shapes
+        })
+    }
+}
+
+/// Reads and checks a CSV file of cards.
+pub fn read(path: &Path) -> io::Result<Tokens> {
+    let contents = fs::read_to_string(path)?;
+    parse(&contents).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Reads cards from the text of a CSV file.
+pub fn parse(source: &str) -> Result<Tokens, String> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header = match lines.next() {
+        Some((_, line)) => fields(line),
+        None => return Err("the tokens file is empty".to_string()),
+    };
+    let mut rows = Vec::new();
+    for (number, line) in lines {
+        let mut row = fields(line);
+        if row.len() != header.len() {
+            return Err(format!(
+                "line {} has {} fields, the first line {}",
+                number + 1,
+                row.len(),
+                header.len()
+            ));
+        }
+        if row[0].is_empty() {
+            return Err(format!("line {} has no name", number + 1));
+        }
+        let name = row.remove(0);
+        rows.push((name, row));
+    }
+    build(&header[0], &header[1..], rows)
+}
+
+/// Makes cards called what they are, with the two or three attributes
+/// named by the headings, of the names of the cards and their values of
+/// each attribute in order. Every level compares at least two attributes,
+/// so a single one is turned down.
+pub fn build(
+    name: &str,
+    headings: &[String],
+    rows: Vec<(String, Vec<String>)>,
+) -> Result<Tokens, String> {
+    if headings.len() < 2 {
+        return Err(format!(
+            "the tokens need at least 2 attributes for a level to compare, not {}",
+            headings.len()
+        ));
+    }
+    if headings.len() > KINDS.len() {
+        return Err(format!(
+            "the tokens have at most {} attributes, not {}",
+            KINDS.len(),
+            headings.len()
+        ));
+    }
+    let mut columns: Vec<Column> = headings
+        .iter()
+        .zip(KINDS)
+        .map(|(name, kind)| Column {
+            name: name.clone(),
+            kind,
+            values: Vec::new(),
+        })
+        .collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    for (name, row) in rows {
+        if row.len() != headings.len() {
+            return Err(format!(
+                "{} has {} values, there are {} attributes",
+                name,
+                row.len(),
+                headings.len()
+            ));
+        }
+        if tokens.iter().any(|token| token.name == name) {
+            return Err(format!("there are two tokens called {}", name));
+        }
+        let mut target = VennTarget::default();
+        for (column, value) in columns.iter_mut().zip(&row) {
+            let index = match column.values.iter().position(|v| v == value) {
+                Some(index) => index,
+                None => {
+                    column.values.push(value.clone());
+                    column.values.len() - 1
+                }
+            };
+            let stand_ins = column.kind.values();
+            match stand_ins.get(index) {
+                Some(stand_in) => stand_in.set(&mut target),
+                None => {
+                    return Err(format!(
+                        "the {} column can have at most {} values, {} is one too many",
+                        column.name,
+                        stand_ins.len(),
+                        value
+                    ))
+                }
+            }
+        }
+        if let Some(same) = tokens.iter().find(|token| token.target == target) {
+            return Err(format!(
+                "{} and {} have the same values, so they can't be told apart",
+                same.name, name
+            ));
+        }
+        tokens.push(Token {
+            name,
+            target,
+            image: None,
+        });
+    }
+    if tokens.is_empty() {
+        return Err("there are no tokens".to_string());
+    }
+    Ok(Tokens {
+        name: name.to_string(),
+        columns,
+        tokens,
+    })
+}
+
+// The fields of a line, trimmed. A field in double quotes can hold commas,
+// and two double quotes in it stand for one.
+fn fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => field.push(c),
+        }
+    }
+    fields
+        .iter()
+        .map(|field| field.trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        puzzle,
+        screenshot::Raster,
+        shapes::{VennColor, VennSize},
+    };
+
+    const ANIMALS: &str = "animal,class,habitat,diet\n\
+                           frog,amphibian,water,meat\n\
+                           cow,mammal,land,plants\n\
+                           \n\
+                           \"duck, mallard\",bird,water,plants\n";
+
+    #[test]
+    fn columns_stand_in_for_attributes() {
+        let tokens = parse(ANIMALS).unwrap();
+        assert_eq!(tokens.name, "animal");
+        let names: Vec<&str> = tokens.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["class", "habitat", "diet"]);
+        assert_eq!(tokens.columns[1].values, ["water", "land"]);
+        let duck = tokens.find("duck, mallard").unwrap().target;
+        assert_eq!(duck.shape, VennShape::Triangle);
+        assert_eq!(duck.color, VennColor::Yellow);
+        assert_eq!(duck.size, VennSize::Medium);
+        assert_eq!(tokens.named(&duck), Some("duck, mallard"));
+        assert_eq!(
+            tokens.value("diet", "plants"),
+            Ok(Value::Size(VennSize::Medium))
+        );
+        assert!(tokens.value("diet", "water").is_err());
+        assert!(tokens.value("legs", "4").is_err());
+        assert_eq!(tokens.shapes().len(), 3);
+    }
+
+    #[test]
+    fn bad_files_are_turned_down() {
+        let bad = [
+            "",
+            "name\nfrog\n",
+            "name,a,b,c,d\nfrog,1,2,3,4\n",
+            "name,habitat\nfrog,water\ncow,land\n",
+            "name,habitat,legs\nfrog,water,4,wet\n",
+            "name,habitat,legs\nfrog,water,4\nfrog,land,4\n",
+            "name,habitat,legs\nfrog,water,4\ntoad,water,4\n",
+            "name,class,habitat\nfrog,a,water\ncow,a,land\nbat,a,air\nmole,a,soil\n",
+            "name,habitat,legs\n",
+        ];
+        for source in bad {
+            assert!(parse(source).is_err(), "{:?}", source);
+        }
+        // The first column has room for every shape.
+        let shapes = "name,habitat,legs\nfrog,water,4\ncow,land,4\nbat,air,2\nmole,soil,4\n";
+        assert!(parse(shapes).is_ok());
+    }
+
+    #[test]
+    fn the_example_puzzle_reads() {
+        let puzzle = puzzle::read(Path::new("puzzles/animals.json")).unwrap();
+        let tokens = puzzle.table.unwrap();
+        assert_eq!(tokens.tokens.len(), 14);
+        let water = puzzle.circles[0].compound.as_ref().unwrap();
+        assert!(water.evaluate(&tokens.find("frog").unwrap().target));
+        assert!(!water.evaluate(&tokens.find("cow").unwrap().target));
+    }
+
+    // Pictures are declared in the puzzle file, next to which they are.
+    #[test]
+    fn pictures_read() {
+        let dir = std::env::temp_dir().join(format!("venn-pictures-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        Raster::new(0.1).write(&dir.join("frog.png")).unwrap();
+        let puzzle = r#"{
+            "level": 0,
+            "pictures": {
+                "name": "animal",
+                "attributes": ["habitat", "legs"],
+                "cards": [
+                    { "name": "frog", "image": "frog.png", "values": ["water", "4"] },
+                    { "name": "cow", "image": "cow.png", "values": ["land", "4"] }
+                ]
+            },
+            "circles": [
+                { "rule": "Any", "token": "frog" },
+                { "rule": "Not", "token": "frog" }
+            ]
+        }"#;
+        let path = dir.join("animals.json");
+        fs::write(&path, puzzle).unwrap();
+        // The cow has no picture yet.
+        assert!(puzzle::read(&path).is_err());
+        fs::write(dir.join("cow.png"), "moo").unwrap();
+        assert!(puzzle::read(&path).is_err());
+        Raster::new(0.1).write(&dir.join("cow.png")).unwrap();
+        let read = puzzle::read(&path).unwrap();
+        let tokens = read.table.unwrap();
+        let frog = tokens.find("frog").unwrap();
+        assert_eq!(read.circles[1].card, frog.target);
+        assert_eq!(
+            tokens.image(&frog.target),
+            Some(dir.join("frog.png").as_path())
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,36 @@
+//! Fading trails of where guesses have been dragged, to see where a player
+//! hesitated.
+
+use crate::graphics::Point;
+use crate::guess::VennGuess;
+
+// How many seconds a point of a trail takes to fade away.
+pub(crate) const FADE: f32 = 3.0;
+
+/// The recent positions of every guess, each with how much of it is left
+/// from 1 down to 0.
+#[derive(Default)]
+pub struct Trails {
+    pub(crate) paths: Vec<Vec<(Point, f32)>>,
+}
+
+impl Trails {
+    /// Adds where the guesses are now if they moved, and fades what is
+    /// already there by the seconds since the last update.
+    pub fn update(&mut self, guesses: &[VennGuess], seconds: f32) {
+        self.paths.resize(guesses.len(), Vec::new());
+        for (path, guess) in self.paths.iter_mut().zip(guesses) {
+            for (_, life) in path.iter_mut() {
+                *life -= seconds / FADE;
+            }
+            path.retain(|&(_, life)| life > 0.0);
+            if path.last().is_none_or(|&(point, _)| point != guess.center) {
+                path.push((guess.center, 1.0));
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.paths.clear();
+    }
+}
@@ -0,0 +1,54 @@
+//! A walk through the first moves for new players, one step at a time.
+
+use crate::model::{Place, VennModel};
+
+/// What the tutorial is waiting for the player to do.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Step {
+    /// Pick up any guess.
+    PickUp,
+    /// Drop the guess that was picked up inside a circle.
+    Drop { guess: usize },
+    /// Look at whether the dropped guess turned green or red, then press
+    /// Space.
+    Feedback { guess: usize },
+    /// Build a card in the box above a circle to guess its hidden card.
+    Answer,
+    /// Press Space to carry on playing.
+    Done,
+}
+
+/// Which step a new player is on. Each step only moves on once it has been
+/// done.
+pub struct Tutorial {
+    step: Step,
+}
+
+impl Tutorial {
+    pub fn new() -> Tutorial {
+        Tutorial { step: Step::PickUp }
+    }
+
+    pub fn step(&self) -> Step {
+        self.step
+    }
+
+    /// Moves on if the board shows the step was done, or if the player asked
+    /// for the next step. Returns false once the tutorial is over.
+    pub fn advance(&mut self, model: &VennModel, next: bool) -> bool {
+        self.step = match (self.step, model.dragging()) {
+            (Step::PickUp, Some(guess)) => Step::Drop { guess },
+            (Step::Drop { guess }, None) => match model.results()[guess].place {
+                Place::Region(_) if model.guesses()[guess].matches.is_some() => {
+                    Step::Feedback { guess }
+                }
+                _ => Step::PickUp,
+            },
+            (Step::Feedback { .. }, _) if next => Step::Answer,
+            (Step::Answer, _) if model.circles().any(|c| c.is_answered()) => Step::Done,
+            (Step::Done, _) if next => return false,
+            (step, _) => step,
+        };
+        true
+    }
+}
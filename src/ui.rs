@@ -0,0 +1,1954 @@
+//! Text drawn over the diagram: circle labels, the score and instructions.
+
+use crate::{
+    adaptive::Trend,
+    builder::{self, Builder, Chip},
+    buttons::Button,
+    circle::VennSet,
+    code,
+    colors::PLAYERS,
+    config::Config,
+    diagnostics::Diagnostics,
+    editor::Editor,
+    generator,
+    leaderboard::Board,
+    locale::{self, fill, text},
+    model::{Mode, Place, Preset, VennModel, HEIGHT, WIDTH},
+    notebook::{self, Mark, Notebook},
+    pack::{Pack, Progress},
+    palette::{Palette, Style},
+    region::{Boundary, Region},
+    seat::HotSeat,
+    shapes::{Kind, Value, VennTarget},
+    stats::{Stats, Tally},
+    theme::ThemeName,
+    tokens::Tokens,
+    tutorial::{Step, Tutorial},
+    words::{
+        caption, color_name, count_name, describe, fill_name, kind_key, kind_name, label, label_at,
+        orientation_name, pattern_name, rule_text, shape_name, size_name, value_key, value_name,
+    },
+};
+use coffee::graphics::{
+    Color, Font, HorizontalAlignment, Mesh, Point, Rectangle, Shape, Target, Text,
+    VerticalAlignment,
+};
+
+// The most entries a menu has in one column.
+const ONE_COLUMN: usize = 11;
+
+// What the debug overlay outlines.
+const DEBUG: Color = Color {
+    r: 0.8,
+    g: 0.0,
+    b: 0.8,
+    a: 1.0,
+};
+
+pub struct Ui {
+    font: Font,
+    // How the game is drawn, for the colors of the theme.
+    style: Style,
+}
+
+impl Ui {
+    pub fn new(font: Font) -> Ui {
+        Ui {
+            font,
+            style: Style::default(),
+        }
+    }
+
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    // A size of text over the board, scaled up with the board.
+    fn size(&self, size: f32) -> f32 {
+        size * self.style.scale
+    }
+
+    // Like size, but only as big as lets the longest line of the content
+    // fit across the window.
+    fn fitted(&self, size: f32, content: &str) -> f32 {
+        let widest = (WIDTH - 20.0) / text_width(content, 1.0).max(0.5);
+        self.size(size).min(widest.max(size))
+    }
+
+    pub fn draw(&mut self, target: &mut Target<'_>, model: &VennModel, round: u32) {
+        let level = model.current_level();
+        // The labels give the rules away, so they only show once the round
+        // is over or when the cards are shown anyway.
+        let finished = model.is_finished() || model.shows_rules();
+        for circle in model.circles() {
+            let (position, above) = label_at(circle);
+            let content = caption(circle, level, finished, above);
+            self.font.add(Text {
+                content: &content,
+                position,
+                bounds: (circle.radius, f32::INFINITY),
+                size: self.size(18.0),
+                horizontal_alignment: HorizontalAlignment::Center,
+                vertical_alignment: if above {
+                    VerticalAlignment::Top
+                } else {
+                    VerticalAlignment::Bottom
+                },
+                color: self.style.theme.text,
+            });
+            // A compound rule is written in its box, as there is no card to
+            // draw there.
+            if let Some(expr) = &circle.compound {
+                let revealed = model.is_forfeited() || model.shows_rules();
+                let answer = &circle.answer;
+                let shown = match (&answer.built_rule, answer.built_kind) {
+                    _ if revealed => Some(rule_text(expr)),
+                    (Some(built), _) => Some(rule_text(built)),
+                    // What was named in Set mode.
+                    (None, Some(kind)) => Some(kind_name(kind).to_string()),
+                    (None, None) => None,
+                };
+                if let Some(shown) = shown {
+                    self.font.add(Text {
+                        content: &shown,
+                        position: answer.center,
+                        bounds: (answer.width - 8.0, answer.height),
+                        size: self.size(14.0),
+                        horizontal_alignment: HorizontalAlignment::Center,
+                        vertical_alignment: VerticalAlignment::Center,
+                        color: self.style.theme.text,
+                    });
+                }
+            }
+        }
+        if let Some(tokens) = model.tokens() {
+            self.draw_names(model, tokens);
+        }
+        let score = model.score();
+        let sandbox = model.options().mode == Mode::Sandbox;
+        let mut lines = vec![
+            fill("hud.score", &[&score.points]),
+            fill("hud.streak", &[&score.streak, &score.best_streak]),
+            fill("hud.level", &[&(model.level() + 1)]),
+            fill(
+                "hud.code",
+                &[&code::encode(
+                    model.options().seed,
+                    model.options().difficulty,
+                )],
+            ),
+        ];
+        // Nothing is scored in the sandbox.
+        if sandbox {
+            lines.drain(..2);
+            lines.insert(0, text("hud.sandbox").to_string());
+        }
+        if score.forfeits > 0 {
+            lines.push(fill("hud.given_up", &[&score.forfeits]));
+        }
+        if let Some(left) = model.placements_left() {
+            lines.push(fill("hud.placements_left", &[&left]));
+        }
+        // The hearts go on a line of their own.
+        let hearts = model.lives_left().map(|_| {
+            lines.push(String::new());
+            lines.len() - 1
+        });
+        // Time attack counts down, otherwise the clock just runs.
+        let (seconds, hurry) = match model.remaining() {
+            Some(remaining) => (remaining.ceil(), remaining < 10.0),
+            None => (model.elapsed().floor(), false),
+        };
+        lines.push(fill("hud.time", &[&clock(seconds)]));
+        let line_height = self.size(24.0);
+        for (i, line) in lines.iter().enumerate() {
+            let last = i == lines.len() - 1;
+            self.font.add(Text {
+                content: line,
+                position: Point::new(WIDTH - 10.0, 10.0 + i as f32 * line_height),
+                size: self.size(20.0),
+                color: if last && hurry {
+                    self.style.theme.alert
+                } else {
+                    self.style.theme.text
+                },
+                horizontal_alignment: HorizontalAlignment::Right,
+                ..Text::default()
+            });
+        }
+        if let Some(line) = hearts {
+            let mut mesh = Mesh::new();
+            model.draw_lives(
+                &mut mesh,
+                &self.style,
+                Point::new(
+                    WIDTH - 10.0,
+                    10.0 + self.size(12.0) + line as f32 * line_height,
+                ),
+            );
+            mesh.draw(target);
+        }
+        let over = if model.is_timed_out() {
+            Some(text("over.time"))
+        } else if model.is_out_of_placements() {
+            Some(text("over.placements"))
+        } else if model.is_out_of_lives() {
+            Some(text("over.lives"))
+        } else {
+            None
+        };
+        if let Some(over) = over {
+            self.font.add(Text {
+                content: over,
+                position: Point::new(WIDTH / 2.0, 10.0),
+                size: self.fitted(28.0, over),
+                color: self.style.theme.alert,
+                horizontal_alignment: HorizontalAlignment::Center,
+                ..Text::default()
+            });
+        }
+        // What a screenshot or a bug report needs to tell the round apart.
+        let options = model.options();
+        let status = fill(
+            "hud.status",
+            &[
+                &round,
+                &options.seed,
+                &difficulty_name(options.difficulty),
+                &model.left_to_place(),
+            ],
+        );
+        // The status goes just over the instructions, however big they are.
+        let instructions = text(if sandbox {
+            "instructions.sandbox"
+        } else {
+            "instructions"
+        });
+        let size = self.fitted(16.0, instructions);
+        let under = instructions.lines().count() as f32 * size + 8.0;
+        self.font.add(Text {
+            content: &status,
+            position: Point::new(WIDTH - 10.0, HEIGHT - 10.0 - under),
+            size: self.fitted(16.0, &status),
+            color: self.style.theme.faint,
+            horizontal_alignment: HorizontalAlignment::Right,
+            vertical_alignment: VerticalAlignment::Bottom,
+            ..Text::default()
+        });
+        self.font.add(Text {
+            content: instructions,
+            position: Point::new(WIDTH - 10.0, HEIGHT - 10.0),
+            size,
+            color: self.style.theme.faint,
+            horizontal_alignment: HorizontalAlignment::Right,
+            vertical_alignment: VerticalAlignment::Bottom,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Writes the names of a puzzle's own cards on the guesses, and in the
+    // answer boxes what was built there or, once the rules show, the hidden
+    // cards. Names are made smaller to fit across a guess, and pictured
+    // cards are left to the game.
+    fn draw_names(&mut self, model: &VennModel, tokens: &Tokens) {
+        let revealed = model.is_forfeited() || model.shows_rules();
+        let mut names: Vec<(String, Point, f32)> = Vec::new();
+        for guess in model.guesses() {
+            match &guess.name {
+                Some(name) if tokens.image(&guess.target).is_none() => {
+                    names.push((name.clone(), guess.center, guess.radius * 2.0))
+                }
+                _ => {}
+            }
+        }
+        for circle in model.circles().filter(|c| c.compound.is_none()) {
+            let answer = &circle.answer;
+            let card = match answer.built {
+                _ if revealed => Some(answer.target),
+                built => built,
+            };
+            // The game draws a picture in its place.
+            let card = card.filter(|card| tokens.image(card).is_none());
+            if let Some(card) = card {
+                let width = answer.width - 8.0;
+                names.push((card_name(model, &card), answer.center, width));
+            }
+        }
+        for (name, position, width) in &names {
+            let fitting = width / text_width(name, 1.0).max(0.5);
+            self.font.add(Text {
+                content: name,
+                position: *position,
+                bounds: (*width, f32::INFINITY),
+                size: self.size(14.0).min(fitting),
+                horizontal_alignment: HorizontalAlignment::Center,
+                vertical_alignment: VerticalAlignment::Center,
+                color: self.style.theme.text,
+            });
+        }
+    }
+
+    // Lists where every guess ended up over a backdrop, with the time and
+    // score of the round, the solver's par for it if it has one, and what
+    // to do next.
+    pub fn draw_results(&mut self, target: &mut Target<'_>, model: &VennModel, par: Option<u32>) {
+        // The screens over the board keep their usual size.
+        let style = &Style {
+            scale: 1.0,
+            ..self.style
+        };
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            Shape::Rectangle(Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: WIDTH,
+                height: HEIGHT,
+            }),
+            Color {
+                a: 0.9,
+                ..self.style.theme.panel
+            },
+        );
+        let results = model.results();
+        // Two columns once one would run off the bottom.
+        let per_column = 20;
+        let row_height = 22.0;
+        let column_width = WIDTH / 2.0 - 40.0;
+        let top = 90.0;
+        let mut rows = Vec::new();
+        for (i, result) in results.iter().enumerate() {
+            let x = 40.0 + (i / per_column) as f32 * (column_width + 40.0);
+            let y = top + (i % per_column) as f32 * row_height;
+            // A puzzle's own cards go by their names alone.
+            if model.tokens().is_none() {
+                result
+                    .target
+                    .draw(&mut mesh, style, Point::new(x, y + row_height / 2.0));
+            }
+            rows.push((
+                Point::new(x + 25.0, y),
+                [
+                    card_name(model, &result.target),
+                    place_name(result.place).to_string(),
+                    verdict(result.right).to_string(),
+                ],
+                result.right,
+            ));
+        }
+        // The cards and rules built in the answer boxes follow the guesses.
+        for (circle, c) in model.circles().enumerate() {
+            let i = rows.len();
+            let x = 40.0 + (i / per_column) as f32 * (column_width + 40.0);
+            let y = top + (i % per_column) as f32 * row_height;
+            let answer = &c.answer;
+            let built = match (answer.built_kind, &answer.built_rule, answer.built) {
+                (Some(kind), _, _) => fill("results.sorted_by", &[&kind_name(kind).to_lowercase()]),
+                (None, Some(rule), _) => rule_text(rule),
+                (None, None, Some(built)) => {
+                    built.draw(&mut mesh, style, Point::new(x, y + row_height / 2.0));
+                    describe(&built)
+                }
+                (None, None, None) => continue,
+            };
+            let right = c.answer.matches == Some(true);
+            rows.push((
+                Point::new(x + 25.0, y),
+                [
+                    built,
+                    box_name(circle).to_string(),
+                    verdict(right).to_string(),
+                ],
+                right,
+            ));
+        }
+        // Running out of lives is game over, which gives the rules away.
+        if model.is_out_of_lives() {
+            for (circle, c) in model.circles().enumerate() {
+                let i = rows.len();
+                let x = 40.0 + (i / per_column) as f32 * (column_width + 40.0);
+                let y = top + (i % per_column) as f32 * row_height;
+                rows.push((
+                    Point::new(x + 25.0, y),
+                    [
+                        fill(
+                            "results.circle",
+                            &[
+                                &circle_name(circle),
+                                &label(c, model.current_level()).replace('\n', " "),
+                            ],
+                        ),
+                        String::new(),
+                        String::new(),
+                    ],
+                    true,
+                ));
+            }
+        }
+        mesh.draw(target);
+        let heading = fill(
+            "results.heading",
+            &[
+                &if model.is_won() {
+                    text("results.solved")
+                } else if model.is_out_of_lives() {
+                    text("results.game_over")
+                } else {
+                    text("results.round_over")
+                },
+                &clock(model.elapsed()),
+                &model.score().points,
+            ],
+        );
+        self.font.add(Text {
+            content: &heading,
+            position: Point::new(WIDTH / 2.0, 30.0),
+            size: 28.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        if let Some(par) = par.filter(|_| model.is_won()) {
+            self.font.add(Text {
+                content: &fill("results.par", &[&model.placements(), &par]),
+                position: Point::new(WIDTH / 2.0, 62.0),
+                size: 20.0,
+                horizontal_alignment: HorizontalAlignment::Center,
+                color: self.style.theme.faint,
+                ..Text::default()
+            });
+        }
+        // The columns are as wide as their longest entry, which depends on
+        // the language.
+        let widths: Vec<usize> = (0..2)
+            .map(|column| {
+                rows.iter()
+                    .map(|(_, row, _)| row[column].chars().count() + 1)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        for (position, [built, place, verdict], right) in &rows {
+            let content = if place.is_empty() {
+                built.clone()
+            } else {
+                format!(
+                    "{:<a$}{:<b$}{}",
+                    built,
+                    place,
+                    verdict,
+                    a = widths[0],
+                    b = widths[1]
+                )
+            };
+            self.font.add(Text {
+                content: &content,
+                position: *position,
+                size: 12.0,
+                color: if *right {
+                    self.style.theme.text
+                } else {
+                    self.style.theme.alert
+                },
+                ..Text::default()
+            });
+        }
+        self.font.add(Text {
+            content: text("results.keys"),
+            position: Point::new(WIDTH / 2.0, HEIGHT - 20.0),
+            size: 20.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Bottom,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Shows the score and right guesses of both players of a hot-seat game
+    // at the top, each in their color, marking whose turn it is.
+    pub fn draw_players(&mut self, target: &mut Target<'_>, seat: &HotSeat) {
+        for (i, player) in seat.players.iter().enumerate() {
+            let line = format!(
+                "{}{}",
+                if i == seat.turn() { "> " } else { "" },
+                fill(
+                    "players.line",
+                    &[&(i + 1), &player.score.points, &player.right]
+                )
+            );
+            self.font.add(Text {
+                content: &line,
+                position: Point::new(WIDTH / 2.0, 10.0 + i as f32 * 22.0),
+                size: 20.0,
+                color: PLAYERS[i],
+                horizontal_alignment: HorizontalAlignment::Center,
+                ..Text::default()
+            });
+        }
+        self.font.draw(target);
+    }
+
+    // Says the game waits for the window to come back.
+    pub fn draw_away(&mut self, target: &mut Target<'_>) {
+        let backdrop = Rectangle {
+            x: WIDTH / 2.0 - 230.0,
+            y: HEIGHT / 2.0 - 40.0,
+            width: 460.0,
+            height: 80.0,
+        };
+        let mut mesh = Mesh::new();
+        mesh.fill(Shape::Rectangle(backdrop), self.style.theme.panel);
+        mesh.stroke(Shape::Rectangle(backdrop), self.style.theme.stroke, 2);
+        mesh.draw(target);
+        self.font.add(Text {
+            content: text("away"),
+            position: Point::new(WIDTH / 2.0, HEIGHT / 2.0),
+            size: 24.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Marks that the round is being recorded, in the bottom corner.
+    pub fn draw_recording(&mut self, target: &mut Target<'_>) {
+        self.font.add(Text {
+            content: text("recording"),
+            position: Point::new(WIDTH - 10.0, HEIGHT - 30.0),
+            size: 20.0,
+            color: self.style.theme.alert,
+            horizontal_alignment: HorizontalAlignment::Right,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Says which player put more guesses where they belong, over the
+    // results.
+    pub fn draw_winner(&mut self, target: &mut Target<'_>, seat: &HotSeat) {
+        let [one, two] = seat.players;
+        let (content, color) = match seat.winner() {
+            Some(i) => (
+                fill(
+                    "winner.wins",
+                    &[
+                        &(i + 1),
+                        &one.right.max(two.right),
+                        &one.right.min(two.right),
+                    ],
+                ),
+                PLAYERS[i],
+            ),
+            None => (fill("winner.tie", &[&one.right]), self.style.theme.text),
+        };
+        self.font.add(Text {
+            content: &content,
+            position: Point::new(WIDTH / 2.0, HEIGHT - 50.0),
+            size: 24.0,
+            color,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Bottom,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Asks for a name to put a round that made it onto the leaderboard
+    // under.
+    pub fn draw_naming(&mut self, target: &mut Target<'_>, name: &str) {
+        let texts = ["naming.heading", "naming.prompt", "naming.keys"];
+        self.draw_entry(target, texts, name);
+    }
+
+    // Asks for the label of the circle selected in the editor.
+    pub fn draw_labelling(&mut self, target: &mut Target<'_>, label: &str) {
+        let texts = ["labelling.heading", "labelling.prompt", "labelling.keys"];
+        self.draw_entry(target, texts, label);
+    }
+
+    // A heading, what to type, the text typed so far and the keys to press,
+    // given the keys of the heading, the prompt and the keys.
+    fn draw_entry(&mut self, target: &mut Target<'_>, texts: [&'static str; 3], entry: &str) {
+        backdrop(target, &self.style);
+        let [heading, prompt, keys] = texts;
+        let lines = [
+            (text(heading).to_string(), 28.0),
+            (text(prompt).to_string(), 20.0),
+            (format!("{}_", entry), 28.0),
+            (text(keys).to_string(), 20.0),
+        ];
+        for (i, (content, size)) in lines.iter().enumerate() {
+            self.font.add(Text {
+                content,
+                position: Point::new(WIDTH / 2.0, HEIGHT / 2.0 - 90.0 + i as f32 * 50.0),
+                size: *size,
+                horizontal_alignment: HorizontalAlignment::Center,
+                color: self.style.theme.text,
+                ..Text::default()
+            });
+        }
+        self.font.draw(target);
+    }
+
+    // Lists the fastest and the most accurate rounds at a difficulty side by
+    // side.
+    pub fn draw_leaderboard(
+        &mut self,
+        target: &mut Target<'_>,
+        board: &Board,
+        difficulty: Option<Preset>,
+    ) {
+        backdrop(target, &self.style);
+        self.font.add(Text {
+            content: &fill("leaderboard.heading", &[&difficulty_name(difficulty)]),
+            position: Point::new(WIDTH / 2.0, 30.0),
+            size: 28.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        let columns = [
+            (text("leaderboard.fastest"), &board.fastest),
+            (text("leaderboard.accurate"), &board.accurate),
+        ];
+        for (column, (heading, entries)) in columns.iter().enumerate() {
+            let x = 40.0 + column as f32 * (WIDTH / 2.0);
+            self.font.add(Text {
+                content: heading,
+                position: Point::new(x, 90.0),
+                size: 22.0,
+                color: self.style.theme.text,
+                ..Text::default()
+            });
+            for (i, entry) in entries.iter().enumerate() {
+                let seconds = entry.seconds as u32;
+                let line = format!(
+                    "{:>2}. {:<16} {}:{:02} {:>3}%",
+                    i + 1,
+                    entry.name,
+                    seconds / 60,
+                    seconds % 60,
+                    entry.accuracy
+                );
+                self.font.add(Text {
+                    content: &line,
+                    position: Point::new(x, 130.0 + i as f32 * 30.0),
+                    size: 18.0,
+                    color: self.style.theme.text,
+                    ..Text::default()
+                });
+            }
+        }
+        self.font.add(Text {
+            content: text("leaderboard.keys"),
+            position: Point::new(WIDTH / 2.0, HEIGHT - 20.0),
+            size: 20.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Bottom,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Lists the statistics kept across sessions over a backdrop.
+    pub fn draw_stats(&mut self, target: &mut Target<'_>, stats: &Stats) {
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            Shape::Rectangle(Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: WIDTH,
+                height: HEIGHT,
+            }),
+            Color {
+                a: 0.9,
+                ..self.style.theme.panel
+            },
+        );
+        mesh.draw(target);
+        let percent = |tally: &Tally| match tally.percent() {
+            Some(percent) => fill("stats.percent", &[&percent, &tally.total]),
+            None => "-".to_string(),
+        };
+        let average = match stats.average_solve_time() {
+            Some(seconds) => clock(seconds),
+            None => "-".to_string(),
+        };
+        let par = match stats.par.average() {
+            Some(average) => fill(
+                "stats.par_value",
+                &[
+                    &stats.par.made,
+                    &stats.par.rounds,
+                    &format!("{:+.1}", average),
+                ],
+            ),
+            None => "-".to_string(),
+        };
+        let adaptive = &stats.adaptive;
+        let recent = match adaptive.success() {
+            Some(success) => fill(
+                "stats.percent",
+                &[&((success * 100.0).round() as u32), &adaptive.rounds()],
+            ),
+            None => "-".to_string(),
+        };
+        let adapting = fill(
+            "stats.adapting",
+            &[
+                &(adaptive.level() + 1),
+                &text(match adaptive.trend() {
+                    Trend::Settling => "stats.settling",
+                    Trend::Easier => "stats.easier",
+                    Trend::Steady => "stats.steady",
+                    Trend::Harder => "stats.harder",
+                }),
+            ],
+        );
+        let rows = [
+            ("stats.played", stats.played.to_string()),
+            ("stats.solved", stats.solved.to_string()),
+            ("stats.given_up", stats.given_up.to_string()),
+            ("stats.average", average),
+            ("stats.best_streak", stats.best_streak.to_string()),
+            ("stats.placements", percent(&stats.placements)),
+            ("stats.shape", percent(&stats.shape)),
+            ("stats.color", percent(&stats.color)),
+            ("stats.size", percent(&stats.size)),
+            ("stats.pattern", percent(&stats.pattern)),
+            ("stats.count", percent(&stats.count)),
+            ("stats.orientation", percent(&stats.orientation)),
+            ("stats.fill", percent(&stats.fill)),
+            ("stats.par", par),
+            ("stats.recent", recent),
+            ("stats.adaptive", adapting),
+        ];
+        self.font.add(Text {
+            content: text("stats.heading"),
+            position: Point::new(WIDTH / 2.0, 30.0),
+            size: 28.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        for (i, (name, value)) in rows.iter().enumerate() {
+            let line = format!("{:<24}{}", text(name), value);
+            self.font.add(Text {
+                content: &line,
+                position: Point::new(WIDTH / 2.0 - 180.0, 90.0 + i as f32 * 30.0),
+                size: 20.0,
+                color: self.style.theme.text,
+                ..Text::default()
+            });
+        }
+        self.font.add(Text {
+            content: text("stats.keys"),
+            position: Point::new(WIDTH / 2.0, HEIGHT - 20.0),
+            size: 20.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Bottom,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Names the attributes of the guess under the cursor in a box next to
+    // it, unless something is being dragged.
+    // Counts the green guesses in the middle of each region, after the
+    // region's set notation if asked for.
+    pub fn draw_regions(&mut self, target: &mut Target<'_>, model: &VennModel, notation: bool) {
+        let circles = model.circles().count();
+        for (region, count) in model.region_counts() {
+            let center = match model.region_center(region) {
+                Some(center) => center,
+                None => continue,
+            };
+            let content = if notation {
+                fill("regions.notation", &[&region.notation(circles), &count])
+            } else {
+                fill("regions.right", &[&count])
+            };
+            self.font.add(Text {
+                content: &content,
+                position: center,
+                size: self.size(14.0),
+                color: self.style.theme.faint,
+                horizontal_alignment: HorizontalAlignment::Center,
+                vertical_alignment: VerticalAlignment::Center,
+                ..Text::default()
+            });
+        }
+        self.font.draw(target);
+    }
+
+    // Outlines what the pointer can hit and where each region is, and lists
+    // the pointer, the guess being dragged and how fast the game runs.
+    pub fn draw_diagnostics(
+        &mut self,
+        target: &mut Target<'_>,
+        model: &VennModel,
+        diagnostics: &Diagnostics,
+        cursor: Point,
+    ) {
+        let mut mesh = Mesh::new();
+        for circle in model.circles() {
+            mesh.stroke(circle.boundary().shape(), DEBUG, 2);
+            let answer = &circle.answer;
+            mesh.stroke(
+                Shape::Rectangle(Rectangle {
+                    x: answer.center.x - answer.width / 2.0,
+                    y: answer.center.y - answer.height / 2.0,
+                    width: answer.width,
+                    height: answer.height,
+                }),
+                DEBUG,
+                1,
+            );
+        }
+        for guess in model.guesses() {
+            mesh.stroke(
+                Shape::Circle {
+                    center: guess.center,
+                    radius: guess.radius,
+                },
+                DEBUG,
+                1,
+            );
+        }
+        let circles = model.circles().count();
+        for region in generator::regions(circles, model.options().outside) {
+            let center = match model.region_center(region) {
+                Some(center) => center,
+                None => continue,
+            };
+            for (from, to) in [((-5.0, 0.0), (5.0, 0.0)), ((0.0, -5.0), (0.0, 5.0))] {
+                mesh.stroke(
+                    Shape::Polyline {
+                        points: vec![
+                            Point::new(center.x + from.0, center.y + from.1),
+                            Point::new(center.x + to.0, center.y + to.1),
+                        ],
+                    },
+                    DEBUG,
+                    1,
+                );
+            }
+            self.font.add(Text {
+                content: &region.notation(circles),
+                position: Point::new(center.x, center.y - 8.0),
+                size: 14.0,
+                color: DEBUG,
+                horizontal_alignment: HorizontalAlignment::Center,
+                vertical_alignment: VerticalAlignment::Bottom,
+                ..Text::default()
+            });
+        }
+        mesh.draw(target);
+        let lines = [
+            format!("Cursor {:.0}, {:.0}", cursor.x, cursor.y),
+            format!("Region {}", model.region(&cursor).notation(circles)),
+            match model.dragging() {
+                Some(index) => format!("Dragging {}", index),
+                None => "Dragging none".to_string(),
+            },
+            format!("Ticks {}/s", diagnostics.tick_rate()),
+            format!(
+                "Meshes {:.2} ms",
+                diagnostics.build_time().as_secs_f32() * 1000.0
+            ),
+        ];
+        self.font.add(Text {
+            content: &lines.join("\n"),
+            position: Point::new(110.0, 10.0),
+            size: 16.0,
+            color: DEBUG,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Names the overlap of two circles while the point is in it, just below
+    // where their edges cross at the top.
+    // Marks the rail as where a guess held in the sandbox is taken away,
+    // darker once it is over it.
+    pub fn draw_trash(&mut self, target: &mut Target<'_>, model: &VennModel, cursor: Point) {
+        let strip = Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: model.rail_right(),
+            height: HEIGHT,
+        };
+        let over = cursor.x < strip.width;
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            Shape::Rectangle(strip),
+            Color {
+                a: if over { 0.35 } else { 0.15 },
+                ..self.style.theme.alert
+            },
+        );
+        mesh.stroke(
+            Shape::Rectangle(strip),
+            self.style.theme.alert,
+            self.style.width(2),
+        );
+        mesh.draw(target);
+    }
+
+    pub fn draw_overlap(&mut self, target: &mut Target<'_>, model: &VennModel, point: Point) {
+        if model.region(&point) != Region::Both {
+            return;
+        }
+        let circles: Vec<&VennSet> = model.circles().collect();
+        let (top, _) = match circles[..] {
+            [left, right] => match left.crossings(right) {
+                Some(crossings) => crossings,
+                None => return,
+            },
+            _ => return,
+        };
+        self.font.add(Text {
+            content: text("overlap"),
+            position: Point::new(top.x, top.y + self.size(20.0)),
+            size: self.size(16.0),
+            horizontal_alignment: HorizontalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    pub fn draw_tooltip(&mut self, target: &mut Target<'_>, model: &VennModel, cursor: Point) {
+        if model.dragging().is_some() {
+            return;
+        }
+        // Over an answer box, a compound rule that shows is put in words, as
+        // the box is too small for more than a few.
+        let shown = model.is_finished() || model.shows_rules();
+        let content = match model.guess_at(&cursor) {
+            Some(index) => card_name(model, &model.guesses()[index].target),
+            None => match model.circles().find(|c| c.answer.contains(&cursor)) {
+                Some(VennSet {
+                    compound: Some(rule),
+                    ..
+                }) if shown => rule_text(rule),
+                _ => return,
+            },
+        };
+        let size = self.size(18.0);
+        let width = text_width(&content, size) + 12.0;
+        let height = size + 8.0;
+        let x = (cursor.x + 14.0).min(WIDTH - width);
+        let y = (cursor.y + 18.0).min(HEIGHT - height);
+        let backdrop = Rectangle {
+            x,
+            y,
+            width,
+            height,
+        };
+        let mut mesh = Mesh::new();
+        mesh.fill(Shape::Rectangle(backdrop), self.style.theme.note);
+        mesh.stroke(
+            Shape::Rectangle(backdrop),
+            self.style.theme.stroke,
+            self.style.width(1),
+        );
+        mesh.draw(target);
+        self.font.add(Text {
+            content: &content,
+            position: Point::new(x + 6.0, y + 4.0),
+            size,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Lists what the selected guess is and which circles it fits as far as
+    // the guesses placed so far tell, in a panel on the right.
+    pub fn draw_inspection(&mut self, target: &mut Target<'_>, model: &VennModel, guess: usize) {
+        let card = &model.guesses()[guess].target;
+        let mut rows = attributes(card, model.tokens());
+        rows.push(("", ""));
+        let solver = model.solver();
+        for circle in 0..model.circles().count() {
+            let fits = match solver.fits(circle, card) {
+                Some(true) => text("inspect.fits"),
+                Some(false) => text("inspect.doesnt_fit"),
+                None => text("inspect.cant_tell"),
+            };
+            rows.push((circle_name(circle), fits));
+        }
+        let width = rows.iter().map(|(name, _)| name.chars().count()).max();
+        let lines: Vec<String> = rows
+            .iter()
+            .map(|(name, value)| format!("{:<w$} {}", name, value, w = width.unwrap_or(0)))
+            .collect();
+        let content = lines.join("\n");
+        // Below the lines of the score, which grow with the text.
+        let size = self.size(16.0);
+        let width = self.size(190.0);
+        let panel = Rectangle {
+            x: WIDTH - 10.0 - width,
+            y: 10.0 + self.size(160.0),
+            width,
+            height: (lines.len() as f32 + 1.0) * size,
+        };
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            Shape::Rectangle(panel),
+            Color {
+                a: 0.9,
+                ..self.style.theme.note
+            },
+        );
+        mesh.stroke(
+            Shape::Rectangle(panel),
+            self.style.theme.stroke,
+            self.style.width(1),
+        );
+        mesh.draw(target);
+        self.font.add(Text {
+            content: &content,
+            position: Point::new(panel.x + size / 2.0, panel.y + size / 2.0),
+            size,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    /// Looks back at the round that just ended: why the guess, if one was
+    /// clicked, is right or wrong where it is, going by the rule of every
+    /// circle, whose text shows in green where the guess fits it.
+    pub fn draw_explanation(
+        &mut self,
+        target: &mut Target<'_>,
+        model: &VennModel,
+        guess: Option<usize>,
+    ) {
+        self.font.add(Text {
+            content: text("explain.prompt"),
+            position: Point::new(WIDTH / 2.0, HEIGHT - 20.0),
+            size: 20.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Bottom,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        let (guess, right) = match guess {
+            Some(index) => (&model.guesses()[index], model.is_in_place(index)),
+            None => {
+                self.font.draw(target);
+                return;
+            }
+        };
+        let theme = self.style.theme;
+        let card = &guess.target;
+        let attributes = attributes(card, model.tokens());
+        let width = attributes
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max();
+        let mut lines: Vec<(String, Color)> = vec![(card_name(model, card), theme.text)];
+        for (name, value) in attributes {
+            let line = format!("{:<w$} {}", name, value, w = width.unwrap_or(0));
+            lines.push((line, theme.text));
+        }
+        lines.push((String::new(), theme.text));
+        let level = model.current_level();
+        for (circle, c) in model.circles().enumerate() {
+            let rule = label(c, level).replace('\n', " ");
+            let (fits, inside) = (c.matches(card, level), c.contains(&guess.center));
+            let rule = fill("results.circle", &[&circle_name(circle), &rule]);
+            lines.push((rule, if fits { theme.right } else { theme.faint }));
+            let why = text(match (fits, inside) {
+                (true, true) => "explain.fits_inside",
+                (true, false) => "explain.fits_outside",
+                (false, true) => "explain.misfit_inside",
+                (false, false) => "explain.misfit_outside",
+            });
+            let color = if fits == inside {
+                theme.right
+            } else {
+                theme.wrong
+            };
+            lines.push((format!("  {}", why), color));
+        }
+        // A guess that belongs nowhere is right left alone, unless the
+        // outside is part of the game.
+        let belongs = match model.belongs(card) {
+            Region::Outside if !model.options().outside => Place::Rail,
+            region => Place::Region(region),
+        };
+        let place = if guess.center == guess.home {
+            Place::Rail
+        } else {
+            Place::Region(model.region(&guess.center))
+        };
+        lines.push((String::new(), theme.text));
+        lines.push((
+            fill("explain.place", &[&place_name(place), &place_name(belongs)]),
+            if right { theme.right } else { theme.wrong },
+        ));
+        let size = self.size(16.0);
+        let width = lines.iter().map(|(line, _)| text_width(line, size));
+        let width = width.fold(0.0, f32::max) + size;
+        let panel = Rectangle {
+            x: WIDTH - 10.0 - width,
+            y: 10.0 + self.size(160.0),
+            width,
+            height: (lines.len() as f32 + 1.0) * size,
+        };
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            Shape::Rectangle(panel),
+            Color {
+                a: 0.9,
+                ..theme.note
+            },
+        );
+        mesh.stroke(Shape::Rectangle(panel), theme.stroke, self.style.width(1));
+        mesh.draw(target);
+        for (i, (line, color)) in lines.iter().enumerate() {
+            self.font.add(Text {
+                content: line,
+                position: Point::new(panel.x + size / 2.0, panel.y + size / 2.0 + i as f32 * size),
+                size,
+                color: *color,
+                ..Text::default()
+            });
+        }
+        self.font.draw(target);
+    }
+
+    // Says what to do next in the tutorial, at the top of the window.
+    pub fn draw_tutorial(
+        &mut self,
+        target: &mut Target<'_>,
+        tutorial: &Tutorial,
+        model: &VennModel,
+    ) {
+        let content = match tutorial.step() {
+            Step::PickUp => text("tutorial.pick_up"),
+            Step::Drop { .. } => text("tutorial.drop"),
+            Step::Feedback { guess } => match model.guesses()[guess].matches {
+                Some(true) => text("tutorial.green"),
+                _ => text("tutorial.red"),
+            },
+            Step::Answer => text("tutorial.answer"),
+            Step::Done => text("tutorial.done"),
+        };
+        let mut mesh = Mesh::new();
+        let width = self.size(540.0).min(WIDTH - 20.0);
+        let backdrop = Rectangle {
+            x: WIDTH / 2.0 - width / 2.0,
+            y: 5.0,
+            width,
+            height: self.size(60.0),
+        };
+        mesh.fill(Shape::Rectangle(backdrop), self.style.theme.panel);
+        mesh.stroke(
+            Shape::Rectangle(backdrop),
+            self.style.theme.stroke,
+            self.style.width(2),
+        );
+        mesh.draw(target);
+        self.font.add(Text {
+            content,
+            position: Point::new(WIDTH / 2.0, backdrop.y + backdrop.height / 2.0),
+            bounds: (width - 20.0, backdrop.height),
+            size: self.size(20.0),
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Center,
+            color: self.style.theme.text,
+        });
+        self.font.draw(target);
+    }
+
+    // Draws the builder for an answer box over the board: the chips of every
+    // row, the card or rule they make and the buttons to submit or cancel.
+    pub fn draw_builder(&mut self, target: &mut Target<'_>, builder: &Builder, model: &VennModel) {
+        let style = &Style {
+            scale: 1.0,
+            ..self.style
+        };
+        let rows = builder::rows(model, builder.circle);
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            Shape::Rectangle(Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: WIDTH,
+                height: HEIGHT,
+            }),
+            Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.5,
+            },
+        );
+        let panel = Rectangle {
+            x: 40.0,
+            y: 60.0,
+            width: WIDTH - 80.0,
+            height: HEIGHT - 120.0,
+        };
+        mesh.fill(Shape::Rectangle(panel), self.style.theme.panel);
+        mesh.stroke(Shape::Rectangle(panel), self.style.theme.stroke, 2);
+        self.font.add(Text {
+            content: &if builder.is_naming() {
+                text("builder.naming").to_string()
+            } else if builder.is_compound() {
+                fill("builder.rule", &[&box_name(builder.circle)])
+            } else {
+                fill("builder.card", &[&box_name(builder.circle)])
+            },
+            position: Point::new(WIDTH / 2.0, 90.0),
+            size: 24.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        for (r, row) in rows.iter().enumerate() {
+            let first = chip(r, 0);
+            if r == builder.row() {
+                mesh.fill(
+                    Shape::Rectangle(Rectangle {
+                        x: panel.x + 10.0,
+                        y: first.y - 6.0,
+                        width: panel.width - 20.0,
+                        height: first.height + 12.0,
+                    }),
+                    Color {
+                        a: 0.5,
+                        ..self.style.theme.selection
+                    },
+                );
+            }
+            self.font.add(Text {
+                content: row_name(row),
+                position: Point::new(panel.x + 20.0, first.y + first.height / 2.0),
+                size: 18.0,
+                vertical_alignment: VerticalAlignment::Center,
+                color: self.style.theme.text,
+                ..Text::default()
+            });
+            for (c, &value) in row.iter().enumerate() {
+                let button = chip(r, c);
+                let on = builder.is_on(r, value);
+                mesh.fill(
+                    Shape::Rectangle(button),
+                    if on {
+                        self.style.theme.selection
+                    } else {
+                        self.style.theme.panel
+                    },
+                );
+                mesh.stroke(
+                    Shape::Rectangle(button),
+                    self.style.theme.stroke,
+                    if on { 3 } else { 1 },
+                );
+                self.font.add(Text {
+                    content: chip_name(value),
+                    position: Point::new(
+                        button.x + button.width / 2.0,
+                        button.y + button.height / 2.0,
+                    ),
+                    size: 16.0,
+                    horizontal_alignment: HorizontalAlignment::Center,
+                    vertical_alignment: VerticalAlignment::Center,
+                    color: self.style.theme.text,
+                    ..Text::default()
+                });
+            }
+        }
+        let built = if builder.is_naming() {
+            kind_name(builder.kind).to_lowercase()
+        } else if builder.is_compound() {
+            match builder.rule(&rows) {
+                Some(rule) => rule_text(&rule),
+                None => text("builder.pick").to_string(),
+            }
+        } else {
+            builder
+                .card
+                .draw(&mut mesh, style, Point::new(WIDTH / 2.0 - 120.0, 430.0));
+            describe(&builder.card)
+        };
+        self.font.add(Text {
+            content: &built,
+            position: Point::new(WIDTH / 2.0 - 90.0, 430.0),
+            size: 20.0,
+            vertical_alignment: VerticalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        for (i, &key) in ["builder.submit", "builder.cancel"].iter().enumerate() {
+            let button = builder_button(i);
+            mesh.fill(Shape::Rectangle(button), self.style.theme.panel);
+            mesh.stroke(Shape::Rectangle(button), self.style.theme.stroke, 2);
+            self.font.add(Text {
+                content: text(key),
+                position: Point::new(
+                    button.x + button.width / 2.0,
+                    button.y + button.height / 2.0,
+                ),
+                size: 20.0,
+                horizontal_alignment: HorizontalAlignment::Center,
+                vertical_alignment: VerticalAlignment::Center,
+                color: self.style.theme.text,
+                ..Text::default()
+            });
+        }
+        mesh.draw(target);
+        self.font.draw(target);
+    }
+
+    // Draws the notebook over the board: a row for every value the level
+    // cares about and a column of marks for every circle.
+    // Lays every card of the level out over the board, to pick the ones to
+    // add to the sandbox, marking the one under the cursor.
+    pub fn draw_palette(&mut self, target: &mut Target<'_>, model: &VennModel, cursor: Point) {
+        // The screens over the board keep their usual size.
+        let style = &Style {
+            scale: 1.0,
+            ..self.style
+        };
+        backdrop(target, style);
+        let palette = model.palette();
+        let mut mesh = Mesh::new();
+        for (index, card) in palette.iter().enumerate() {
+            let slot = palette_slot(index, palette.len());
+            let center = Point::new(slot.x + slot.width / 2.0, slot.y + slot.height / 2.0);
+            if inside(&cursor, &slot) {
+                mesh.fill(Shape::Rectangle(slot), self.style.theme.selection);
+            }
+            mesh.stroke(Shape::Rectangle(slot), self.style.theme.stroke, 1);
+            card.draw(&mut mesh, style, center);
+        }
+        mesh.draw(target);
+        self.font.add(Text {
+            content: text("palette.heading"),
+            position: Point::new(WIDTH / 2.0, 30.0),
+            size: 28.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.add(Text {
+            content: text("palette.keys"),
+            position: Point::new(WIDTH / 2.0, HEIGHT - 20.0),
+            size: 20.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Bottom,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    pub fn draw_notebook(
+        &mut self,
+        target: &mut Target<'_>,
+        model: &VennModel,
+        notebook: &Notebook,
+    ) {
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            Shape::Rectangle(Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: WIDTH,
+                height: HEIGHT,
+            }),
+            Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.5,
+            },
+        );
+        let panel = Rectangle {
+            x: 40.0,
+            y: 60.0,
+            width: WIDTH - 80.0,
+            height: HEIGHT - 120.0,
+        };
+        mesh.fill(Shape::Rectangle(panel), self.style.theme.panel);
+        mesh.stroke(Shape::Rectangle(panel), self.style.theme.stroke, 2);
+        self.font.add(Text {
+            content: text("notes.heading"),
+            position: Point::new(WIDTH / 2.0, 80.0),
+            size: 24.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        let circles = model.circles().count();
+        for circle in 0..circles {
+            let top = note(0, 0, circle);
+            self.font.add(Text {
+                content: circle_name(circle),
+                position: Point::new(top.x + top.width / 2.0, top.y - 12.0),
+                size: 16.0,
+                horizontal_alignment: HorizontalAlignment::Center,
+                vertical_alignment: VerticalAlignment::Center,
+                color: self.style.theme.text,
+                ..Text::default()
+            });
+        }
+        for (row, (group, value)) in notebook::rows(model).into_iter().enumerate() {
+            let first = note(row, group, 0);
+            self.font.add(Text {
+                content: value_name(value),
+                position: Point::new(panel.x + 20.0, first.y + first.height / 2.0),
+                size: 14.0,
+                vertical_alignment: VerticalAlignment::Center,
+                color: self.style.theme.text,
+                ..Text::default()
+            });
+            for circle in 0..circles {
+                let chip = note(row, group, circle);
+                let (fill, content) = match notebook.mark(circle, value) {
+                    Mark::Unknown => (self.style.theme.panel, ""),
+                    Mark::Eliminated => (
+                        Color {
+                            a: 0.3,
+                            ..self.style.theme.wrong
+                        },
+                        text("notes.no"),
+                    ),
+                    Mark::Confirmed => (
+                        Color {
+                            a: 0.3,
+                            ..self.style.theme.right
+                        },
+                        text("notes.yes"),
+                    ),
+                };
+                mesh.fill(Shape::Rectangle(chip), fill);
+                mesh.stroke(Shape::Rectangle(chip), self.style.theme.stroke, 1);
+                self.font.add(Text {
+                    content,
+                    position: Point::new(chip.x + chip.width / 2.0, chip.y + chip.height / 2.0),
+                    size: 14.0,
+                    horizontal_alignment: HorizontalAlignment::Center,
+                    vertical_alignment: VerticalAlignment::Center,
+                    color: self.style.theme.text,
+                    ..Text::default()
+                });
+            }
+        }
+        self.font.add(Text {
+            content: if notebook.is_assisting() {
+                text("notes.keys_on")
+            } else {
+                text("notes.keys_off")
+            },
+            position: Point::new(WIDTH / 2.0, panel.y + panel.height - 16.0),
+            size: 16.0,
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Center,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        mesh.draw(target);
+        self.font.draw(target);
+    }
+
+    // Shows the puzzle being edited: every circle's rule, which circle is
+    // selected or being dragged, and which guesses are left out.
+    pub fn draw_editor(
+        &mut self,
+        target: &mut Target<'_>,
+        model: &VennModel,
+        editor: &Editor,
+        pointer: Point,
+    ) {
+        let mut mesh = Mesh::new();
+        if let Some(circle) = model.circles().nth(editor.selected) {
+            let boundary = circle.boundary();
+            mesh.stroke(boundary.grown(4.0).shape(), self.style.theme.stroke, 3);
+            if let Some(center) = editor.dragged(&pointer) {
+                let dragged = Boundary { center, ..boundary };
+                mesh.stroke(dragged.shape(), self.style.theme.stroke, 1);
+            }
+        }
+        for guess in model.guesses() {
+            if editor.is_chosen(&guess.target) {
+                continue;
+            }
+            let r = guess.radius;
+            let (x, y) = (guess.center.x, guess.center.y);
+            for (from, to) in [((-r, -r), (r, r)), ((-r, r), (r, -r))] {
+                mesh.stroke(
+                    Shape::Polyline {
+                        points: vec![
+                            Point::new(x + from.0, y + from.1),
+                            Point::new(x + to.0, y + to.1),
+                        ],
+                    },
+                    self.style.theme.alert,
+                    3,
+                );
+            }
+        }
+        mesh.draw(target);
+        let level = model.current_level();
+        for circle in model.circles() {
+            let (position, above) = label_at(circle);
+            self.font.add(Text {
+                content: &caption(circle, level, true, above),
+                position,
+                size: 20.0,
+                horizontal_alignment: HorizontalAlignment::Center,
+                vertical_alignment: if above {
+                    VerticalAlignment::Top
+                } else {
+                    VerticalAlignment::Bottom
+                },
+                color: self.style.theme.text,
+                ..Text::default()
+            });
+        }
+        self.font.add(Text {
+            content: &fill(
+                "editor.heading",
+                &[&editor.path().display(), &(model.level() + 1)],
+            ),
+            position: Point::new(WIDTH - 10.0, 10.0),
+            size: 20.0,
+            horizontal_alignment: HorizontalAlignment::Right,
+            color: self.style.theme.text,
+            ..Text::default()
+        });
+        self.font.add(Text {
+            content: text("editor.instructions"),
+            position: Point::new(WIDTH - 10.0, HEIGHT - 10.0),
+            size: 16.0,
+            color: self.style.theme.faint,
+            horizontal_alignment: HorizontalAlignment::Right,
+            vertical_alignment: VerticalAlignment::Bottom,
+            ..Text::default()
+        });
+        self.font.draw(target);
+    }
+
+    // Draws the entries of a menu as buttons in the middle of the window.
+    pub fn draw_menu(&mut self, target: &mut Target<'_>, entries: &[&str], selected: usize) {
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            Shape::Rectangle(Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: WIDTH,
+                height: HEIGHT,
+            }),
+            Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.5,
+            },
+        );
+        for (i, entry) in entries.iter().enumerate() {
+            let button = menu_entry(i, entries.len());
+            mesh.fill(
+                Shape::Rectangle(button),
+                if i == selected {
+                    self.style.theme.selection
+                } else {
+                    self.style.theme.panel
+                },
+            );
+            mesh.stroke(Shape::Rectangle(button), self.style.theme.stroke, 2);
+            self.font.add(Text {
+                content: entry,
+                position: Point::new(
+                    button.x + button.width / 2.0,
+                    button.y + button.height / 2.0,
+                ),
+                size: if menu_columns(entries.len()) == 1 {
+                    24.0
+                } else {
+                    20.0
+                },
+                horizontal_alignment: HorizontalAlignment::Center,
+                vertical_alignment: VerticalAlignment::Center,
+                color: self.style.theme.text,
+                ..Text::default()
+            });
+        }
+        mesh.draw(target);
+        self.font.draw(target);
+    }
+}
+
+// Covers the board in the almost opaque color of the panels, for a screen
+// of text over it.
+fn backdrop(target: &mut Target<'_>, style: &Style) {
+    let mut mesh = Mesh::new();
+    mesh.fill(
+        Shape::Rectangle(Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: WIDTH,
+            height: HEIGHT,
+        }),
+        Color {
+            a: 0.9,
+            ..style.theme.panel
+        },
+    );
+    mesh.draw(target);
+}
+
+// The button of a menu entry, stacked around the middle of the window.
+// Long menus get flatter buttons so they fit, in two columns once one would
+// be too flat to read.
+fn menu_entry(index: usize, count: usize) -> Rectangle<f32> {
+    let columns = menu_columns(count);
+    let rows = count.div_ceil(columns);
+    let width = if columns == 1 { 420.0 } else { 360.0 };
+    let spacing = 20.0;
+    let height = f32::min(50.0, (HEIGHT - 40.0) / rows as f32 - spacing);
+    let top = HEIGHT / 2.0 - (rows as f32 * (height + spacing) - spacing) / 2.0;
+    let left = WIDTH / 2.0 - (columns as f32 * (width + spacing) - spacing) / 2.0;
+    Rectangle {
+        x: left + (index / rows) as f32 * (width + spacing),
+        y: top + (index % rows) as f32 * (height + spacing),
+        width,
+        height,
+    }
+}
+
+fn menu_columns(count: usize) -> usize {
+    if count > ONE_COLUMN {
+        2
+    } else {
+        1
+    }
+}
+
+/// The menu entry under the point, if any.
+pub fn menu_entry_at(point: &Point, count: usize) -> Option<usize> {
+    (0..count).find(|&i| inside(point, &menu_entry(i, count)))
+}
+
+// A chip of the card builder, in rows of up to seven.
+fn chip(row: usize, column: usize) -> Rectangle<f32> {
+    Rectangle {
+        x: 150.0 + column as f32 * 86.0,
+        y: 130.0 + row as f32 * 50.0,
+        width: 80.0,
+        height: 32.0,
+    }
+}
+
+/// The chip of the card builder under the point, as its row and column.
+pub fn chip_at(point: &Point, rows: &[Vec<Chip>]) -> Option<(usize, usize)> {
+    rows.iter().enumerate().find_map(|(r, row)| {
+        (0..row.len())
+            .find(|&c| inside(point, &chip(r, c)))
+            .map(|c| (r, c))
+    })
+}
+
+// The box of a card of the palette, in rows across the middle of the
+// window.
+fn palette_slot(index: usize, count: usize) -> Rectangle<f32> {
+    const COLUMNS: usize = 9;
+    const SIZE: f32 = 64.0;
+    const GAP: f32 = 10.0;
+    let rows = count.div_ceil(COLUMNS) as f32;
+    let top = (HEIGHT - rows * (SIZE + GAP)) / 2.0;
+    let left = (WIDTH - COLUMNS as f32 * (SIZE + GAP)) / 2.0;
+    Rectangle {
+        x: left + (index % COLUMNS) as f32 * (SIZE + GAP),
+        y: top + (index / COLUMNS) as f32 * (SIZE + GAP),
+        width: SIZE,
+        height: SIZE,
+    }
+}
+
+/// The card of the palette under the point, by its index.
+pub fn palette_at(point: &Point, count: usize) -> Option<usize> {
+    (0..count).find(|&index| inside(point, &palette_slot(index, count)))
+}
+
+// The mark of a circle in a row of the notebook, with a gap between the
+// values of one attribute and the next.
+fn note(row: usize, group: usize, circle: usize) -> Rectangle<f32> {
+    Rectangle {
+        x: 220.0 + circle as f32 * 130.0,
+        y: 120.0 + row as f32 * 19.0 + group as f32 * 8.0,
+        width: 100.0,
+        height: 16.0,
+    }
+}
+
+/// The mark of the notebook under the point, as its circle and value.
+pub fn note_at(point: &Point, model: &VennModel) -> Option<(usize, Value)> {
+    let circles = model.circles().count();
+    notebook::rows(model)
+        .into_iter()
+        .enumerate()
+        .find_map(|(row, (group, value))| {
+            (0..circles)
+                .find(|&circle| inside(point, &note(row, group, circle)))
+                .map(|circle| (circle, value))
+        })
+}
+
+// Submitting the card built, then leaving the builder without it.
+fn builder_button(index: usize) -> Rectangle<f32> {
+    Rectangle {
+        x: WIDTH / 2.0 - 170.0 + index as f32 * 180.0,
+        y: 470.0,
+        width: 160.0,
+        height: 40.0,
+    }
+}
+
+/// Whether the point is on the submit button of the card builder, or on
+/// the cancel button.
+pub fn builder_button_at(point: &Point) -> Option<bool> {
+    (0..2)
+        .find(|&i| inside(point, &builder_button(i)))
+        .map(|i| i == 0)
+}
+
+// How wide the text is written at the size, by its longest line.
+// Inconsolata is monospaced, half as wide as it is high.
+fn text_width(text: &str, size: f32) -> f32 {
+    let longest = text.lines().map(|line| line.chars().count()).max();
+    longest.unwrap_or(0) as f32 * size / 2.0
+}
+
+fn inside(point: &Point, rectangle: &Rectangle<f32>) -> bool {
+    point.x >= rectangle.x
+        && point.x <= rectangle.x + rectangle.width
+        && point.y >= rectangle.y
+        && point.y <= rectangle.y + rectangle.height
+}
+
+/// The entries of the pack screen: each pack with how many of its puzzles
+/// have been solved.
+pub fn pack_entries(packs: &[Pack], progress: &Progress) -> Vec<String> {
+    if packs.is_empty() {
+        return vec![text("packs.none").to_string()];
+    }
+    packs
+        .iter()
+        .map(|pack| {
+            format!(
+                "{} ({}/{})",
+                pack.name,
+                pack.solved(progress),
+                pack.entries.len()
+            )
+        })
+        .collect()
+}
+
+/// The entries of the settings screen: each setting with its value, then
+/// saving and leaving without saving.
+pub fn settings_entries(config: &Config) -> Vec<String> {
+    let on = |value: bool| text(if value { "settings.on" } else { "settings.off" });
+    let difficulty = difficulty_name(config.difficulty);
+    vec![
+        fill(
+            "settings.palette",
+            &[&text(match config.palette {
+                Palette::Standard => "settings.standard",
+                Palette::Colorblind => "settings.colorblind",
+            })],
+        ),
+        fill("settings.patterns", &[&on(config.patterns)]),
+        fill("settings.volume", &[&config.volume]),
+        fill("settings.difficulty", &[&difficulty]),
+        fill("settings.right_click_home", &[&on(config.right_click_home)]),
+        fill("settings.click_to_carry", &[&on(config.click_to_carry)]),
+        fill(
+            "settings.window_size",
+            &[&(config.window_scale() * 100.0).round()],
+        ),
+        fill("settings.tick_rate", &[&config.tick_rate()]),
+        match config.frame_cap {
+            Some(cap) => fill("settings.frame_cap", &[&cap]),
+            None => fill("settings.frame_cap", &[&on(false)]),
+        },
+        fill("settings.language", &[&config.language.name()]),
+        fill("settings.speech", &[&on(config.speech)]),
+        fill(
+            "settings.theme",
+            &[&text(match config.theme {
+                ThemeName::Light => "theme.light",
+                ThemeName::Dark => "theme.dark",
+                ThemeName::HighContrast => "theme.high_contrast",
+            })],
+        ),
+        fill("settings.ui_scale", &[&(config.ui_scale() * 100.0).round()]),
+        fill("settings.drag_button", &[&button_name(config.buttons.drag)]),
+        fill("settings.home_button", &[&button_name(config.buttons.home)]),
+        fill("settings.hint_button", &[&button_name(config.buttons.hint)]),
+        text("settings.save").to_string(),
+        text("settings.cancel").to_string(),
+    ]
+}
+
+fn button_name(button: Button) -> &'static str {
+    text(match button {
+        Button::Left => "button.left",
+        Button::Right => "button.right",
+        Button::Middle => "button.middle",
+    })
+}
+
+// What a difficulty is called, the levels if there is no preset.
+fn difficulty_name(difficulty: Option<Preset>) -> &'static str {
+    text(match difficulty {
+        None => "difficulty.levels",
+        Some(Preset::Easy) => "difficulty.easy",
+        Some(Preset::Medium) => "difficulty.medium",
+        Some(Preset::Hard) => "difficulty.hard",
+    })
+}
+
+fn verdict(right: bool) -> &'static str {
+    text(if right {
+        "results.right"
+    } else {
+        "results.wrong"
+    })
+}
+
+// Minutes and seconds, like 2:05.
+fn clock(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// What is read out for a guess let go of: what it is, where it went and,
+/// if it got a result there, whether it was right.
+pub fn spoken_drop(card: &str, place: Place, matches: Option<bool>) -> String {
+    let place = place_name(place);
+    match matches {
+        Some(right) => fill("speech.judged", &[&card, &place, &verdict(right)]),
+        None => fill("speech.dropped", &[&card, &place]),
+    }
+}
+
+/// What the card is called: the name of the token it stands for in a
+/// puzzle with its own cards, or else what it looks like.
+pub(crate) fn card_name(model: &VennModel, target: &VennTarget) -> String {
+    match model.tokens().and_then(|tokens| tokens.named(target)) {
+        Some(name) => name.to_string(),
+        None => describe(target),
+    }
+}
+
+/// Names the attributes and their values after the columns of a puzzle's
+/// own cards, or after the language again without any.
+pub(crate) fn speak(tokens: Option<&Tokens>) {
+    let mut words = Vec::new();
+    if let Some(tokens) = tokens {
+        words.push(("rule.shapes", fill("rule.plural", &[&tokens.name])));
+    }
+    for column in tokens.iter().flat_map(|tokens| &tokens.columns) {
+        words.push((kind_key(column.kind), column.name.clone()));
+        let values = column.kind.values().into_iter().zip(&column.values);
+        words.extend(values.map(|(value, word)| (value_key(value), word.clone())));
+    }
+    locale::rename(&words);
+}
+
+// Every attribute of the card with its name, like ("Color", "blue"), or
+// those of the columns of a puzzle's own cards.
+fn attributes(card: &VennTarget, tokens: Option<&Tokens>) -> Vec<(&'static str, &'static str)> {
+    if let Some(tokens) = tokens {
+        let columns = tokens.columns.iter();
+        return columns
+            .map(|column| (kind_name(column.kind), value_name(column.kind.of(card))))
+            .collect();
+    }
+    vec![
+        (kind_name(Kind::Color), color_name(card.color)),
+        (kind_name(Kind::Shape), shape_name(card.shape)),
+        (kind_name(Kind::Size), size_name(card.size)),
+        (kind_name(Kind::Pattern), pattern_name(card.pattern)),
+        (kind_name(Kind::Count), count_name(card.count)),
+        (
+            kind_name(Kind::Orientation),
+            orientation_name(card.orientation),
+        ),
+        (kind_name(Kind::Fill), fill_name(card.fill)),
+    ]
+}
+
+// Names the attribute of a row of the builder by its values.
+fn row_name(row: &[Chip]) -> &'static str {
+    match row.first() {
+        Some(Chip::Kind(_)) => text("builder.sorted_by"),
+        Some(Chip::And) => text("builder.join"),
+        _ => row
+            .iter()
+            .find_map(|&chip| match chip {
+                Chip::Value(value) => Some(kind_name(value.kind())),
+                _ => None,
+            })
+            .unwrap_or_else(|| text("builder.join")),
+    }
+}
+
+fn chip_name(chip: Chip) -> &'static str {
+    match chip {
+        Chip::Value(value) => value_name(value),
+        Chip::Kind(kind) => kind_name(kind),
+        Chip::Skip => "-",
+        Chip::And => text("chip.and"),
+        Chip::Or => text("chip.or"),
+        Chip::Except => text("chip.except"),
+    }
+}
+
+fn circle_name(circle: usize) -> &'static str {
+    text(match circle {
+        0 => "circle.left",
+        1 => "circle.right",
+        _ => "circle.bottom",
+    })
+}
+
+fn box_name(circle: usize) -> &'static str {
+    text(match circle {
+        0 => "box.left",
+        1 => "box.right",
+        _ => "box.bottom",
+    })
+}
+
+fn place_name(place: Place) -> &'static str {
+    text(match place {
+        Place::Rail => "place.rail",
+        Place::Region(Region::LeftOnly) => "place.left",
+        Place::Region(Region::RightOnly) => "place.right",
+        Place::Region(Region::Both) => "place.both",
+        Place::Region(Region::BottomOnly) => "place.bottom",
+        Place::Region(Region::LeftBottom) => "place.left_bottom",
+        Place::Region(Region::RightBottom) => "place.right_bottom",
+        Place::Region(Region::All) => "place.all",
+        Place::Region(Region::Outside) => "place.outside",
+    })
+}
@@ -0,0 +1,58 @@
+//! Noticing when the files the game was started with change on disk, so a
+//! puzzle being written by hand can be played again as soon as it is saved.
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+};
+
+/// Watches files for changes.
+pub struct Watcher {
+    // Kept so the watching goes on.
+    watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+    files: Vec<PathBuf>,
+}
+
+impl Watcher {
+    pub fn new() -> notify::Result<Watcher> {
+        let (sender, events) = channel();
+        Ok(Watcher {
+            watcher: notify::recommended_watcher(sender)?,
+            events,
+            files: vec![],
+        })
+    }
+
+    /// Starts watching the file. Its directory is watched rather than the
+    /// file itself, since editors often save by replacing the file.
+    pub fn watch(&mut self, file: &Path) -> notify::Result<()> {
+        let file = file.canonicalize()?;
+        if self.files.contains(&file) {
+            return Ok(());
+        }
+        if let Some(dir) = file.parent() {
+            self.watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        }
+        self.files.push(file);
+        Ok(())
+    }
+
+    /// The watched files that changed since the last time, each once.
+    pub fn changed(&self) -> Vec<PathBuf> {
+        let mut changed = vec![];
+        for event in self.events.try_iter() {
+            let event = match event {
+                Ok(event) if event.kind.is_create() || event.kind.is_modify() => event,
+                _ => continue,
+            };
+            for path in event.paths {
+                if self.files.contains(&path) && !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+        changed
+    }
+}
@@ -0,0 +1,294 @@
+//! What the game calls cards, rules and circles, in the player's language.
+//! The window and the other frontends write the same words.
+
+use crate::{
+    circle::VennSet,
+    graphics::Point,
+    locale::{fill, text},
+    model::{Level, WIDTH},
+    region::Outline,
+    rule::{Rule, RuleExpr},
+    shapes::{
+        Kind, Value, VennColor, VennCount, VennFill, VennOrientation, VennPattern, VennShape,
+        VennSize, VennTarget,
+    },
+};
+
+// Like "small blue triangle", "small blue striped triangle" if it isn't
+// solid, "small blue rotated outlined triangle" if it is turned and only
+// outlined or "2 small blue triangles".
+pub(crate) fn describe(target: &VennTarget) -> String {
+    let pattern = match target.pattern {
+        VennPattern::Solid => "",
+        pattern => pattern_name(pattern),
+    };
+    let pattern = match target.orientation {
+        VennOrientation::Upright => pattern.to_string(),
+        orientation => format!("{} {}", pattern, orientation_name(orientation)),
+    };
+    let pattern = match target.fill {
+        VennFill::Filled => pattern,
+        fill => format!("{} {}", pattern, fill_name(fill)),
+    };
+    let description = fill(
+        "card",
+        &[
+            &size_name(target.size),
+            &color_name(target.color),
+            &pattern,
+            &shape_name(target.shape),
+        ],
+    );
+    // A solid card leaves a gap where its pattern would go.
+    let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+    match target.count {
+        VennCount(1) => description,
+        VennCount(n) => fill("cards", &[&n, &description]),
+    }
+}
+
+pub(crate) fn kind_name(kind: Kind) -> &'static str {
+    text(kind_key(kind))
+}
+
+pub(crate) fn kind_key(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Shape => "kind.shape",
+        Kind::Color => "kind.color",
+        Kind::Size => "kind.size",
+        Kind::Pattern => "kind.pattern",
+        Kind::Count => "kind.count",
+        Kind::Orientation => "kind.orientation",
+        Kind::Fill => "kind.fill",
+    }
+}
+
+pub(crate) fn value_name(value: Value) -> &'static str {
+    text(value_key(value))
+}
+
+pub(crate) fn value_key(value: Value) -> &'static str {
+    match value {
+        Value::Color(VennColor::Yellow) => "color.yellow",
+        Value::Color(VennColor::Blue) => "color.blue",
+        Value::Color(VennColor::Purple) => "color.purple",
+        Value::Size(VennSize::Small) => "size.small",
+        Value::Size(VennSize::Medium) => "size.medium",
+        Value::Size(VennSize::Large) => "size.large",
+        Value::Shape(VennShape::Circle) => "shape.circle",
+        Value::Shape(VennShape::Triangle) => "shape.triangle",
+        Value::Shape(VennShape::Square) => "shape.square",
+        Value::Shape(VennShape::Diamond) => "shape.diamond",
+        Value::Shape(VennShape::Pentagon) => "shape.pentagon",
+        Value::Shape(VennShape::Hexagon) => "shape.hexagon",
+        Value::Shape(VennShape::Star) => "shape.star",
+        Value::Pattern(VennPattern::Solid) => "pattern.solid",
+        Value::Pattern(VennPattern::Striped) => "pattern.striped",
+        Value::Pattern(VennPattern::Hollow) => "pattern.hollow",
+        Value::Pattern(VennPattern::Dotted) => "pattern.dotted",
+        Value::Orientation(VennOrientation::Upright) => "orientation.upright",
+        Value::Orientation(VennOrientation::Rotated) => "orientation.rotated",
+        Value::Fill(VennFill::Filled) => "fill.filled",
+        Value::Fill(VennFill::Outlined) => "fill.outlined",
+        Value::Count(VennCount(1)) => "count.one",
+        Value::Count(VennCount(2)) => "count.two",
+        Value::Count(VennCount(_)) => "count.three",
+    }
+}
+
+/// Puts a compound rule in words, as the guesses it picks out, like "blue
+/// triangles", "blue shapes that are not triangles" or "small shapes or
+/// stars".
+pub(crate) fn rule_text(rule: &RuleExpr) -> String {
+    if let RuleExpr::Or(a, b) = rule {
+        return fill("rule.or", &[&rule_text(a), &rule_text(b)]);
+    }
+    // Everything that has to hold at once goes in one phrase: the values
+    // that describe a shape in front of it, and the rest after it.
+    let mut terms = vec![];
+    conjuncts(rule, &mut terms);
+    let mut adjectives = vec![];
+    let mut noun = None;
+    let mut qualifiers = vec![];
+    for term in terms {
+        match term {
+            RuleExpr::Is(Value::Shape(shape)) if noun.is_none() => {
+                noun = Some(fill("rule.plural", &[&shape_name(*shape)]));
+            }
+            RuleExpr::Is(Value::Count(count)) => {
+                qualifiers.push(fill("rule.count", &[&count_name(*count)]));
+            }
+            RuleExpr::Is(value @ Value::Shape(_)) => {
+                qualifiers.push(fill("rule.are", &[&value_words(*value)]));
+            }
+            RuleExpr::Is(value) => adjectives.push(value_name(*value)),
+            RuleExpr::Not(negated) => {
+                let words = match negated.as_ref() {
+                    RuleExpr::Is(value) => value_words(*value),
+                    negated => rule_text(negated),
+                };
+                qualifiers.push(fill("rule.not", &[&words]));
+            }
+            other => qualifiers.push(fill("rule.are", &[&rule_text(other)])),
+        }
+    }
+    let noun = noun.unwrap_or_else(|| text("rule.shapes").to_string());
+    let phrase = fill("rule.phrase", &[&adjectives.join(" "), &noun]);
+    let phrase = qualifiers
+        .iter()
+        .enumerate()
+        .fold(phrase, |phrase, (i, qualifier)| {
+            if i == 0 {
+                format!("{} {}", phrase, qualifier)
+            } else {
+                fill("rule.and", &[&phrase, qualifier])
+            }
+        });
+    // No adjectives leave a gap.
+    phrase.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// The rules that all have to hold, with every and taken apart.
+fn conjuncts<'a>(rule: &'a RuleExpr, terms: &mut Vec<&'a RuleExpr>) {
+    match rule {
+        RuleExpr::And(a, b) => {
+            conjuncts(a, terms);
+            conjuncts(b, terms);
+        }
+        rule => terms.push(rule),
+    }
+}
+
+// A value as something a guess is, like "blue" or "triangles".
+fn value_words(value: Value) -> String {
+    match value {
+        Value::Shape(shape) => fill("rule.plural", &[&shape_name(shape)]),
+        Value::Count(count) => fill("rule.count", &[&count_name(count)]),
+        value => value_name(value).to_string(),
+    }
+}
+
+// Where the label of a circle goes and whether that is at the top of the
+// circle. Labels go inside the edge nearest the answer box, pushed outwards
+// so the ones of overlapping circles don't collide.
+pub(crate) fn label_at(circle: &VennSet) -> (Point, bool) {
+    let above = circle.answer.center.y < circle.center.y;
+    // How far up or down the edge goes, and where, the middle of a flat
+    // side.
+    let (x, end) = match circle.outline {
+        Outline::Circle if above => (circle.center.x, circle.center.y - circle.radius),
+        Outline::Circle => (circle.center.x, circle.center.y + circle.radius),
+        _ => {
+            let edge = circle.boundary().edge();
+            let far = |point: &Point| if above { -point.y } else { point.y };
+            let most = edge.iter().map(far).fold(f32::NEG_INFINITY, f32::max);
+            let ends: Vec<f32> = edge
+                .iter()
+                .filter(|point| far(point) > most - 0.5)
+                .map(|point| point.x)
+                .collect();
+            let x = ends.iter().sum::<f32>() / ends.len() as f32;
+            (x, if above { -most } else { most })
+        }
+    };
+    let y = if above { end + 15.0 } else { end - 15.0 };
+    let side = (circle.center.x - WIDTH / 2.0).signum();
+    (Point::new(x + side * circle.radius * 0.3, y), above)
+}
+
+// What is written in a circle: its label from the puzzle if it has one, by
+// the edge, and its rule once it is given away, or a question mark if there
+// is nothing to show yet.
+pub(crate) fn caption(circle: &VennSet, level: &Level, finished: bool, above: bool) -> String {
+    match (&circle.label, finished) {
+        (Some(name), true) if above => format!("{}\n{}", name, label(circle, level)),
+        (Some(name), true) => format!("{}\n{}", label(circle, level), name),
+        (Some(name), false) => name.clone(),
+        (None, true) => label(circle, level),
+        (None, false) => "?".to_string(),
+    }
+}
+
+// Describes a circle's rule in terms of its hidden card, like "Any of:
+// blue, triangle".
+pub(crate) fn label(circle: &VennSet, level: &Level) -> String {
+    if let Some(expr) = &circle.compound {
+        return fill("rule.compound", &[&rule_text(expr)]);
+    }
+    let card = &circle.answer.target;
+    let mut attributes = vec![];
+    if level.size {
+        attributes.push(size_name(card.size));
+    }
+    if level.color {
+        attributes.push(color_name(card.color));
+    }
+    if level.pattern {
+        attributes.push(pattern_name(card.pattern));
+    }
+    if level.count {
+        attributes.push(count_name(card.count));
+    }
+    if level.orientation {
+        attributes.push(orientation_name(card.orientation));
+    }
+    if level.fill {
+        attributes.push(fill_name(card.fill));
+    }
+    attributes.push(shape_name(card.shape));
+    let rule = match circle.rule {
+        Rule::All => text("rule.all").to_string(),
+        Rule::Any => text("rule.any").to_string(),
+        Rule::Not => text("rule.none").to_string(),
+        Rule::Exactly(n) => fill("rule.exactly", &[&n]),
+    };
+    fill("rule.label", &[&rule, &attributes.join(", ")])
+}
+
+pub(crate) fn color_name(color: VennColor) -> &'static str {
+    text(value_key(Value::Color(color)))
+}
+
+pub(crate) fn size_name(size: VennSize) -> &'static str {
+    text(value_key(Value::Size(size)))
+}
+
+pub(crate) fn shape_name(shape: VennShape) -> &'static str {
+    text(value_key(Value::Shape(shape)))
+}
+
+pub(crate) fn pattern_name(pattern: VennPattern) -> &'static str {
+    text(value_key(Value::Pattern(pattern)))
+}
+
+pub(crate) fn orientation_name(orientation: VennOrientation) -> &'static str {
+    text(value_key(Value::Orientation(orientation)))
+}
+
+pub(crate) fn fill_name(fill: VennFill) -> &'static str {
+    text(value_key(Value::Fill(fill)))
+}
+
+pub(crate) fn count_name(count: VennCount) -> &'static str {
+    text(value_key(Value::Count(count)))
+}
+
+/// Breaks the text into lines of at most the columns, between words where
+/// it can, keeping the lines it already has.
+pub(crate) fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > columns {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}